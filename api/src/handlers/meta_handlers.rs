@@ -188,6 +188,55 @@ pub async fn post_scheduler_cancel(
     (status, Json(resp))
 }
 
+pub async fn get_scheduler_checkpoint(
+    State(state): State<SharedState>,
+    Path(job_id): Path<String>,
+) -> (StatusCode, Json<ApiResp<Value>>) {
+    let Some(scheduler) = state.scheduler.as_ref() else {
+        return scheduler_unavailable();
+    };
+
+    let (status, resp) = scheduler.query_checkpoint(job_id).await;
+    (status, Json(resp))
+}
+
+pub async fn post_scheduler_checkpoint(
+    State(state): State<SharedState>,
+    Path(job_id): Path<String>,
+    Json(body): Json<Value>,
+) -> (StatusCode, Json<ApiResp<Value>>) {
+    let Some(scheduler) = state.scheduler.as_ref() else {
+        return scheduler_unavailable();
+    };
+
+    let (status, resp) = scheduler.set_checkpoint(job_id, body).await;
+    (status, Json(resp))
+}
+
+pub async fn post_scheduler_checkpoint_reset(
+    State(state): State<SharedState>,
+    Path(job_id): Path<String>,
+) -> (StatusCode, Json<ApiResp<Value>>) {
+    let Some(scheduler) = state.scheduler.as_ref() else {
+        return scheduler_unavailable();
+    };
+
+    let (status, resp) = scheduler.reset_checkpoint(job_id).await;
+    (status, Json(resp))
+}
+
+pub async fn post_scheduler_resume(
+    State(state): State<SharedState>,
+    Path(run_id): Path<String>,
+) -> (StatusCode, Json<ApiResp<Value>>) {
+    let Some(scheduler) = state.scheduler.as_ref() else {
+        return scheduler_unavailable();
+    };
+
+    let (status, resp) = scheduler.resume_run(run_id).await;
+    (status, Json(resp))
+}
+
 pub async fn list_tables_postgres(pool: &PgPool) -> anyhow::Result<Vec<String>> {
     let rows = sqlx::query("select tablename from pg_catalog.pg_tables where schemaname not in ('pg_catalog','information_schema')").fetch_all(pool).await?;
     let mut out = Vec::new();
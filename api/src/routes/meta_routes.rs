@@ -4,8 +4,9 @@ use axum::Router;
 use relus_common::resp::{DescribeQuery, GenMapQuery, TablesQuery};
 
 use crate::handlers::meta_handlers::{
-    get_scheduler_tasks, h_describe, h_gen_mapping, h_list_tables, h_sync, post_scheduler_cancel,
-    post_scheduler_task,
+    get_scheduler_checkpoint, get_scheduler_tasks, h_describe, h_gen_mapping, h_list_tables,
+    h_sync, post_scheduler_cancel, post_scheduler_checkpoint, post_scheduler_checkpoint_reset,
+    post_scheduler_resume, post_scheduler_task,
 };
 use crate::server::SharedState;
 
@@ -30,4 +31,16 @@ pub fn routes() -> Router<SharedState> {
             "/scheduler/tasks/:job_id/cancel",
             post(post_scheduler_cancel),
         )
+        .route(
+            "/scheduler/checkpoints/:job_id",
+            get(get_scheduler_checkpoint).post(post_scheduler_checkpoint),
+        )
+        .route(
+            "/scheduler/checkpoints/:job_id/reset",
+            post(post_scheduler_checkpoint_reset),
+        )
+        .route(
+            "/scheduler/runs/:run_id/resume",
+            post(post_scheduler_resume),
+        )
 }
@@ -11,6 +11,8 @@ use std::pin::Pin;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::signal;
+#[cfg(unix)]
+use tokio::signal::unix::{self, SignalKind};
 
 pub type ApiFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
 pub type ApiHandlerResult = (StatusCode, ApiResp<Value>);
@@ -23,6 +25,10 @@ pub trait SchedulerControl: Send + Sync {
     fn query_tasks(&self, job_id: Option<String>) -> ApiFuture<ApiHandlerResult>;
     fn submit_task(&self, path: String) -> ApiFuture<ApiHandlerResult>;
     fn cancel_task(&self, job_id: String) -> ApiFuture<ApiHandlerResult>;
+    fn query_checkpoint(&self, job_id: String) -> ApiFuture<ApiHandlerResult>;
+    fn reset_checkpoint(&self, job_id: String) -> ApiFuture<ApiHandlerResult>;
+    fn set_checkpoint(&self, job_id: String, position: Value) -> ApiFuture<ApiHandlerResult>;
+    fn resume_run(&self, run_id: String) -> ApiFuture<ApiHandlerResult>;
 }
 
 pub type SharedState = Arc<AppState>;
@@ -70,6 +76,7 @@ pub async fn start(host: String, port: u16, app_state: SharedState) -> anyhow::R
     Ok(())
 }
 
+#[allow(clippy::expect_used)]
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
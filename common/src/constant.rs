@@ -6,6 +6,9 @@ pub mod schema {
 
     /// Schema 版本号初始值
     pub const SCHEMA_VERSION_INITIAL: u64 = 1;
+
+    /// CDC 任务检测源表结构变更的默认间隔（秒）
+    pub const DEFAULT_SCHEMA_CHECK_INTERVAL_SECS: u64 = 60;
 }
 
 pub mod key {
@@ -33,3 +36,69 @@ pub mod pipeline {
     /// 默认 Writer 线程数
     pub const DEFAULT_WRITER_THREADS: usize = 4;
 }
+
+pub mod retry {
+    /// 可重试错误的默认最大重试次数
+    pub const DEFAULT_MAX_RETRIES: usize = 3;
+
+    /// 首次重试前的默认等待时间（毫秒），之后按指数增长
+    pub const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+
+    /// 单次重试等待时间上限（毫秒）
+    pub const DEFAULT_MAX_BACKOFF_MS: u64 = 30_000;
+}
+
+pub mod cdc {
+    /// CDC 行元数据：变更类型（insert / update / delete）
+    pub const OP_FIELD: &str = "_op";
+
+    /// CDC 行元数据：源表（`database.table`）
+    pub const TABLE_FIELD: &str = "_table";
+
+    /// CDC 行元数据：事件时间戳
+    pub const TIMESTAMP_FIELD: &str = "_timestamp";
+
+    /// Binlog CDC 行元数据：所属事务开始前的 binlog 文件名
+    pub const BINLOG_FILE_FIELD: &str = "_binlog_file";
+
+    /// Binlog CDC 行元数据：所属事务开始前的 binlog 位置
+    pub const BINLOG_POS_FIELD: &str = "_binlog_pos";
+
+    /// Binlog CDC 行元数据：所属事务开始前已执行的 GTID 集合
+    pub const BINLOG_GTID_FIELD: &str = "_gtid";
+
+    /// 只用于路由与 checkpoint、不会写入目标表的元数据字段
+    pub const METADATA_FIELDS: [&str; 6] = [
+        OP_FIELD,
+        TABLE_FIELD,
+        TIMESTAMP_FIELD,
+        BINLOG_FILE_FIELD,
+        BINLOG_POS_FIELD,
+        BINLOG_GTID_FIELD,
+    ];
+
+    /// Binlog CDC reader 的 source_type
+    pub const BINLOG_SOURCE_TYPE: &str = "mysql_binlog";
+}
+
+pub mod watermark {
+    /// 数据库源增量同步的水位列（如 `updated_at`、自增 id）
+    pub const COLUMN_KEY: &str = "watermark_column";
+
+    /// 水位回退窗口：时间列按秒、数值列按数值回退，覆盖迟到提交和同值行
+    pub const OVERLAP_KEY: &str = "watermark_overlap";
+}
+
+pub mod history {
+    /// soft_delete 模式默认的删除时间列
+    pub const DEFAULT_DELETED_AT_COLUMN: &str = "deleted_at";
+
+    /// scd2 模式默认的版本生效时间列
+    pub const DEFAULT_VALID_FROM_COLUMN: &str = "valid_from";
+
+    /// scd2 模式默认的版本失效时间列，当前版本为 NULL
+    pub const DEFAULT_VALID_TO_COLUMN: &str = "valid_to";
+
+    /// scd2 模式默认的当前版本标记列
+    pub const DEFAULT_IS_CURRENT_COLUMN: &str = "is_current";
+}
@@ -1,3 +1,4 @@
+use crate::constant::history;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -14,6 +15,12 @@ pub struct DataSourceConfig {
     #[serde(default)]
     pub writer_mode: Option<String>,
     pub config: Value,
+    /// split 阶段执行一次的 SQL（如清空目标表、禁用索引）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_sql: Vec<String>,
+    /// 所有 Pair 成功完成后执行一次的 SQL（如 ANALYZE、重建索引）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_sql: Vec<String>,
 }
 
 fn default_is_table_mode() -> bool {
@@ -40,9 +47,82 @@ pub struct DbConfig {
     pub max_connections: Option<u32>,
     pub acquire_timeout_secs: Option<u64>,
     pub use_transaction: Option<bool>,
+    /// 开启事务时每个事务提交的批次数，默认 1
+    pub commit_batches: Option<usize>,
+    /// insert 模式的写入方式
+    pub insert_method: Option<InsertMethod>,
+    /// 目标表不存在时按源表结构自动建表
+    pub create_table_if_missing: Option<bool>,
+    /// 先写入 staging 表，全部成功后原子替换目标表（全量重载）
+    pub use_staging_table: Option<bool>,
+    /// soft_delete / scd2 写入模式使用的列
+    #[serde(flatten)]
+    pub history: HistoryColumns,
     pub timezone: Option<String>,
 }
 
+/// soft_delete / scd2 写入模式使用的列，在目标连接中按 `*_column` 配置
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct HistoryColumns {
+    /// soft_delete：删除时写入当前时间的列
+    #[serde(rename = "deleted_at_column")]
+    pub deleted_at: String,
+    /// scd2：版本生效时间
+    #[serde(rename = "valid_from_column")]
+    pub valid_from: String,
+    /// scd2：版本失效时间，当前版本为 NULL
+    #[serde(rename = "valid_to_column")]
+    pub valid_to: String,
+    /// scd2：是否当前版本
+    #[serde(rename = "is_current_column")]
+    pub is_current: String,
+    /// scd2：值变化时产生新版本的列，为空时跟踪所有非 key 列
+    pub tracked_columns: Vec<String>,
+}
+
+impl Default for HistoryColumns {
+    fn default() -> Self {
+        Self {
+            deleted_at: history::DEFAULT_DELETED_AT_COLUMN.to_string(),
+            valid_from: history::DEFAULT_VALID_FROM_COLUMN.to_string(),
+            valid_to: history::DEFAULT_VALID_TO_COLUMN.to_string(),
+            is_current: history::DEFAULT_IS_CURRENT_COLUMN.to_string(),
+            tracked_columns: Vec::new(),
+        }
+    }
+}
+
+/// insert / upsert 写入的方式
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertMethod {
+    /// 多行 `INSERT ... VALUES`
+    #[default]
+    Values,
+    /// `COPY ... FROM STDIN`，只对 PostgreSQL 目标生效
+    Copy,
+    /// `LOAD DATA LOCAL INFILE`，只对 MySQL 目标生效
+    #[serde(rename = "load_data")]
+    LoadData,
+}
+
+impl std::str::FromStr for InsertMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "values" => Ok(InsertMethod::Values),
+            "copy" => Ok(InsertMethod::Copy),
+            "load_data" => Ok(InsertMethod::LoadData),
+            other => anyhow::bail!(
+                "未知的 insert_method: {}，可选 values / copy / load_data",
+                other
+            ),
+        }
+    }
+}
+
 fn default_port() -> u16 {
     3306
 }
@@ -61,6 +141,11 @@ impl Default for DbConfig {
             max_connections: Some(10),
             acquire_timeout_secs: Some(30),
             use_transaction: Some(false),
+            commit_batches: None,
+            insert_method: None,
+            create_table_if_missing: None,
+            use_staging_table: None,
+            history: HistoryColumns::default(),
             timezone: None,
         }
     }
@@ -180,6 +265,21 @@ impl DataSourceConfig {
             .map(|i| i as u32);
         let acquire_timeout_secs = conn.get("acquire_timeout_secs").and_then(|v| v.as_u64());
         let use_transaction = conn.get("use_transaction").and_then(|v| v.as_bool());
+        let commit_batches = conn
+            .get("commit_batches")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let insert_method = conn
+            .get("insert_method")
+            .and_then(|v| v.as_str())
+            .map(str::parse)
+            .transpose()?;
+        let create_table_if_missing = conn
+            .get("create_table_if_missing")
+            .and_then(|v| v.as_bool());
+        let use_staging_table = conn.get("use_staging_table").and_then(|v| v.as_bool());
+        let history = serde_json::from_value(conn.clone())
+            .map_err(|e| anyhow::anyhow!("soft_delete / scd2 列配置错误: {}", e))?;
         let timezone = conn
             .get("timezone")
             .or_else(|| conn.get("time_zone"))
@@ -198,10 +298,52 @@ impl DataSourceConfig {
             max_connections,
             acquire_timeout_secs,
             use_transaction,
+            commit_batches,
+            insert_method,
+            create_table_if_missing,
+            use_staging_table,
+            history,
             timezone,
         })
     }
 
+    /// 连接中配置的表名
+    pub fn table_name(&self) -> Option<String> {
+        self.extract_connection()
+            .ok()
+            .and_then(|conn| conn.get("table"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    }
+
+    /// 覆盖连接对象中的字段，`connections` 中的每个连接都会被覆盖
+    pub fn set_connection_field(&mut self, key: &str, value: Value) {
+        if let Some(arr) = self
+            .config
+            .get_mut("connections")
+            .and_then(|v| v.as_array_mut())
+        {
+            for conn in arr.iter_mut().filter_map(|c| c.as_object_mut()) {
+                conn.insert(key.to_string(), value.clone());
+            }
+        }
+        if let Some(conn) = self
+            .config
+            .get_mut("connection")
+            .and_then(|v| v.as_object_mut())
+        {
+            conn.insert(key.to_string(), value);
+        }
+    }
+
+    /// 覆盖 config 顶层字段
+    pub fn set_config_field(&mut self, key: &str, value: Value) {
+        if let Some(config) = self.config.as_object_mut() {
+            config.insert(key.to_string(), value);
+        }
+    }
+
     pub fn get_source_db_type(&self) -> Option<String> {
         if self.source_type != "database" {
             return None;
@@ -1,7 +1,11 @@
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet};
 use std::{fmt, str::FromStr};
 
+use crate::constant::schema::{DEFAULT_SCHEMA_CACHE_DIR, DEFAULT_SCHEMA_CHECK_INTERVAL_SECS};
+use crate::constant::{retry, watermark};
 use crate::data_source_config::DataSourceConfig;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -10,6 +14,7 @@ pub struct JobConfig {
     pub source: DataSourceConfig,
     #[serde(alias = "output")]
     pub target: DataSourceConfig,
+    #[serde(default)]
     pub column_mapping: BTreeMap<String, String>,
     pub column_types: Option<BTreeMap<String, String>>,
     pub sync_mode: Option<SyncMode>,
@@ -19,6 +24,322 @@ pub struct JobConfig {
     pub job_id: Option<String>,
     #[serde(default)]
     pub schedule: Option<ScheduleConfig>,
+    /// 多表任务：每个元素是一对源表 → 目标表，为空时即单表任务
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<TableJobConfig>,
+    /// 整库迁移：运行前发现 source 库中匹配的表并展开为 `tables`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migrate: Option<MigrateConfig>,
+    /// 源表结构发现与演进：运行时检测源表变更并同步到目标表
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_discovery: Option<SchemaDiscoveryConfig>,
+    /// 脏数据容忍：配置后写入失败的行逐行隔离，不再直接让任务失败
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_limit: Option<ErrorLimitConfig>,
+    /// 瞬时错误（死锁、锁等待超时、连接断开等）的重试策略，缺省使用系统配置 `retry`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryConfig>,
+    /// Writer 并发数，配置后不再与 Reader 分片 1:1 配对，行按 key 哈希分发到各 Writer；
+    /// 缺省使用系统配置 `pipeline.writer_threads`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub writer_threads: Option<usize>,
+    /// 全局限速（`record` 条/秒、`byte` 字节/秒），未配置的项使用系统配置 `pipeline.speed.*`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed: Option<SpeedConfig>,
+}
+
+/// 脏数据容忍配置
+///
+/// 转换失败或写入失败的行记为脏数据，写入 `dead_letter` 并计入 `records_failed`；
+/// 超过 `record` 条或占已读行数的比例超过 `percentage` 时任务失败。两者都不配置时不限制。
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ErrorLimitConfig {
+    /// 允许的脏数据条数
+    #[serde(default)]
+    pub record: Option<usize>,
+    /// 允许的脏数据比例（0 ~ 1）
+    #[serde(default)]
+    pub percentage: Option<f64>,
+    /// 脏数据输出位置，缺省只记录日志
+    #[serde(default)]
+    pub dead_letter: Option<DeadLetterConfig>,
+}
+
+/// 脏数据输出位置
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DeadLetterConfig {
+    /// 追加写入 JSON Lines 文件
+    File { path: String },
+    /// 写入目标库中的表，不存在时自动创建
+    Table { table: String },
+}
+
+/// 整库迁移配置
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MigrateConfig {
+    /// 要迁移的表名 glob（`*` / `?`），缺省为全部
+    #[serde(default = "default_include")]
+    pub include: Vec<String>,
+    /// 排除的表名 glob，优先于 include
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// 目标库缺少的表是否按源表结构自动创建
+    #[serde(default = "default_create_tables")]
+    pub create_tables: bool,
+}
+
+/// Schema 发现配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchemaDiscoveryConfig {
+    /// 是否启用自动发现
+    pub enabled: bool,
+    /// 缓存目录
+    pub cache_dir: Option<String>,
+    /// 是否检测 Schema 演进
+    pub check_evolution: bool,
+    /// 破坏性变更时是否中断任务
+    pub break_on_incompatible: bool,
+    /// CDC 任务运行中检测源表结构的间隔（秒）
+    pub check_interval_secs: u64,
+}
+
+/// 瞬时错误重试配置
+///
+/// 写入批次或读取分片遇到可重试的数据库错误时，按指数退避加随机抖动等待后重试，
+/// 超过 `max_retries` 次仍失败才让任务失败。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// 最大重试次数，0 表示不重试
+    pub max_retries: usize,
+    /// 首次重试前的等待时间（毫秒）
+    pub initial_backoff_ms: u64,
+    /// 单次等待时间上限（毫秒）
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: retry::DEFAULT_MAX_RETRIES,
+            initial_backoff_ms: retry::DEFAULT_INITIAL_BACKOFF_MS,
+            max_backoff_ms: retry::DEFAULT_MAX_BACKOFF_MS,
+        }
+    }
+}
+
+/// 全局限速配置，整个任务的所有 Pair 共享；两项都不配置时不限速
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct SpeedConfig {
+    /// 每秒记录数上限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<u64>,
+    /// 每秒字节数上限（按行数据大小估算）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte: Option<u64>,
+}
+
+impl SpeedConfig {
+    /// 逐项合并：本配置未设置的项使用 `fallback` 中的值
+    pub fn or(&self, fallback: &SpeedConfig) -> SpeedConfig {
+        SpeedConfig {
+            record: self.record.or(fallback.record),
+            byte: self.byte.or(fallback.byte),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.record.is_none() && self.byte.is_none()
+    }
+}
+
+impl Default for SchemaDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cache_dir: Some(DEFAULT_SCHEMA_CACHE_DIR.to_string()),
+            check_evolution: true,
+            break_on_incompatible: true,
+            check_interval_secs: DEFAULT_SCHEMA_CHECK_INTERVAL_SECS,
+        }
+    }
+}
+
+fn default_include() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+fn default_create_tables() -> bool {
+    true
+}
+
+impl MigrateConfig {
+    /// 表名是否需要迁移（大小写不敏感）
+    pub fn matches(&self, table: &str) -> bool {
+        let table = table.to_lowercase();
+        let hit = |pattern: &String| glob_match(&pattern.to_lowercase(), &table);
+        self.include.iter().any(hit) && !self.exclude.iter().any(hit)
+    }
+}
+
+/// 简单 glob：`*` 匹配任意串，`?` 匹配单个字符
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// 多表任务中的一张表
+///
+/// 未配置的字段沿用 job 顶层配置（`column_mapping`、`column_types`、
+/// 连接中的 `key_columns`、source 的 `split_pk`、target 的 `writer_mode`）。
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TableJobConfig {
+    pub source_table: String,
+    /// 目标表，缺省与源表同名
+    #[serde(default)]
+    pub target_table: Option<String>,
+    #[serde(default)]
+    pub column_mapping: BTreeMap<String, String>,
+    #[serde(default)]
+    pub column_types: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub key_columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub split_pk: Option<String>,
+    #[serde(default)]
+    pub writer_mode: Option<String>,
+}
+
+impl TableJobConfig {
+    pub fn target_table(&self) -> &str {
+        self.target_table.as_deref().unwrap_or(&self.source_table)
+    }
+
+    /// 结果与 checkpoint 中使用的表名
+    pub fn name(&self) -> String {
+        match self.target_table() {
+            target if target == self.source_table => self.source_table.clone(),
+            target => format!("{}->{}", self.source_table, target),
+        }
+    }
+}
+
+impl JobConfig {
+    /// 按水位列增量同步的数据库任务：每次调度运行一次，不常驻
+    pub fn is_watermark_incremental(&self) -> bool {
+        self.sync_mode == Some(SyncMode::Incremental)
+            && self.source.config_str(watermark::COLUMN_KEY).is_some()
+    }
+
+    /// 开启结构演进检测时返回 schema 配置
+    pub fn schema_evolution(&self) -> Option<&SchemaDiscoveryConfig> {
+        self.schema_discovery
+            .as_ref()
+            .filter(|c| c.enabled && c.check_evolution)
+    }
+
+    /// 是否为多表任务
+    pub fn is_multi_table(&self) -> bool {
+        !self.tables.is_empty()
+    }
+
+    /// 按表展开为单表任务配置，返回 (表名, 配置)
+    ///
+    /// 单表任务原样返回，表名取 source 连接中的 table（没有时用 source 名称）。
+    pub fn table_jobs(&self) -> Result<Vec<(String, JobConfig)>> {
+        if self.tables.is_empty() {
+            let name = self
+                .source
+                .table_name()
+                .unwrap_or_else(|| self.source.name.clone());
+            return Ok(vec![(name, self.clone())]);
+        }
+
+        let mut seen = BTreeSet::new();
+        self.tables
+            .iter()
+            .map(|table| {
+                let name = table.name();
+                if !seen.insert(name.clone()) {
+                    bail!("多表任务中表 {} 重复配置", name);
+                }
+                Ok((name, self.table_job(table)?))
+            })
+            .collect()
+    }
+
+    fn table_job(&self, table: &TableJobConfig) -> Result<JobConfig> {
+        let mut config = self.clone();
+        config.tables = Vec::new();
+
+        if !table.column_mapping.is_empty() {
+            config.column_mapping = table.column_mapping.clone();
+        }
+        if config.column_mapping.is_empty() {
+            bail!("表 {} 缺少 column_mapping", table.name());
+        }
+        if table.column_types.is_some() {
+            config.column_types = table.column_types.clone();
+        }
+
+        config
+            .source
+            .set_connection_field("table", JsonValue::from(table.source_table.as_str()));
+        if let Some(split_pk) = &table.split_pk {
+            config
+                .source
+                .set_config_field("split_pk", JsonValue::from(split_pk.as_str()));
+        }
+
+        config
+            .target
+            .set_connection_field("table", JsonValue::from(table.target_table()));
+        if let Some(key_columns) = &table.key_columns {
+            config
+                .target
+                .set_connection_field("key_columns", JsonValue::from(key_columns.clone()));
+        }
+        if table.writer_mode.is_some() {
+            config.target.writer_mode = table.writer_mode.clone();
+        }
+
+        Ok(config)
+    }
+
+    /// 常驻运行的 CDC 任务（binlog 增量或 Mix）
+    pub fn is_cdc(&self) -> bool {
+        match self.sync_mode {
+            Some(SyncMode::Mix) => true,
+            Some(SyncMode::Incremental) => !self.is_watermark_incremental(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
@@ -51,6 +372,11 @@ pub enum WriteMode {
     Upsert,
     Update,
     Delete,
+    /// 删除改为 `UPDATE ... SET deleted_at = 当前时间`，其余按 upsert 写入
+    #[serde(rename = "soft_delete")]
+    SoftDelete,
+    /// SCD Type 2：跟踪列变化时关闭当前版本并插入新版本
+    Scd2,
 }
 
 impl WriteMode {
@@ -60,6 +386,8 @@ impl WriteMode {
             WriteMode::Upsert => "upsert",
             WriteMode::Update => "update",
             WriteMode::Delete => "delete",
+            WriteMode::SoftDelete => "soft_delete",
+            WriteMode::Scd2 => "scd2",
         }
     }
 
@@ -82,6 +410,8 @@ impl FromStr for WriteMode {
             "upsert" => Ok(WriteMode::Upsert),
             "update" => Ok(WriteMode::Update),
             "delete" => Ok(WriteMode::Delete),
+            "soft_delete" => Ok(WriteMode::SoftDelete),
+            "scd2" => Ok(WriteMode::Scd2),
             _ => Err(()),
         }
     }
@@ -155,3 +485,155 @@ pub struct MappingConfig {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn multi_table_job() -> JobConfig {
+        serde_json::from_value(json!({
+            "source": {
+                "name": "src",
+                "type": "database",
+                "config": {
+                    "split_pk": "id",
+                    "connections": [{"type": "mysql", "database": "shop"}]
+                }
+            },
+            "target": {
+                "name": "dst",
+                "type": "database",
+                "writer_mode": "insert",
+                "config": {"connection": {"type": "postgres", "database": "dw"}}
+            },
+            "column_mapping": {"id": "id"},
+            "tables": [
+                {"source_table": "users"},
+                {
+                    "source_table": "orders",
+                    "target_table": "ods_orders",
+                    "column_mapping": {"order_id": "id", "amount": "amount"},
+                    "key_columns": ["id"],
+                    "split_pk": "order_id",
+                    "writer_mode": "upsert"
+                }
+            ]
+        }))
+        .expect("job config")
+    }
+
+    #[test]
+    fn table_jobs_expand_each_table_with_overrides() -> Result<()> {
+        let jobs = multi_table_job().table_jobs()?;
+        assert_eq!(jobs.len(), 2);
+
+        let (name, users) = &jobs[0];
+        assert_eq!(name, "users");
+        assert!(users.tables.is_empty());
+        assert_eq!(users.source.parse_database_config()?.table, "users");
+        assert_eq!(users.target.parse_database_config()?.table, "users");
+        assert_eq!(users.source.config_str("split_pk").as_deref(), Some("id"));
+        assert_eq!(users.target.writer_mode.as_deref(), Some("insert"));
+        assert_eq!(users.column_mapping.len(), 1);
+
+        let (name, orders) = &jobs[1];
+        assert_eq!(name, "orders->ods_orders");
+        assert_eq!(orders.target.parse_database_config()?.table, "ods_orders");
+        assert_eq!(
+            orders.target.parse_database_config()?.key_columns,
+            Some(vec!["id".to_string()])
+        );
+        assert_eq!(
+            orders.source.config_str("split_pk").as_deref(),
+            Some("order_id")
+        );
+        assert_eq!(WriteMode::from_config(orders), WriteMode::Upsert);
+        assert_eq!(orders.column_mapping.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn hook_sql_is_optional_and_kept_per_table() -> Result<()> {
+        let job = multi_table_job();
+        assert!(job.source.pre_sql.is_empty() && job.target.post_sql.is_empty());
+
+        let mut job = job;
+        job.target.pre_sql = vec!["SET session_replication_role = replica".to_string()];
+        job.target.post_sql = vec!["ANALYZE".to_string()];
+        for (_, table_job) in job.table_jobs()? {
+            assert_eq!(table_job.target.pre_sql, job.target.pre_sql);
+            assert_eq!(table_job.target.post_sql, job.target.post_sql);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn table_jobs_reject_duplicates_and_missing_mapping() {
+        let mut job = multi_table_job();
+        job.tables.push(job.tables[0].clone());
+        assert!(job.table_jobs().is_err());
+
+        let mut job = multi_table_job();
+        job.column_mapping.clear();
+        assert!(job.table_jobs().is_err());
+    }
+
+    #[test]
+    fn migrate_filters_tables_by_glob() {
+        let migrate: MigrateConfig =
+            serde_json::from_value(json!({"exclude": ["tmp_*", "*_bak"]})).expect("migrate");
+        assert!(migrate.create_tables);
+        assert!(migrate.matches("orders"));
+        assert!(migrate.matches("Order_Items"));
+        assert!(!migrate.matches("tmp_orders"));
+        assert!(!migrate.matches("orders_bak"));
+
+        let migrate = MigrateConfig {
+            include: vec!["order?".to_string(), "user*".to_string()],
+            exclude: Vec::new(),
+            create_tables: false,
+        };
+        assert!(migrate.matches("orders"));
+        assert!(migrate.matches("users"));
+        assert!(migrate.matches("user"));
+        assert!(!migrate.matches("order"));
+        assert!(!migrate.matches("order_items"));
+    }
+
+    #[test]
+    fn schema_evolution_requires_enabled_discovery() {
+        let mut job = multi_table_job();
+        assert!(job.schema_evolution().is_none());
+
+        job.schema_discovery = Some(
+            serde_json::from_value(json!({"enabled": true, "break_on_incompatible": false}))
+                .expect("schema discovery"),
+        );
+        let config = job.schema_evolution().expect("evolution enabled");
+        assert!(!config.break_on_incompatible);
+        assert_eq!(
+            config.check_interval_secs,
+            crate::constant::schema::DEFAULT_SCHEMA_CHECK_INTERVAL_SECS
+        );
+
+        if let Some(config) = job.schema_discovery.as_mut() {
+            config.check_evolution = false;
+        }
+        assert!(job.schema_evolution().is_none());
+    }
+
+    #[test]
+    fn single_table_job_is_returned_as_is() -> Result<()> {
+        let mut job = multi_table_job();
+        job.tables.clear();
+        job.source
+            .set_connection_field("table", JsonValue::from("accounts"));
+
+        let jobs = job.table_jobs()?;
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].0, "accounts");
+        assert!(!job.is_multi_table());
+        Ok(())
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! PipelineMessage 和 DbBatch 是 Reader → Writer 的数据通道类型，
 //! 属于公共接口层，被 core、writer 共同使用。
+//! SpeedLimiter 是 Reader / Writer 两侧共享的全局限速器。
 
 mod message;
+mod speed;
 
 pub use message::*;
+pub use speed::*;
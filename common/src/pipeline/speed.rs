@@ -0,0 +1,358 @@
+//! 全局限速
+//!
+//! 记录数与字节数各一个令牌桶，整个任务的所有 Pair 共享同一个 [`SpeedLimiter`]。
+//! 令牌允许透支：取走超过余量的令牌后按欠量计算等待时间，大批次不会永久阻塞，
+//! 并发的调用方依次排在欠量之后，合计速率不超过上限。
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::job_config::SpeedConfig;
+use crate::types::{MappingRow, UnifiedValue};
+
+/// 速率统计窗口
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// 限速执行结果
+///
+/// 读取侧与写入侧各自按同一上限取令牌，分别记录实际平均速率。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpeedStats {
+    #[serde(default)]
+    pub record_limit: Option<u64>,
+    #[serde(default)]
+    pub byte_limit: Option<u64>,
+    /// 写入侧实际平均速率（条/秒）
+    pub record_rate: f64,
+    /// 写入侧实际平均速率（字节/秒）
+    pub byte_rate: f64,
+    /// 读取侧实际平均速率（条/秒）
+    #[serde(default)]
+    pub read_record_rate: f64,
+    /// 读取侧实际平均速率（字节/秒）
+    #[serde(default)]
+    pub read_byte_rate: f64,
+}
+
+impl SpeedStats {
+    /// 合并读取侧限速器的平均速率
+    pub fn with_read(mut self, read: Option<&SpeedLimiter>) -> Self {
+        if let Some(read) = read {
+            let (record_rate, byte_rate) = read.average_rate();
+            self.read_record_rate = record_rate;
+            self.read_byte_rate = byte_rate;
+        }
+        self
+    }
+}
+
+/// 全局限速器，clone 后共享同一组令牌桶
+#[derive(Clone)]
+pub struct SpeedLimiter {
+    inner: Arc<LimiterInner>,
+}
+
+impl std::fmt::Debug for SpeedLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpeedLimiter")
+            .field("config", &self.inner.config)
+            .finish()
+    }
+}
+
+struct LimiterInner {
+    config: SpeedConfig,
+    record: Option<Mutex<TokenBucket>>,
+    byte: Option<Mutex<TokenBucket>>,
+    meter: Mutex<RateMeter>,
+}
+
+impl SpeedLimiter {
+    /// 两项都未配置时返回 None
+    pub fn new(config: &SpeedConfig) -> Option<Self> {
+        if config.is_unlimited() {
+            return None;
+        }
+        let now = Instant::now();
+        Some(Self {
+            inner: Arc::new(LimiterInner {
+                config: config.clone(),
+                record: config
+                    .record
+                    .map(|rate| Mutex::new(TokenBucket::new(rate, now))),
+                byte: config
+                    .byte
+                    .map(|rate| Mutex::new(TokenBucket::new(rate, now))),
+                meter: Mutex::new(RateMeter::new(now)),
+            }),
+        })
+    }
+
+    /// 取走令牌并返回需要等待的时间，调用方可先做别的事（如提交事务）再等待
+    pub fn reserve(&self, records: usize, bytes: usize) -> Duration {
+        let now = Instant::now();
+        self.inner
+            .meter
+            .lock()
+            .add(records as u64, bytes as u64, now);
+        let take = |bucket: &Option<Mutex<TokenBucket>>, amount: usize| {
+            bucket
+                .as_ref()
+                .map_or(Duration::ZERO, |b| b.lock().take(amount as f64, now))
+        };
+        take(&self.inner.record, records).max(take(&self.inner.byte, bytes))
+    }
+
+    /// 取走令牌，超速时等待
+    pub async fn acquire(&self, records: usize, bytes: usize) {
+        let wait = self.reserve(records, bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// 最近一个统计窗口的速率（条/秒，字节/秒）
+    pub fn current_rate(&self) -> (f64, f64) {
+        self.inner.meter.lock().current(Instant::now())
+    }
+
+    /// 进度条上显示的当前速率
+    pub fn rate_message(&self) -> String {
+        let (records, bytes) = self.current_rate();
+        format!("{:.0} rec/s, {}/s", records, format_bytes(bytes))
+    }
+
+    /// 启动以来的平均速率（条/秒，字节/秒）
+    pub fn average_rate(&self) -> (f64, f64) {
+        self.inner.meter.lock().average(Instant::now())
+    }
+
+    pub fn stats(&self) -> SpeedStats {
+        let (record_rate, byte_rate) = self.average_rate();
+        SpeedStats {
+            record_limit: self.inner.config.record,
+            byte_limit: self.inner.config.byte,
+            record_rate,
+            byte_rate,
+            ..Default::default()
+        }
+    }
+}
+
+/// 令牌桶，容量为一秒的令牌
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        let rate = rate.max(1) as f64;
+        Self {
+            rate,
+            tokens: rate,
+            updated: now,
+        }
+    }
+
+    /// 取走 `amount` 个令牌（允许透支），返回还清欠量需要等待的时间
+    fn take(&mut self, amount: f64, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.updated = now;
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate) - amount;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// 累计与窗口速率
+struct RateMeter {
+    start: Instant,
+    records: u64,
+    bytes: u64,
+    window_start: Instant,
+    window_records: u64,
+    window_bytes: u64,
+    /// 上一个完整窗口的速率
+    last: Option<(f64, f64)>,
+}
+
+impl RateMeter {
+    fn new(now: Instant) -> Self {
+        Self {
+            start: now,
+            records: 0,
+            bytes: 0,
+            window_start: now,
+            window_records: 0,
+            window_bytes: 0,
+            last: None,
+        }
+    }
+
+    fn add(&mut self, records: u64, bytes: u64, now: Instant) {
+        self.records += records;
+        self.bytes += bytes;
+        self.window_records += records;
+        self.window_bytes += bytes;
+        self.roll(now);
+    }
+
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= RATE_WINDOW {
+            let secs = elapsed.as_secs_f64();
+            self.last = Some((
+                self.window_records as f64 / secs,
+                self.window_bytes as f64 / secs,
+            ));
+            self.window_start = now;
+            self.window_records = 0;
+            self.window_bytes = 0;
+        }
+    }
+
+    fn current(&mut self, now: Instant) -> (f64, f64) {
+        self.roll(now);
+        self.last.unwrap_or_else(|| self.average(now))
+    }
+
+    fn average(&self, now: Instant) -> (f64, f64) {
+        let secs = now.saturating_duration_since(self.start).as_secs_f64();
+        if secs <= 0.0 {
+            return (0.0, 0.0);
+        }
+        (self.records as f64 / secs, self.bytes as f64 / secs)
+    }
+}
+
+/// 估算 JSON 值的字节数（字符串按长度，数字与布尔按定长）
+fn json_bytes(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Null => 0,
+        serde_json::Value::Bool(_) => 1,
+        serde_json::Value::Number(_) => 8,
+        serde_json::Value::String(s) => s.len(),
+        serde_json::Value::Array(items) => items.iter().map(json_bytes).sum(),
+        serde_json::Value::Object(map) => map.values().map(json_bytes).sum(),
+    }
+}
+
+/// 估算一行映射后数据的字节数，读取侧与写入侧限速都按此口径计算
+pub fn row_bytes(row: &MappingRow) -> usize {
+    row.fields.values().map(|f| value_bytes(&f.value)).sum()
+}
+
+fn value_bytes(value: &UnifiedValue) -> usize {
+    match value {
+        UnifiedValue::Null
+        | UnifiedValue::OptI64(None)
+        | UnifiedValue::OptF64(None)
+        | UnifiedValue::OptBool(None)
+        | UnifiedValue::OptDecimal(None)
+        | UnifiedValue::OptDateTime(None) => 0,
+        UnifiedValue::Bool(_) | UnifiedValue::OptBool(Some(_)) => 1,
+        UnifiedValue::Date(_) => 4,
+        UnifiedValue::Int(_)
+        | UnifiedValue::OptI64(Some(_))
+        | UnifiedValue::Float(_)
+        | UnifiedValue::OptF64(Some(_))
+        | UnifiedValue::Time(_)
+        | UnifiedValue::DateTime(_)
+        | UnifiedValue::OptDateTime(Some(_)) => 8,
+        UnifiedValue::Decimal(_) | UnifiedValue::OptDecimal(Some(_)) => 16,
+        UnifiedValue::String(s) => s.len(),
+        UnifiedValue::Bytes(b) => b.len(),
+        UnifiedValue::Json(v) => json_bytes(v),
+        UnifiedValue::Array(items) => items.iter().map(value_bytes).sum(),
+    }
+}
+
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_waits_for_debt_at_configured_rate() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(100, now);
+        // 初始容量一秒令牌，之后透支 50 个需要等待 0.5 秒
+        assert_eq!(bucket.take(100.0, now), Duration::ZERO);
+        assert_eq!(bucket.take(50.0, now), Duration::from_millis(500));
+        // 再过一秒补充 100 个，还清欠量后余 50
+        let later = now + Duration::from_secs(1);
+        assert_eq!(bucket.take(50.0, later), Duration::ZERO);
+        assert_eq!(bucket.take(50.0, later), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn limiter_is_disabled_without_limits_and_merges_system_config() {
+        assert!(SpeedLimiter::new(&SpeedConfig::default()).is_none());
+        let job = SpeedConfig {
+            record: Some(1000),
+            byte: None,
+        };
+        let system = SpeedConfig {
+            record: Some(10),
+            byte: Some(1 << 20),
+        };
+        assert_eq!(
+            job.or(&system),
+            SpeedConfig {
+                record: Some(1000),
+                byte: Some(1 << 20),
+            }
+        );
+    }
+
+    #[test]
+    fn stats_report_read_and_write_rates() {
+        let config = SpeedConfig {
+            record: Some(1000),
+            byte: None,
+        };
+        let (Some(read), Some(write)) = (SpeedLimiter::new(&config), SpeedLimiter::new(&config))
+        else {
+            panic!("配置了限速时应创建限速器");
+        };
+        read.reserve(10, 100);
+        std::thread::sleep(Duration::from_millis(10));
+
+        let stats = write.stats().with_read(Some(&read));
+        assert_eq!(stats.record_limit, Some(1000));
+        assert_eq!(stats.record_rate, 0.0);
+        assert!(stats.read_record_rate > 0.0);
+        assert!(stats.read_byte_rate > 0.0);
+        assert_eq!(write.stats().with_read(None).read_record_rate, 0.0);
+    }
+
+    #[test]
+    fn estimates_row_sizes() {
+        let json = serde_json::json!({"id": 1, "name": "alice", "tags": ["a", "bc"], "x": null});
+        assert_eq!(json_bytes(&json), 8 + 5 + 3);
+
+        let mut row = MappingRow::simple();
+        row.insert_simple("id", UnifiedValue::Int(1), "int");
+        row.insert_simple("name", UnifiedValue::String("alice".into()), "string");
+        row.insert_simple("deleted_at", UnifiedValue::OptDateTime(None), "datetime");
+        assert_eq!(row_bytes(&row), 13);
+        assert_eq!(format_bytes(1536.0), "1.5KB");
+    }
+}
@@ -3,6 +3,7 @@
 //! 支持 UnifiedValue 的类型转换
 
 use anyhow::Result;
+use base64::Engine;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -162,6 +163,73 @@ impl TypeConverter for TimestampConverter {
     }
 }
 
+/// 日期转换器
+pub struct DateConverter;
+
+impl TypeConverter for DateConverter {
+    fn convert(&self, v: &JsonValue) -> Result<UnifiedValue> {
+        use chrono::NaiveDate;
+
+        match v {
+            JsonValue::Null => Ok(UnifiedValue::Null),
+            JsonValue::String(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map(UnifiedValue::Date)
+                .map_err(|_| anyhow::anyhow!("无法解析日期: {}", s)),
+            _ => anyhow::bail!("无法转换为 date: {}", v),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "date"
+    }
+}
+
+/// 时间转换器
+pub struct TimeConverter;
+
+impl TypeConverter for TimeConverter {
+    fn convert(&self, v: &JsonValue) -> Result<UnifiedValue> {
+        use chrono::NaiveTime;
+
+        match v {
+            JsonValue::Null => Ok(UnifiedValue::Null),
+            JsonValue::String(s) => NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+                .map(UnifiedValue::Time)
+                .map_err(|_| anyhow::anyhow!("无法解析时间: {}", s)),
+            _ => anyhow::bail!("无法转换为 time: {}", v),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "time"
+    }
+}
+
+/// 二进制在 JSON 记录中的表示：标准 base64 字符串
+pub fn bytes_to_json(bytes: &[u8]) -> JsonValue {
+    JsonValue::String(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// 二进制转换器（base64 字符串 → Bytes）
+pub struct BytesConverter;
+
+impl TypeConverter for BytesConverter {
+    fn convert(&self, v: &JsonValue) -> Result<UnifiedValue> {
+        match v {
+            JsonValue::Null => Ok(UnifiedValue::Null),
+            JsonValue::String(s) => base64::engine::general_purpose::STANDARD
+                .decode(s)
+                .map(UnifiedValue::Bytes)
+                .map_err(|_| anyhow::anyhow!("无法解析 base64 二进制: {}", s)),
+            _ => anyhow::bail!("无法转换为 bytes: {}", v),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "bytes"
+    }
+}
+
 /// JSON 转换器
 pub struct JsonConverter;
 
@@ -180,10 +248,8 @@ pub struct TextConverter;
 
 impl TypeConverter for TextConverter {
     fn convert(&self, v: &JsonValue) -> Result<UnifiedValue> {
-        if v.is_null() {
-            return Ok(UnifiedValue::String(String::new()));
-        }
         match v {
+            JsonValue::Null => Ok(UnifiedValue::Null),
             JsonValue::String(s) => Ok(UnifiedValue::String(s.clone())),
             JsonValue::Number(n) => Ok(UnifiedValue::String(n.to_string())),
             JsonValue::Bool(b) => Ok(UnifiedValue::String(b.to_string())),
@@ -217,6 +283,9 @@ impl TypeConverterRegistry {
         converters.insert(TypeKind::Bool, Arc::new(BoolConverter));
         converters.insert(TypeKind::Decimal, Arc::new(DecimalConverter));
         converters.insert(TypeKind::Timestamp, Arc::new(TimestampConverter));
+        converters.insert(TypeKind::Date, Arc::new(DateConverter));
+        converters.insert(TypeKind::Time, Arc::new(TimeConverter));
+        converters.insert(TypeKind::Bytes, Arc::new(BytesConverter));
         converters.insert(TypeKind::Json, Arc::new(JsonConverter));
 
         Self {
@@ -290,6 +359,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_text_converter_keeps_null() -> Result<()> {
+        let result = TextConverter.convert(&json!(null))?;
+        assert!(result.is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn test_date_time_bytes_converters() -> Result<()> {
+        let registry = TypeConverterRegistry::new();
+
+        let date = registry.convert(&json!("2024-02-29"), Some("date"))?;
+        assert_eq!(
+            date,
+            UnifiedValue::Date(chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap_or_default())
+        );
+
+        let time = registry.convert(&json!("23:59:58.123456"), Some("time"))?;
+        assert_eq!(
+            time,
+            UnifiedValue::Time(
+                chrono::NaiveTime::from_hms_micro_opt(23, 59, 58, 123_456).unwrap_or_default()
+            )
+        );
+
+        let bytes = registry.convert(&bytes_to_json(&[0, 159, 255]), Some("bytes"))?;
+        assert_eq!(bytes, UnifiedValue::Bytes(vec![0, 159, 255]));
+        Ok(())
+    }
+
     #[test]
     fn test_registry() -> Result<()> {
         let registry = TypeConverterRegistry::new();
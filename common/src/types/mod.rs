@@ -24,7 +24,7 @@ mod unified_value;
 
 // === 核心类型导出 ===
 
-pub use converter::{TypeConverter, TypeConverterRegistry};
+pub use converter::{bytes_to_json, TypeConverter, TypeConverterRegistry};
 pub use mapping_field::MappingField;
 pub use mapping_row::{BatchMetadata, MappingBatch, MappingRow};
 pub use mapping_schema::MappingSchema;
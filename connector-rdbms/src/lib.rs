@@ -1,6 +1,9 @@
 pub mod connector;
 pub mod metadata;
+pub mod mysql_load;
+pub mod pg_copy;
 pub mod pool;
+pub mod retry;
 pub mod schema;
 pub mod sql_builder;
 pub mod util;
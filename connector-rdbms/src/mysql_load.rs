@@ -0,0 +1,472 @@
+//! MySQL `LOAD DATA LOCAL INFILE` 写入
+//!
+//! sqlx 的 MySQL 驱动不声明 CLIENT_LOCAL_FILES，也不处理 LOCAL INFILE 请求包，
+//! 这里通过 mysql_async 连接写入。写入任务的其它语句（update / delete、事务、保存点）
+//! 也走同一条连接，LOAD DATA 与它们处在同一事务中。
+//! 批次在内存中编码为 TSV，作为 infile 数据分块发送，不落盘。
+//!
+//! 编码规则：列之间用 tab 分隔，行以换行结束，NULL 写作 `\N`，
+//! 值中的反斜杠、tab、换行、回车、NUL 按 LOAD DATA 规则转义。
+//! 文件按 `CHARACTER SET binary` 读取，字符串按 UTF-8 原样写入，二进制值不做转换。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use futures::stream::{self, StreamExt};
+use mysql_async::prelude::Queryable;
+use mysql_async::{
+    Conn, InfileData, Opts, OptsBuilder, Params, Pool, PoolConstraints, PoolOpts, Row, Value,
+};
+use relus_common::data_source_config::DbConfig;
+use relus_common::job_config::WriteMode;
+use relus_common::types::UnifiedValue;
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use tracing::warn;
+
+use crate::pool::{current_system_timezone, ColumnValue, DatabaseExecutor};
+use crate::sql_builder::WriteRows;
+
+/// infile 数据每次发送的块大小
+const INFILE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 生成 `LOAD DATA LOCAL INFILE` 语句，表名与 INSERT 一致不加引号
+///
+/// insert 使用 `IGNORE`（LOCAL 模式下服务端无法中途停止传输，重复键本来就只产生警告），
+/// upsert 使用 `REPLACE`：冲突行先删除再插入，未出现在列清单中的列会回到默认值。
+pub fn load_data_statement(table: &str, columns: &[String], mode: WriteMode) -> Result<String> {
+    let duplicate = match mode {
+        WriteMode::Insert => "IGNORE",
+        WriteMode::Upsert => "REPLACE",
+        other => bail!("LOAD DATA 不支持 {} 写入模式", other.as_str()),
+    };
+    let mut sql = format!(
+        "LOAD DATA LOCAL INFILE 'relus_batch' {} INTO TABLE {} CHARACTER SET binary \
+         FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\' LINES TERMINATED BY '\\n' (",
+        duplicate, table
+    );
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            sql.push_str(", ");
+        }
+        sql.push('`');
+        sql.push_str(&column.replace('`', "``"));
+        sql.push('`');
+    }
+    sql.push(')');
+    Ok(sql)
+}
+
+/// 将行编码为 LOAD DATA 默认的 TSV 格式
+pub fn encode_load_data(rows: &[Vec<UnifiedValue>]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for row in rows {
+        for (i, value) in row.iter().enumerate() {
+            if i > 0 {
+                out.push(b'\t');
+            }
+            push_value(&mut out, value)?;
+        }
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+/// 通过 LOAD DATA 写入一批行，返回发送的行数
+///
+/// 被 IGNORE 跳过的重复键、被截断的值只产生警告，不算写入失败。
+pub async fn execute_load_data(
+    executor: &dyn DatabaseExecutor,
+    table: &str,
+    mode: WriteMode,
+    write_rows: &WriteRows,
+) -> Result<usize> {
+    if write_rows.values.is_empty() {
+        return Ok(0);
+    }
+    let statement = load_data_statement(table, &write_rows.columns, mode)?;
+    executor
+        .load_data(&statement, &encode_load_data(&write_rows.values)?)
+        .await?;
+    Ok(write_rows.values.len())
+}
+
+/// 目标 MySQL 的 LOCAL INFILE 连接池，连接按需建立
+#[derive(Debug, Clone)]
+pub struct LocalInfileLoader {
+    pool: Pool,
+}
+
+impl LocalInfileLoader {
+    /// 按目标连接配置创建连接池，会话时区与 sqlx 连接池一致
+    ///
+    /// 时区放在 setup 语句中：连接回到连接池时会被重置，重置后只重新执行 setup 语句。
+    pub fn new(db_config: &DbConfig) -> Result<Self> {
+        let opts = Opts::from_url(&db_config.to_url()).context("LOAD DATA 连接串无效")?;
+        let timezone = db_config
+            .timezone
+            .clone()
+            .unwrap_or_else(current_system_timezone);
+        let max_conns = db_config.max_connections.unwrap_or(20).max(1) as usize;
+        let constraints = PoolConstraints::new(0, max_conns).context("LOAD DATA 连接池大小无效")?;
+        let opts = OptsBuilder::from_opts(opts)
+            .setup(vec![format!(
+                "SET time_zone = '{}'",
+                timezone.replace('\'', "''")
+            )])
+            .pool_opts(PoolOpts::default().with_constraints(constraints));
+        Ok(Self {
+            pool: Pool::new(opts),
+        })
+    }
+
+    /// 创建写入任务使用的执行器，每个执行器独占一条连接
+    pub fn executor(&self) -> Box<dyn DatabaseExecutor> {
+        Box::new(LocalInfileExecutor {
+            pool: self.pool.clone(),
+            conn: Mutex::new(None),
+            in_tx: AtomicBool::new(false),
+        })
+    }
+}
+
+/// 在同一条 mysql_async 连接上执行写入任务的全部语句
+///
+/// 连接在第一次使用时获取，executor drop 后回到连接池并被重置，未提交的事务随之回滚。
+pub struct LocalInfileExecutor {
+    pool: Pool,
+    conn: Mutex<Option<Conn>>,
+    in_tx: AtomicBool,
+}
+
+impl LocalInfileExecutor {
+    async fn conn(&self) -> Result<MappedMutexGuard<'_, Conn>> {
+        let mut conn = self.conn.lock().await;
+        if conn.is_none() {
+            *conn = Some(self.pool.get_conn().await?);
+        }
+        MutexGuard::try_map(conn, Option::as_mut).map_err(|_| anyhow!("LOAD DATA 连接不可用"))
+    }
+
+    async fn fetch_row(&self, sql: &str) -> Result<Option<Row>> {
+        Ok(self.conn().await?.exec_first(sql, Params::Empty).await?)
+    }
+
+    /// 在事务连接上以文本协议执行（保存点语句不能预编译）
+    async fn execute_in_tx(&self, sql: &str) -> Result<()> {
+        if !self.in_tx.load(Ordering::SeqCst) {
+            bail!("保存点只能在事务中使用");
+        }
+        self.conn().await?.query_drop(sql).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DatabaseExecutor for LocalInfileExecutor {
+    async fn fetch_string_pair(&self, sql: &str) -> Result<(Option<String>, Option<String>)> {
+        let row = self.fetch_row(sql).await?.context("查询没有返回结果")?;
+        let text = |idx: usize| match column_value(row.as_ref(idx)) {
+            Some(ColumnValue::Text(s)) => Some(s),
+            _ => None,
+        };
+        Ok((text(0), text(1)))
+    }
+
+    async fn fetch_optional_string(&self, sql: &str) -> Result<Option<String>> {
+        let row = self.fetch_row(sql).await?;
+        Ok(row.and_then(|row| match column_value(row.as_ref(0)) {
+            Some(ColumnValue::Text(s)) => Some(s),
+            _ => None,
+        }))
+    }
+
+    async fn fetch_column_pair(
+        &self,
+        sql: &str,
+    ) -> Result<(Option<ColumnValue>, Option<ColumnValue>)> {
+        let row = self.fetch_row(sql).await?.context("查询没有返回结果")?;
+        Ok((column_value(row.as_ref(0)), column_value(row.as_ref(1))))
+    }
+
+    async fn execute(&self, sql: &str) -> Result<u64> {
+        let mut conn = self.conn().await?;
+        conn.query_drop(sql).await?;
+        Ok(conn.affected_rows())
+    }
+
+    async fn execute_with_params(&self, sql: &str, params: &[UnifiedValue]) -> Result<u64> {
+        let params: Vec<Value> = params.iter().map(mysql_value).collect();
+        let mut conn = self.conn().await?;
+        conn.exec_drop(sql, Params::from(params)).await?;
+        Ok(conn.affected_rows())
+    }
+
+    async fn begin(&self) -> Result<()> {
+        if self.in_tx.load(Ordering::SeqCst) {
+            bail!("事务已开启，不能重复 begin");
+        }
+        self.conn().await?.query_drop("BEGIN").await?;
+        self.in_tx.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<()> {
+        if !self.in_tx.swap(false, Ordering::SeqCst) {
+            bail!("没有进行中的事务可以提交");
+        }
+        self.conn().await?.query_drop("COMMIT").await?;
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        if self.in_tx.swap(false, Ordering::SeqCst) {
+            self.conn().await?.query_drop("ROLLBACK").await?;
+        }
+        Ok(())
+    }
+
+    async fn load_data(&self, statement: &str, data: &[u8]) -> Result<u64> {
+        let data = Bytes::copy_from_slice(data);
+        let mut conn = self.conn().await?;
+        conn.set_infile_handler(async move { Ok(infile_chunks(data)) });
+        conn.query_drop(statement).await?;
+
+        let warnings = conn.get_warnings();
+        if warnings > 0 {
+            warn!(
+                "[LOAD DATA] 写入产生 {} 条警告（重复键被跳过或值被转换）",
+                warnings
+            );
+        }
+        Ok(conn.affected_rows())
+    }
+
+    async fn savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("SAVEPOINT {}", name)).await
+    }
+
+    async fn rollback_to_savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("ROLLBACK TO SAVEPOINT {}", name))
+            .await
+    }
+
+    async fn release_savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("RELEASE SAVEPOINT {}", name))
+            .await
+    }
+}
+
+/// 查询结果中的值，与 sqlx 执行器一致：日期时间等其它类型不返回
+fn column_value(value: Option<&Value>) -> Option<ColumnValue> {
+    match value? {
+        Value::Int(n) => Some(ColumnValue::Int(*n)),
+        Value::UInt(n) => i64::try_from(*n).ok().map(ColumnValue::Int),
+        Value::Float(n) => Some(ColumnValue::Float(f64::from(*n))),
+        Value::Double(n) => Some(ColumnValue::Float(*n)),
+        Value::Bytes(b) => String::from_utf8(b.clone()).ok().map(ColumnValue::Text),
+        Value::NULL | Value::Date(..) | Value::Time(..) => None,
+    }
+}
+
+/// 绑定参数，与 sqlx MySQL 执行器的绑定方式一致
+fn mysql_value(value: &UnifiedValue) -> Value {
+    match value {
+        UnifiedValue::Null
+        | UnifiedValue::OptI64(None)
+        | UnifiedValue::OptF64(None)
+        | UnifiedValue::OptBool(None)
+        | UnifiedValue::OptDecimal(None)
+        | UnifiedValue::OptDateTime(None) => Value::NULL,
+        UnifiedValue::Bool(b) | UnifiedValue::OptBool(Some(b)) => Value::Int(i64::from(*b)),
+        UnifiedValue::Int(n) | UnifiedValue::OptI64(Some(n)) => Value::Int(*n),
+        UnifiedValue::Float(n) | UnifiedValue::OptF64(Some(n)) => Value::Double(*n),
+        UnifiedValue::Decimal(d) | UnifiedValue::OptDecimal(Some(d)) => {
+            Value::Bytes(d.to_string().into_bytes())
+        }
+        UnifiedValue::DateTime(dt) | UnifiedValue::OptDateTime(Some(dt)) => datetime_value(dt),
+        UnifiedValue::Date(d) => date_value(d, NaiveTime::MIN),
+        UnifiedValue::Time(t) => Value::Time(
+            false,
+            0,
+            t.hour() as u8,
+            t.minute() as u8,
+            t.second() as u8,
+            t.nanosecond() / 1000,
+        ),
+        UnifiedValue::String(s) => Value::Bytes(s.as_bytes().to_vec()),
+        UnifiedValue::Json(j) => Value::Bytes(j.to_string().into_bytes()),
+        UnifiedValue::Bytes(b) => Value::Bytes(b.clone()),
+        UnifiedValue::Array(_) => Value::Bytes(Vec::new()),
+    }
+}
+
+fn datetime_value(dt: &NaiveDateTime) -> Value {
+    date_value(&dt.date(), dt.time())
+}
+
+fn date_value(date: &NaiveDate, time: NaiveTime) -> Value {
+    Value::Date(
+        date.year() as u16,
+        date.month() as u8,
+        date.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+        time.nanosecond() / 1000,
+    )
+}
+
+/// 把编码好的数据切成块，作为 infile 流发送
+fn infile_chunks(data: Bytes) -> InfileData {
+    let chunks: Vec<std::io::Result<Bytes>> = (0..data.len())
+        .step_by(INFILE_CHUNK_SIZE)
+        .map(|start| Ok(data.slice(start..data.len().min(start + INFILE_CHUNK_SIZE))))
+        .collect();
+    stream::iter(chunks).boxed()
+}
+
+fn push_value(out: &mut Vec<u8>, value: &UnifiedValue) -> Result<()> {
+    match value {
+        UnifiedValue::Null
+        | UnifiedValue::OptI64(None)
+        | UnifiedValue::OptF64(None)
+        | UnifiedValue::OptBool(None)
+        | UnifiedValue::OptDecimal(None)
+        | UnifiedValue::OptDateTime(None) => out.extend_from_slice(b"\\N"),
+        UnifiedValue::Bool(b) | UnifiedValue::OptBool(Some(b)) => {
+            out.push(if *b { b'1' } else { b'0' })
+        }
+        UnifiedValue::Int(n) | UnifiedValue::OptI64(Some(n)) => {
+            out.extend_from_slice(n.to_string().as_bytes())
+        }
+        UnifiedValue::Float(n) | UnifiedValue::OptF64(Some(n)) => {
+            if !n.is_finite() {
+                bail!("MySQL 不支持浮点值 {}", n);
+            }
+            out.extend_from_slice(n.to_string().as_bytes())
+        }
+        UnifiedValue::Decimal(d) | UnifiedValue::OptDecimal(Some(d)) => {
+            out.extend_from_slice(d.to_string().as_bytes())
+        }
+        UnifiedValue::DateTime(dt) | UnifiedValue::OptDateTime(Some(dt)) => {
+            out.extend_from_slice(dt.to_string().as_bytes())
+        }
+        UnifiedValue::Date(d) => out.extend_from_slice(d.to_string().as_bytes()),
+        UnifiedValue::Time(t) => out.extend_from_slice(t.to_string().as_bytes()),
+        UnifiedValue::String(s) => push_escaped(out, s.as_bytes()),
+        UnifiedValue::Json(j) => push_escaped(out, j.to_string().as_bytes()),
+        UnifiedValue::Bytes(b) => push_escaped(out, b),
+        // 与 VALUES 写入一致，MySQL 没有数组类型，写为空字符串
+        UnifiedValue::Array(_) => {}
+    }
+    Ok(())
+}
+
+fn push_escaped(out: &mut Vec<u8>, bytes: &[u8]) {
+    for &byte in bytes {
+        match byte {
+            b'\\' => out.extend_from_slice(b"\\\\"),
+            b'\t' => out.extend_from_slice(b"\\t"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            0 => out.extend_from_slice(b"\\0"),
+            _ => out.push(byte),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn load_data_statement_maps_write_modes() -> Result<()> {
+        let columns = vec!["id".to_string(), "na`me".to_string()];
+        assert_eq!(
+            load_data_statement("db.users", &columns, WriteMode::Insert)?,
+            "LOAD DATA LOCAL INFILE 'relus_batch' IGNORE INTO TABLE db.users CHARACTER SET binary \
+             FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\' LINES TERMINATED BY '\\n' \
+             (`id`, `na``me`)"
+        );
+        assert!(load_data_statement("users", &columns, WriteMode::Upsert)?
+            .starts_with("LOAD DATA LOCAL INFILE 'relus_batch' REPLACE INTO TABLE users "));
+        assert!(load_data_statement("users", &columns, WriteMode::Update).is_err());
+        assert!(load_data_statement("users", &columns, WriteMode::Scd2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn encodes_nulls_escapes_and_typed_values() -> Result<()> {
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .and_then(|d| d.and_hms_milli_opt(3, 4, 5, 600))
+            .map(UnifiedValue::DateTime)
+            .unwrap_or_default();
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .map(UnifiedValue::Date)
+            .unwrap_or_default();
+        let rows = vec![
+            vec![
+                UnifiedValue::Int(1),
+                UnifiedValue::String("a\tb\\c\nd\re".to_string()),
+                UnifiedValue::OptI64(None),
+                UnifiedValue::Bool(false),
+                UnifiedValue::String("\\N".to_string()),
+            ],
+            vec![
+                UnifiedValue::Bytes(vec![0x00, 0xff, b'\n', b'x']),
+                UnifiedValue::Float(1.5),
+                datetime,
+                date,
+                UnifiedValue::String(String::new()),
+            ],
+        ];
+
+        let encoded = encode_load_data(&rows)?;
+        let mut expected = b"1\ta\\tb\\\\c\\nd\\re\t\\N\t0\t\\\\N\n".to_vec();
+        expected.extend_from_slice(b"\\0\xff\\nx\t1.5\t2024-01-02 03:04:05.600\t2024-02-29\t\n");
+        assert_eq!(encoded, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_non_finite_floats() {
+        let rows = vec![vec![UnifiedValue::Float(f64::NAN)]];
+        assert!(encode_load_data(&rows).is_err());
+    }
+
+    #[test]
+    fn binds_unified_values_as_mysql_values() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .and_then(|d| d.and_hms_micro_opt(3, 4, 5, 600))
+            .unwrap_or_default();
+        assert_eq!(
+            mysql_value(&UnifiedValue::DateTime(datetime)),
+            Value::Date(2024, 1, 2, 3, 4, 5, 600)
+        );
+        assert_eq!(mysql_value(&UnifiedValue::Bool(true)), Value::Int(1));
+        assert_eq!(mysql_value(&UnifiedValue::OptI64(None)), Value::NULL);
+        assert_eq!(
+            mysql_value(&UnifiedValue::Decimal(rust_decimal::Decimal::new(150, 2))),
+            Value::Bytes(b"1.50".to_vec())
+        );
+        assert!(matches!(
+            column_value(Some(&Value::Bytes(b"abc".to_vec()))),
+            Some(ColumnValue::Text(s)) if s == "abc"
+        ));
+    }
+
+    #[test]
+    fn infile_chunks_cover_all_bytes() {
+        let data = Bytes::from(vec![7u8; INFILE_CHUNK_SIZE * 2 + 3]);
+        let chunks = futures::executor::block_on(infile_chunks(data).collect::<Vec<_>>());
+        let sizes: Vec<usize> = chunks
+            .iter()
+            .map(|chunk| chunk.as_ref().map_or(0, Bytes::len))
+            .collect();
+        assert_eq!(sizes, vec![INFILE_CHUNK_SIZE, INFILE_CHUNK_SIZE, 3]);
+    }
+}
@@ -0,0 +1,177 @@
+//! PostgreSQL `COPY ... FROM STDIN` 写入
+//!
+//! 使用 text 格式：列之间用 tab 分隔，行以换行结束，NULL 写作 `\N`，
+//! 值中的反斜杠、tab、换行、回车按 COPY 规则转义。
+
+use anyhow::Result;
+use relus_common::types::UnifiedValue;
+
+use crate::pool::DatabaseExecutor;
+use crate::sql_builder::{PostgresBackend, SqlBackend, WriteRows};
+
+/// 生成 `COPY table (cols) FROM STDIN`，表名与 INSERT 一致不加引号
+pub fn copy_statement(table: &str, columns: &[String]) -> String {
+    let mut sql = format!("COPY {} (", table);
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            sql.push_str(", ");
+        }
+        PostgresBackend::push_identifier(&mut sql, column);
+    }
+    sql.push_str(") FROM STDIN (FORMAT text)");
+    sql
+}
+
+/// 将行编码为 COPY text 格式
+pub fn encode_copy_text(rows: &[Vec<UnifiedValue>]) -> Vec<u8> {
+    let mut out = String::new();
+    for row in rows {
+        for (i, value) in row.iter().enumerate() {
+            if i > 0 {
+                out.push('\t');
+            }
+            match value_text(value) {
+                Some(text) => push_escaped(&mut out, &text),
+                None => out.push_str("\\N"),
+            }
+        }
+        out.push('\n');
+    }
+    out.into_bytes()
+}
+
+/// 通过 COPY 写入一批行，返回写入条数
+pub async fn execute_copy_insert(
+    executor: &dyn DatabaseExecutor,
+    table: &str,
+    write_rows: &WriteRows,
+) -> Result<usize> {
+    if write_rows.values.is_empty() {
+        return Ok(0);
+    }
+    let statement = copy_statement(table, &write_rows.columns);
+    let copied = executor
+        .copy_in(&statement, &encode_copy_text(&write_rows.values))
+        .await?;
+    Ok(copied as usize)
+}
+
+/// 值的 PostgreSQL 文本表示，`None` 表示 NULL
+fn value_text(value: &UnifiedValue) -> Option<String> {
+    match value {
+        UnifiedValue::Null
+        | UnifiedValue::OptI64(None)
+        | UnifiedValue::OptF64(None)
+        | UnifiedValue::OptBool(None)
+        | UnifiedValue::OptDecimal(None)
+        | UnifiedValue::OptDateTime(None) => None,
+        UnifiedValue::Bool(b) | UnifiedValue::OptBool(Some(b)) => {
+            Some(if *b { "t" } else { "f" }.to_string())
+        }
+        UnifiedValue::Int(n) | UnifiedValue::OptI64(Some(n)) => Some(n.to_string()),
+        UnifiedValue::Float(n) | UnifiedValue::OptF64(Some(n)) => Some(float_text(*n)),
+        UnifiedValue::Decimal(d) | UnifiedValue::OptDecimal(Some(d)) => Some(d.to_string()),
+        UnifiedValue::DateTime(dt) | UnifiedValue::OptDateTime(Some(dt)) => Some(dt.to_string()),
+        UnifiedValue::Date(d) => Some(d.to_string()),
+        UnifiedValue::Time(t) => Some(t.to_string()),
+        UnifiedValue::String(s) => Some(s.clone()),
+        UnifiedValue::Json(j) => Some(j.to_string()),
+        UnifiedValue::Bytes(b) => {
+            let mut hex = String::with_capacity(2 + b.len() * 2);
+            hex.push_str("\\x");
+            for byte in b {
+                hex.push_str(&format!("{:02x}", byte));
+            }
+            Some(hex)
+        }
+        UnifiedValue::Array(items) => Some(array_text(items)),
+    }
+}
+
+fn float_text(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// 数组字面量 `{"a","b",NULL}`，元素统一加引号
+fn array_text(items: &[UnifiedValue]) -> String {
+    let mut out = String::from("{");
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        match value_text(item) {
+            Some(text) => {
+                out.push('"');
+                out.push_str(&text.replace('\\', "\\\\").replace('"', "\\\""));
+                out.push('"');
+            }
+            None => out.push_str("NULL"),
+        }
+    }
+    out.push('}');
+    out
+}
+
+fn push_escaped(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn copy_statement_quotes_columns() {
+        assert_eq!(
+            copy_statement("public.users", &["id".to_string(), "name".to_string()]),
+            "COPY public.users (\"id\", \"name\") FROM STDIN (FORMAT text)"
+        );
+    }
+
+    #[test]
+    fn encodes_nulls_escapes_and_typed_values() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .and_then(|d| d.and_hms_milli_opt(3, 4, 5, 600))
+            .map(UnifiedValue::DateTime)
+            .unwrap_or_default();
+        let rows = vec![
+            vec![
+                UnifiedValue::Int(1),
+                UnifiedValue::String("a\tb\\c\nd".to_string()),
+                UnifiedValue::OptI64(None),
+                UnifiedValue::Bool(true),
+            ],
+            vec![
+                UnifiedValue::Bytes(vec![0xde, 0xad]),
+                UnifiedValue::Float(f64::NEG_INFINITY),
+                datetime,
+                UnifiedValue::Array(vec![
+                    UnifiedValue::String("x\"y".to_string()),
+                    UnifiedValue::Null,
+                ]),
+            ],
+        ];
+
+        let encoded = String::from_utf8(encode_copy_text(&rows)).unwrap_or_default();
+        assert_eq!(
+            encoded,
+            "1\ta\\tb\\\\c\\nd\t\\N\tt\n\
+             \\\\xdead\t-Infinity\t2024-01-02 03:04:05.600\t{\"x\\\\\"y\",NULL}\n"
+        );
+    }
+}
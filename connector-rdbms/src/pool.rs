@@ -2,18 +2,20 @@
 //Database connection pool management
 //Provides connection pooling for PostgreSQL and MySQL with caching support.
 ///
-use anyhow::Result;
+use anyhow::{bail, Result};
 
 use async_trait::async_trait;
 use chrono::{Local, Offset};
 use clap::ValueEnum;
 use dashmap::DashMap;
 use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions};
-use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::postgres::{PgConnectOptions, PgCopyIn, PgPoolCopyExt, PgPoolOptions};
 use sqlx::{MySqlPool, PgPool};
+use std::ops::DerefMut;
 use std::str::FromStr;
 use std::sync::OnceLock;
 use std::time::Duration;
+use tokio::sync::Mutex;
 
 use super::util::{database_kind_from_opt_str, DbParams};
 use relus_common::types::UnifiedValue;
@@ -72,7 +74,7 @@ async fn create_pool(cfg: &PoolConfig) -> Result<RdbmsPool> {
     }
 }
 
-fn current_system_timezone() -> String {
+pub(crate) fn current_system_timezone() -> String {
     timezone_offset_string(Local::now().offset().fix().local_minus_utc())
 }
 
@@ -175,11 +177,65 @@ pub trait DatabaseExecutor: Send + Sync {
 
     /// 执行带参数的 SQL
     async fn execute_with_params(&self, sql: &str, params: &[UnifiedValue]) -> Result<u64>;
+
+    /// 开启事务：之后的 `execute` / `execute_with_params` 都在事务连接上执行，
+    /// 直到 `commit` 或 `rollback`
+    async fn begin(&self) -> Result<()>;
+
+    /// 提交当前事务
+    async fn commit(&self) -> Result<()>;
+
+    /// 回滚当前事务，没有进行中的事务时什么都不做
+    async fn rollback(&self) -> Result<()>;
+
+    /// 执行 `COPY ... FROM STDIN` 并发送编码好的数据，返回写入行数
+    async fn copy_in(&self, _statement: &str, _data: &[u8]) -> Result<u64> {
+        bail!("当前数据库不支持 COPY")
+    }
+
+    /// 执行 `LOAD DATA LOCAL INFILE` 并发送编码好的数据，返回影响行数
+    async fn load_data(&self, _statement: &str, _data: &[u8]) -> Result<u64> {
+        bail!("当前连接不支持 LOAD DATA LOCAL INFILE")
+    }
+
+    /// 查询表的列名和列类型（不含长度等类型修饰符）
+    async fn fetch_column_types(&self, _table: &str) -> Result<Vec<(String, String)>> {
+        bail!("当前数据库不支持查询列类型")
+    }
+
+    /// 在当前事务中设置保存点
+    async fn savepoint(&self, _name: &str) -> Result<()> {
+        bail!("当前数据库不支持保存点")
+    }
+
+    /// 回滚到保存点：之后的语句失效，事务可以继续使用
+    async fn rollback_to_savepoint(&self, _name: &str) -> Result<()> {
+        bail!("当前数据库不支持保存点")
+    }
+
+    /// 释放保存点
+    async fn release_savepoint(&self, _name: &str) -> Result<()> {
+        bail!("当前数据库不支持保存点")
+    }
 }
 
 /// PostgreSQL executor reference
 pub struct PgExecutorRef {
     pool: sqlx::PgPool,
+    tx: Mutex<Option<sqlx::Transaction<'static, sqlx::Postgres>>>,
+}
+
+impl PgExecutorRef {
+    /// 在事务连接上以文本协议执行（保存点语句不能预编译）
+    async fn execute_in_tx(&self, sql: &str) -> Result<()> {
+        match self.tx.lock().await.as_mut() {
+            Some(tx) => {
+                sqlx::Executor::execute(&mut **tx, sql).await?;
+                Ok(())
+            }
+            None => bail!("保存点只能在事务中使用"),
+        }
+    }
 }
 
 #[async_trait]
@@ -204,7 +260,11 @@ impl DatabaseExecutor for PgExecutorRef {
     }
 
     async fn execute(&self, sql: &str) -> Result<u64> {
-        let result = sqlx::query(sql).execute(&self.pool).await?;
+        let query = sqlx::query(sql);
+        let result = match self.tx.lock().await.as_mut() {
+            Some(tx) => query.execute(&mut **tx).await?,
+            None => query.execute(&self.pool).await?,
+        };
         Ok(result.rows_affected())
     }
 
@@ -213,9 +273,83 @@ impl DatabaseExecutor for PgExecutorRef {
         for p in params {
             query = bind_typed_val_pg(query, p);
         }
-        let result = query.execute(&self.pool).await?;
+        let result = match self.tx.lock().await.as_mut() {
+            Some(tx) => query.execute(&mut **tx).await?,
+            None => query.execute(&self.pool).await?,
+        };
         Ok(result.rows_affected())
     }
+
+    async fn begin(&self) -> Result<()> {
+        let mut tx = self.tx.lock().await;
+        if tx.is_some() {
+            bail!("事务已开启，不能重复 begin");
+        }
+        *tx = Some(self.pool.begin().await?);
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<()> {
+        match self.tx.lock().await.take() {
+            Some(tx) => Ok(tx.commit().await?),
+            None => bail!("没有进行中的事务可以提交"),
+        }
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        if let Some(tx) = self.tx.lock().await.take() {
+            tx.rollback().await?;
+        }
+        Ok(())
+    }
+
+    async fn copy_in(&self, statement: &str, data: &[u8]) -> Result<u64> {
+        match self.tx.lock().await.as_mut() {
+            Some(tx) => send_copy(tx.copy_in_raw(statement).await?, data).await,
+            None => send_copy(self.pool.copy_in_raw(statement).await?, data).await,
+        }
+    }
+
+    async fn fetch_column_types(&self, table: &str) -> Result<Vec<(String, String)>> {
+        // 不带类型修饰符：显式转换到 varchar(n) 会静默截断，长度留给赋值时按列定义校验
+        let query = sqlx::query_as(
+            "SELECT a.attname::text, format_type(a.atttypid, NULL) \
+             FROM pg_attribute a \
+             WHERE a.attrelid = to_regclass($1) AND a.attnum > 0 AND NOT a.attisdropped",
+        )
+        .bind(table);
+        let rows = match self.tx.lock().await.as_mut() {
+            Some(tx) => query.fetch_all(&mut **tx).await?,
+            None => query.fetch_all(&self.pool).await?,
+        };
+        Ok(rows)
+    }
+
+    async fn savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("SAVEPOINT {}", name)).await
+    }
+
+    async fn rollback_to_savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("ROLLBACK TO SAVEPOINT {}", name))
+            .await
+    }
+
+    async fn release_savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("RELEASE SAVEPOINT {}", name))
+            .await
+    }
+}
+
+/// 发送 COPY 数据，失败时中止 COPY 让连接回到可用状态
+async fn send_copy<C>(mut copy: PgCopyIn<C>, data: &[u8]) -> Result<u64>
+where
+    C: DerefMut<Target = sqlx::PgConnection>,
+{
+    if let Err(e) = copy.send(data).await {
+        let _ = copy.abort(e.to_string()).await;
+        return Err(e.into());
+    }
+    Ok(copy.finish().await?)
 }
 
 fn pg_column_value(row: &sqlx::postgres::PgRow, idx: usize) -> Option<ColumnValue> {
@@ -266,7 +400,7 @@ fn bind_typed_val_pg<'q>(
         UnifiedValue::Bytes(b) => q = q.bind(b.clone()),
         UnifiedValue::Date(d) => q = q.bind(*d),
         UnifiedValue::Time(t) => q = q.bind(*t),
-        UnifiedValue::Json(j) => q = q.bind(j.to_string()),
+        UnifiedValue::Json(j) => q = q.bind(sqlx::types::Json(j.clone())),
         UnifiedValue::Array(_) => q = q.bind(String::new()), // Arrays not directly supported
     }
     q
@@ -275,6 +409,20 @@ fn bind_typed_val_pg<'q>(
 /// MySQL executor reference
 pub struct MySqlExecutorRef {
     pool: sqlx::MySqlPool,
+    tx: Mutex<Option<sqlx::Transaction<'static, sqlx::MySql>>>,
+}
+
+impl MySqlExecutorRef {
+    /// 在事务连接上以文本协议执行（保存点语句不能预编译）
+    async fn execute_in_tx(&self, sql: &str) -> Result<()> {
+        match self.tx.lock().await.as_mut() {
+            Some(tx) => {
+                sqlx::Executor::execute(&mut **tx, sql).await?;
+                Ok(())
+            }
+            None => bail!("保存点只能在事务中使用"),
+        }
+    }
 }
 
 #[async_trait]
@@ -299,7 +447,11 @@ impl DatabaseExecutor for MySqlExecutorRef {
     }
 
     async fn execute(&self, sql: &str) -> Result<u64> {
-        let result = sqlx::query(sql).execute(&self.pool).await?;
+        let query = sqlx::query(sql);
+        let result = match self.tx.lock().await.as_mut() {
+            Some(tx) => query.execute(&mut **tx).await?,
+            None => query.execute(&self.pool).await?,
+        };
         Ok(result.rows_affected())
     }
 
@@ -308,9 +460,49 @@ impl DatabaseExecutor for MySqlExecutorRef {
         for p in params {
             query = bind_typed_val_my(query, p);
         }
-        let result = query.execute(&self.pool).await?;
+        let result = match self.tx.lock().await.as_mut() {
+            Some(tx) => query.execute(&mut **tx).await?,
+            None => query.execute(&self.pool).await?,
+        };
         Ok(result.rows_affected())
     }
+
+    async fn begin(&self) -> Result<()> {
+        let mut tx = self.tx.lock().await;
+        if tx.is_some() {
+            bail!("事务已开启，不能重复 begin");
+        }
+        *tx = Some(self.pool.begin().await?);
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<()> {
+        match self.tx.lock().await.take() {
+            Some(tx) => Ok(tx.commit().await?),
+            None => bail!("没有进行中的事务可以提交"),
+        }
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        if let Some(tx) = self.tx.lock().await.take() {
+            tx.rollback().await?;
+        }
+        Ok(())
+    }
+
+    async fn savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("SAVEPOINT {}", name)).await
+    }
+
+    async fn rollback_to_savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("ROLLBACK TO SAVEPOINT {}", name))
+            .await
+    }
+
+    async fn release_savepoint(&self, name: &str) -> Result<()> {
+        self.execute_in_tx(&format!("RELEASE SAVEPOINT {}", name))
+            .await
+    }
 }
 
 fn bind_typed_val_my<'q>(
@@ -341,12 +533,27 @@ fn bind_typed_val_my<'q>(
 
 impl RdbmsPool {
     /// Get executor for database operations
+    ///
+    /// 每个 executor 最多持有一个进行中的事务，drop 时未提交的事务自动回滚。
     pub fn executor(&self) -> Box<dyn DatabaseExecutor> {
         match self {
-            RdbmsPool::Postgres(p) => Box::new(PgExecutorRef { pool: p.clone() }),
-            RdbmsPool::Mysql(p) => Box::new(MySqlExecutorRef { pool: p.clone() }),
+            RdbmsPool::Postgres(p) => Box::new(PgExecutorRef {
+                pool: p.clone(),
+                tx: Mutex::new(None),
+            }),
+            RdbmsPool::Mysql(p) => Box::new(MySqlExecutorRef {
+                pool: p.clone(),
+                tx: Mutex::new(None),
+            }),
         }
     }
+    pub fn kind(&self) -> DatabaseKind {
+        match self {
+            Self::Postgres(_) => DatabaseKind::Postgres,
+            Self::Mysql(_) => DatabaseKind::Mysql,
+        }
+    }
+
     pub fn db_type(&self) -> &str {
         match self {
             Self::Postgres(_) => "postgres",
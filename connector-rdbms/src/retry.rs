@@ -0,0 +1,174 @@
+//! 瞬时错误重试
+//!
+//! 把 sqlx / mysql_async 错误分为可重试（死锁、锁等待超时、连接断开、连接池超时等）与不可重试两类，
+//! 可重试错误按指数退避加随机抖动等待后重试。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use relus_common::job_config::RetryConfig;
+use sqlx::mysql::MySqlDatabaseError;
+use tracing::warn;
+
+/// PostgreSQL 可重试的 SQLSTATE
+///
+/// 40001 serialization_failure、40P01 deadlock_detected、55P03 lock_not_available、
+/// 53300 too_many_connections、57P01 admin_shutdown，以及 08 开头的连接异常。
+const PG_RETRYABLE_STATES: &[&str] = &["40001", "40P01", "55P03", "53300", "57P01"];
+
+/// MySQL 可重试的错误号
+///
+/// 1205 锁等待超时、1213 死锁、1040 连接数过多、2006 / 2013 连接断开。
+const MYSQL_RETRYABLE_ERRORS: &[u16] = &[1205, 1213, 1040, 2006, 2013];
+
+/// 判断错误链中是否包含可重试的数据库错误
+///
+/// 只认驱动返回的错误：单独的 `io::Error`（例如读写本地文件失败）不是连接问题，不重试。
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<sqlx::Error>() {
+            return is_retryable_sqlx(e);
+        }
+        if let Some(e) = cause.downcast_ref::<mysql_async::Error>() {
+            return is_retryable_mysql_async(e);
+        }
+        false
+    })
+}
+
+fn is_retryable_sqlx(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::WorkerCrashed => true,
+        sqlx::Error::Database(db) => {
+            if let Some(mysql) = db.try_downcast_ref::<MySqlDatabaseError>() {
+                return MYSQL_RETRYABLE_ERRORS.contains(&mysql.number());
+            }
+            db.code().is_some_and(|code| {
+                PG_RETRYABLE_STATES.contains(&code.as_ref()) || code.starts_with("08")
+            })
+        }
+        _ => false,
+    }
+}
+
+/// LOAD DATA 使用的 mysql_async 连接的错误
+fn is_retryable_mysql_async(err: &mysql_async::Error) -> bool {
+    match err {
+        mysql_async::Error::Io(_) => true,
+        mysql_async::Error::Server(e) => MYSQL_RETRYABLE_ERRORS.contains(&e.code),
+        _ => false,
+    }
+}
+
+/// 重试策略，克隆后共享同一个重试计数
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    config: RetryConfig,
+    retries: Arc<AtomicUsize>,
+}
+
+impl RetryPolicy {
+    pub fn new(config: RetryConfig) -> Self {
+        Self {
+            config,
+            retries: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// 不重试的策略
+    pub fn disabled() -> Self {
+        Self::new(RetryConfig {
+            max_retries: 0,
+            ..Default::default()
+        })
+    }
+
+    /// 已经重试 `attempt` 次后是否还应重试该错误
+    pub fn should_retry(&self, attempt: usize, err: &anyhow::Error) -> bool {
+        attempt < self.config.max_retries && is_retryable(err)
+    }
+
+    /// 第 `attempt` 次重试（从 1 开始）前的等待时间：指数退避，在 [delay/2, delay] 间随机抖动
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(20) as u32;
+        let delay = self
+            .config
+            .initial_backoff_ms
+            .saturating_mul(1 << exponent)
+            .min(self.config.max_backoff_ms);
+        let half = delay / 2;
+        Duration::from_millis(half + rand::random_range(0..=delay - half))
+    }
+
+    /// 记录一次重试并等待退避时间
+    pub async fn wait(&self, attempt: usize, err: &anyhow::Error, context: &str) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+        let delay = self.backoff(attempt);
+        warn!(
+            "{} 遇到可重试错误，{}ms 后第 {}/{} 次重试: {}",
+            context,
+            delay.as_millis(),
+            attempt,
+            self.config.max_retries,
+            err
+        );
+        tokio::time::sleep(delay).await;
+    }
+
+    /// 累计重试次数
+    pub fn retries(&self) -> usize {
+        self.retries.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new(RetryConfig {
+            max_retries: 2,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 300,
+        })
+    }
+
+    #[test]
+    fn classifies_transient_errors() {
+        assert!(is_retryable(&sqlx::Error::PoolTimedOut.into()));
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        assert!(is_retryable(
+            &anyhow::Error::from(sqlx::Error::Io(io)).context("写入失败")
+        ));
+        assert!(!is_retryable(&sqlx::Error::RowNotFound.into()));
+        assert!(!is_retryable(&anyhow::anyhow!("语法错误")));
+
+        let io = || std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        assert!(is_retryable(&mysql_async::Error::from(io()).into()));
+        assert!(!is_retryable(
+            &anyhow::Error::from(io()).context("读取文件失败")
+        ));
+    }
+
+    #[test]
+    fn retries_up_to_max() {
+        let policy = policy();
+        let err = sqlx::Error::PoolTimedOut.into();
+        assert!(policy.should_retry(0, &err));
+        assert!(policy.should_retry(1, &err));
+        assert!(!policy.should_retry(2, &err));
+        assert!(!RetryPolicy::disabled().should_retry(0, &err));
+    }
+
+    #[test]
+    fn backoff_grows_with_jitter_and_cap() {
+        let policy = policy();
+        for _ in 0..20 {
+            let first = policy.backoff(1).as_millis();
+            assert!((50..=100).contains(&first));
+            let capped = policy.backoff(5).as_millis();
+            assert!((150..=300).contains(&capped));
+        }
+    }
+}
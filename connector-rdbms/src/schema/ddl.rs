@@ -0,0 +1,432 @@
+//! 按源表结构生成目标库建表语句
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::pool::{DatabaseKind, RdbmsPool};
+use crate::sql_builder::{ColumnDef, RdbmsSqlBuilder};
+
+use super::{
+    is_widening, ColumnInfo, MetadataDiscoverer, RdbmsDiscoverer, SchemaChange, TableSchema,
+};
+
+/// 生成目标库的 `CREATE TABLE IF NOT EXISTS`，保留主键与可空性
+pub fn create_table_sql(schema: &TableSchema, target: DatabaseKind, table: &str) -> String {
+    let columns: Vec<ColumnDef> = schema
+        .columns
+        .iter()
+        .map(|col| {
+            let is_key = schema.primary_keys.contains(&col.name);
+            ColumnDef {
+                name: col.name.clone(),
+                sql_type: target_type(col, target, is_key),
+                nullable: col.nullable && !is_key,
+            }
+        })
+        .collect();
+
+    RdbmsSqlBuilder::new(target).create_table(table, &columns, &schema.primary_keys)
+}
+
+/// 按 column_mapping（目标列 → 源列）把源表结构改写为目标表结构
+///
+/// 映射到源列的目标列沿用源列的类型、可空性和精度；映射到表达式、或 column_types
+/// 指定了不同逻辑类型的列按逻辑类型建列。`key_columns` 为空时沿用源表主键，
+/// 源表主键有列没有被映射时不建主键。
+pub fn map_to_target_schema(
+    source: &TableSchema,
+    target_table: &str,
+    column_mapping: &BTreeMap<String, String>,
+    column_types: Option<&BTreeMap<String, String>>,
+    key_columns: &[String],
+) -> TableSchema {
+    let columns: Vec<ColumnInfo> = if column_mapping.is_empty() {
+        source.columns.clone()
+    } else {
+        column_mapping
+            .iter()
+            .map(|(target, source_col)| {
+                let declared = column_types.and_then(|types| types.get(target));
+                match (source.get_column(source_col), declared) {
+                    (Some(col), Some(logical)) if !same_logical(&col.logical_type, logical) => {
+                        ColumnInfo::new(target.clone(), String::new(), logical.clone())
+                            .with_nullable(col.nullable)
+                    }
+                    (Some(col), _) => ColumnInfo {
+                        name: target.clone(),
+                        ..col.clone()
+                    },
+                    (None, logical) => ColumnInfo::new(
+                        target.clone(),
+                        String::new(),
+                        logical.cloned().unwrap_or_else(|| "text".to_string()),
+                    ),
+                }
+            })
+            .collect()
+    };
+
+    let primary_keys = if key_columns.is_empty() {
+        let mapped: Option<Vec<String>> = source
+            .primary_keys
+            .iter()
+            .map(|pk| {
+                if column_mapping.is_empty() {
+                    return Some(pk.clone());
+                }
+                column_mapping
+                    .iter()
+                    .find(|(_, source_col)| *source_col == pk)
+                    .map(|(target, _)| target.clone())
+            })
+            .collect();
+        mapped.unwrap_or_default()
+    } else {
+        key_columns
+            .iter()
+            .filter(|key| columns.iter().any(|c| &c.name == *key))
+            .cloned()
+            .collect()
+    };
+
+    TableSchema::new(target_table.to_string(), source.db_kind.clone())
+        .with_columns(columns)
+        .with_primary_keys(primary_keys)
+}
+
+/// 目标表不存在时按 schema 建表，返回是否执行了建表
+pub async fn create_table_if_missing(
+    target_pool: Arc<RdbmsPool>,
+    schema: &TableSchema,
+    table: &str,
+) -> Result<bool> {
+    let discoverer = RdbmsDiscoverer::new(Arc::clone(&target_pool), table.to_string());
+    if discoverer.table_exists(table).await? {
+        return Ok(false);
+    }
+
+    let ddl = create_table_sql(schema, target_pool.kind(), table);
+    info!("目标表 {} 不存在，自动建表: {}", table, ddl);
+    target_pool.executor().execute(&ddl).await?;
+    Ok(true)
+}
+
+/// 把源表结构变更翻译为目标表的 ALTER 语句，只生成可以安全执行的部分
+///
+/// 新增列一律按可空列添加（目标表已有数据）；类型放宽、非空改可空时重新定义列类型。
+/// 删除列、收窄类型等变更不生成语句。列名经 column_mapping 映射到目标列，
+/// 没有映射到目标表的源列跳过。
+pub fn evolution_ddl(
+    old: &TableSchema,
+    new: &TableSchema,
+    changes: &[SchemaChange],
+    column_mapping: &BTreeMap<String, String>,
+    target: DatabaseKind,
+    table: &str,
+) -> Vec<String> {
+    let builder = RdbmsSqlBuilder::new(target);
+    let mut statements = Vec::new();
+    let mut modified: Vec<&str> = Vec::new();
+
+    for change in changes {
+        match change {
+            SchemaChange::ColumnAdded(col) => {
+                let Some(name) = target_column(column_mapping, &col.name) else {
+                    continue;
+                };
+                let column = ColumnDef {
+                    name,
+                    sql_type: target_type(col, target, false),
+                    nullable: true,
+                };
+                statements.push(builder.add_column(table, &column));
+            }
+            SchemaChange::ColumnTypeChanged { column_name, .. }
+            | SchemaChange::NullabilityChanged { column_name, .. } => {
+                if modified.contains(&column_name.as_str()) {
+                    continue;
+                }
+                modified.push(column_name);
+
+                let (Some(old_col), Some(new_col)) =
+                    (old.get_column(column_name), new.get_column(column_name))
+                else {
+                    continue;
+                };
+                let Some(name) = target_column(column_mapping, column_name) else {
+                    continue;
+                };
+                if !is_widening(old_col, new_col) {
+                    continue;
+                }
+
+                let is_key = new.primary_keys.contains(column_name);
+                let sql_type = target_type(new_col, target, is_key);
+                let relax_null = new_col.nullable && !old_col.nullable && !is_key;
+                if sql_type == target_type(old_col, target, is_key) && !relax_null {
+                    continue;
+                }
+                let column = ColumnDef {
+                    name,
+                    sql_type,
+                    nullable: (old_col.nullable || new_col.nullable) && !is_key,
+                };
+                statements.push(builder.modify_column(table, &column));
+            }
+            SchemaChange::ColumnRemoved(_) | SchemaChange::NoChange => {}
+        }
+    }
+
+    statements
+}
+
+/// 源列对应的目标列
+///
+/// column_mapping 为空或只有同名映射（镜像表）时，未映射的新列按同名添加。
+fn target_column(column_mapping: &BTreeMap<String, String>, source_col: &str) -> Option<String> {
+    if let Some((target, _)) = column_mapping.iter().find(|(_, s)| *s == source_col) {
+        return Some(target.clone());
+    }
+    column_mapping
+        .iter()
+        .all(|(target, source)| target == source)
+        .then(|| source_col.to_string())
+}
+
+/// `string` 与 `text` 视为同一种逻辑类型
+fn same_logical(a: &str, b: &str) -> bool {
+    let is_text = |t: &str| matches!(t, "string" | "text");
+    a == b || (is_text(a) && is_text(b))
+}
+
+/// 按逻辑类型（整数和浮点再看原生类型宽度）翻译为目标库的列类型
+fn target_type(col: &ColumnInfo, target: DatabaseKind, is_key: bool) -> String {
+    let native = col.native_type.to_lowercase();
+    let pg = target == DatabaseKind::Postgres;
+
+    match col.logical_type.as_str() {
+        "int" => integer_type(&native, col.unsigned, target),
+        "float" => match (native.as_str(), pg) {
+            ("real" | "float4" | "float", true) => "REAL",
+            ("real" | "float4" | "float", false) => "FLOAT",
+            (_, true) => "DOUBLE PRECISION",
+            (_, false) => "DOUBLE",
+        }
+        .to_string(),
+        "bool" => "BOOLEAN".to_string(),
+        "decimal" => match (col.precision, col.scale, pg) {
+            (Some(p), s, true) => format!("NUMERIC({},{})", p, s.unwrap_or(0)),
+            (Some(p), s, false) => format!("DECIMAL({},{})", p, s.unwrap_or(0)),
+            (None, _, true) => "NUMERIC".to_string(),
+            (None, _, false) => "DECIMAL(65,30)".to_string(),
+        },
+        "date" => "DATE".to_string(),
+        "time" => "TIME".to_string(),
+        "timestamp" => match (pg, native.contains("time zone") || native == "timestamptz") {
+            (true, true) => "TIMESTAMPTZ",
+            (true, false) => "TIMESTAMP",
+            (false, _) => "DATETIME(6)",
+        }
+        .to_string(),
+        "json" => if pg { "JSONB" } else { "JSON" }.to_string(),
+        "bytes" => if pg { "BYTEA" } else { "LONGBLOB" }.to_string(),
+        // MySQL 的 TEXT 不能直接作为主键
+        _ if is_key && !pg => "VARCHAR(255)".to_string(),
+        _ => "TEXT".to_string(),
+    }
+}
+
+/// 按原生宽度选择整数类型，UNSIGNED 在 PostgreSQL 中升一级
+fn integer_type(native: &str, unsigned: bool, target: DatabaseKind) -> String {
+    let width = match native {
+        "tinyint" | "smallint" | "int2" | "smallserial" => 2,
+        "mediumint" | "int" | "integer" | "int4" | "serial" | "year" => 4,
+        _ => 8,
+    };
+
+    match target {
+        DatabaseKind::Postgres => match (width, unsigned) {
+            (2, false) => "SMALLINT",
+            (2, true) | (4, false) => "INTEGER",
+            (4, true) | (8, false) => "BIGINT",
+            _ => "NUMERIC(20,0)",
+        }
+        .to_string(),
+        DatabaseKind::Mysql => {
+            let base = match width {
+                2 => "SMALLINT",
+                4 => "INT",
+                _ => "BIGINT",
+            };
+            if unsigned {
+                format!("{} UNSIGNED", base)
+            } else {
+                base.to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::EvolutionChecker;
+
+    fn schema(db_kind: &str, columns: Vec<ColumnInfo>, keys: &[&str]) -> TableSchema {
+        TableSchema::new("orders".to_string(), db_kind.to_string())
+            .with_columns(columns)
+            .with_primary_keys(keys.iter().map(|k| k.to_string()).collect())
+    }
+
+    fn column(name: &str, native: &str, logical: &str) -> ColumnInfo {
+        ColumnInfo::new(name.to_string(), native.to_string(), logical.to_string())
+    }
+
+    #[test]
+    fn mysql_schema_to_postgres() {
+        let mut id = column("id", "int", "int");
+        id.unsigned = true;
+        let source = schema(
+            "mysql",
+            vec![
+                id.with_nullable(false),
+                column("amount", "decimal", "decimal")
+                    .with_precision(10)
+                    .with_scale(2),
+                column("created_at", "datetime", "timestamp").with_nullable(false),
+                column("payload", "json", "json"),
+                column("note", "varchar", "text"),
+            ],
+            &["id"],
+        );
+
+        assert_eq!(
+            create_table_sql(&source, DatabaseKind::Postgres, "orders"),
+            "CREATE TABLE IF NOT EXISTS orders (\"id\" BIGINT NOT NULL, \
+             \"amount\" NUMERIC(10,2), \"created_at\" TIMESTAMP NOT NULL, \
+             \"payload\" JSONB, \"note\" TEXT, PRIMARY KEY (\"id\"))"
+        );
+    }
+
+    #[test]
+    fn target_schema_follows_column_mapping() {
+        let source = schema(
+            "mysql",
+            vec![
+                column("id", "int", "int").with_nullable(false),
+                column("amount", "decimal", "decimal")
+                    .with_precision(12)
+                    .with_scale(4),
+                column("created_at", "datetime", "timestamp"),
+            ],
+            &["id"],
+        );
+        let mapping: BTreeMap<String, String> = [
+            ("order_id", "id"),
+            ("total", "amount"),
+            ("created", "created_at"),
+            ("source", "'erp'"),
+        ]
+        .into_iter()
+        .map(|(t, s)| (t.to_string(), s.to_string()))
+        .collect();
+        let types: BTreeMap<String, String> = [("created", "string")]
+            .into_iter()
+            .map(|(t, s)| (t.to_string(), s.to_string()))
+            .collect();
+
+        let target = map_to_target_schema(&source, "ods_orders", &mapping, Some(&types), &[]);
+        assert_eq!(target.primary_keys, vec!["order_id".to_string()]);
+        assert_eq!(
+            create_table_sql(&target, DatabaseKind::Postgres, "ods_orders"),
+            "CREATE TABLE IF NOT EXISTS ods_orders (\"created\" TEXT, \
+             \"order_id\" INTEGER NOT NULL, \"source\" TEXT, \"total\" NUMERIC(12,4), \
+             PRIMARY KEY (\"order_id\"))"
+        );
+    }
+
+    #[test]
+    fn evolution_ddl_applies_safe_changes() {
+        let old = schema(
+            "mysql",
+            vec![
+                column("id", "int", "int").with_nullable(false),
+                column("qty", "smallint", "int").with_nullable(false),
+                column("note", "varchar", "text"),
+            ],
+            &["id"],
+        );
+        let new = schema(
+            "mysql",
+            vec![
+                column("id", "bigint", "int").with_nullable(false),
+                column("qty", "int", "int"),
+                column("note", "varchar", "text"),
+                column("email", "varchar", "text").with_nullable(false),
+            ],
+            &["id"],
+        );
+        let changes = EvolutionChecker::default().detect_changes(&old, &new);
+
+        let identity = old.to_column_mapping();
+        assert_eq!(
+            evolution_ddl(
+                &old,
+                &new,
+                &changes,
+                &identity,
+                DatabaseKind::Postgres,
+                "orders"
+            ),
+            vec![
+                "ALTER TABLE orders ALTER COLUMN \"id\" TYPE BIGINT".to_string(),
+                "ALTER TABLE orders ALTER COLUMN \"qty\" TYPE INTEGER, \
+                 ALTER COLUMN \"qty\" DROP NOT NULL"
+                    .to_string(),
+                "ALTER TABLE orders ADD COLUMN \"email\" TEXT".to_string(),
+            ]
+        );
+
+        // 重命名映射下未映射的新列不添加
+        let renamed: BTreeMap<String, String> = [("order_id", "id"), ("amount", "qty")]
+            .into_iter()
+            .map(|(t, s)| (t.to_string(), s.to_string()))
+            .collect();
+        assert_eq!(
+            evolution_ddl(
+                &old,
+                &new,
+                &changes,
+                &renamed,
+                DatabaseKind::Mysql,
+                "orders"
+            ),
+            vec![
+                "ALTER TABLE orders MODIFY COLUMN order_id BIGINT NOT NULL".to_string(),
+                "ALTER TABLE orders MODIFY COLUMN amount INT NULL".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn postgres_schema_to_mysql() {
+        let source = schema(
+            "postgres",
+            vec![
+                column("code", "character varying", "text"),
+                column("seen_at", "timestamp with time zone", "timestamp"),
+                column("raw", "bytea", "bytes"),
+                column("ratio", "real", "float"),
+            ],
+            &["code"],
+        );
+
+        assert_eq!(
+            create_table_sql(&source, DatabaseKind::Mysql, "orders"),
+            "CREATE TABLE IF NOT EXISTS orders (code VARCHAR(255) NOT NULL, \
+             seen_at DATETIME(6), raw LONGBLOB, ratio FLOAT, PRIMARY KEY (code))"
+        );
+    }
+}
@@ -33,7 +33,7 @@ impl EvolutionChecker {
 
     /// 比较两个 Schema 的兼容性
     fn compare_schemas(&self, old: &TableSchema, new: &TableSchema) -> CompatibilityResult {
-        let changes = self.detect_all_changes(old, new);
+        let changes = self.detect_changes(old, new);
 
         if changes.is_empty() {
             return CompatibilityResult::Compatible;
@@ -57,13 +57,18 @@ impl EvolutionChecker {
                 SchemaChange::ColumnRemoved(name) => {
                     breaking_changes.push(format!("Column '{}' removed", name));
                 }
-                // 类型变更 - 通常破坏性
+                // 类型变更 - 只有放宽（如 int -> bigint）兼容
                 SchemaChange::ColumnTypeChanged {
                     column_name,
                     old_type,
                     new_type,
                 } => {
-                    if self.is_type_compatible(old_type, new_type) {
+                    let widening = match (old.get_column(column_name), new.get_column(column_name))
+                    {
+                        (Some(old_col), Some(new_col)) => is_widening(old_col, new_col),
+                        _ => false,
+                    };
+                    if widening {
                         forward_compatible.push(change.clone());
                     } else {
                         breaking_changes.push(format!(
@@ -127,68 +132,117 @@ impl EvolutionChecker {
         CompatibilityResult::Compatible
     }
 
-    /// 检测所有变更
-    fn detect_all_changes(&self, old: &TableSchema, new: &TableSchema) -> Vec<SchemaChange> {
+    /// 检测所有变更，按新表的列顺序输出，删除的列排在最后
+    ///
+    /// 逻辑类型相同但原生类型或精度不同（如 int -> bigint）也视为类型变更。
+    pub fn detect_changes(&self, old: &TableSchema, new: &TableSchema) -> Vec<SchemaChange> {
         let mut changes = Vec::new();
 
-        let old_columns: std::collections::HashMap<&str, &ColumnInfo> =
-            old.columns.iter().map(|c| (c.name.as_str(), c)).collect();
+        for new_col in &new.columns {
+            let Some(old_col) = old.get_column(&new_col.name) else {
+                // 新增列
+                changes.push(SchemaChange::ColumnAdded(new_col.clone()));
+                continue;
+            };
 
-        let new_columns: std::collections::HashMap<&str, &ColumnInfo> =
-            new.columns.iter().map(|c| (c.name.as_str(), c)).collect();
+            // 类型变更和可空性变更
+            let (old_type, new_type) = (old_col.type_label(), new_col.type_label());
+            if old_col.logical_type != new_col.logical_type || old_type != new_type {
+                changes.push(SchemaChange::ColumnTypeChanged {
+                    column_name: new_col.name.clone(),
+                    old_type,
+                    new_type,
+                });
+            }
 
-        // 新增列
-        for (name, col) in &new_columns {
-            if !old_columns.contains_key(*name) {
-                changes.push(SchemaChange::ColumnAdded((*col).clone()));
+            if old_col.nullable != new_col.nullable {
+                changes.push(SchemaChange::NullabilityChanged {
+                    column_name: new_col.name.clone(),
+                    old_nullable: old_col.nullable,
+                    new_nullable: new_col.nullable,
+                });
             }
         }
 
         // 删除列
-        for name in old_columns.keys() {
-            if !new_columns.contains_key(*name) {
-                changes.push(SchemaChange::ColumnRemoved((*name).to_string()));
+        for old_col in &old.columns {
+            if new.get_column(&old_col.name).is_none() {
+                changes.push(SchemaChange::ColumnRemoved(old_col.name.clone()));
             }
         }
 
-        // 类型变更和可空性变更
-        for (name, new_col) in &new_columns {
-            if let Some(old_col) = old_columns.get(*name) {
-                if old_col.logical_type != new_col.logical_type {
-                    changes.push(SchemaChange::ColumnTypeChanged {
-                        column_name: (*name).to_string(),
-                        old_type: old_col.logical_type.clone(),
-                        new_type: new_col.logical_type.clone(),
-                    });
-                }
+        changes
+    }
+}
 
-                if old_col.nullable != new_col.nullable {
-                    changes.push(SchemaChange::NullabilityChanged {
-                        column_name: (*name).to_string(),
-                        old_nullable: old_col.nullable,
-                        new_nullable: new_col.nullable,
-                    });
-                }
+/// 列类型变更是否为放宽：旧类型的任何值都能无损存入新类型
+///
+/// 支持整数位宽扩大、REAL -> DOUBLE、DECIMAL 整数位与小数位都不缩小、
+/// 变长字符串 -> TEXT 类；其他跨类型变更一律不视为放宽。
+pub fn is_widening(old: &ColumnInfo, new: &ColumnInfo) -> bool {
+    if old.logical_type != new.logical_type {
+        return false;
+    }
+    if old.type_label() == new.type_label() {
+        return true;
+    }
+
+    match old.logical_type.as_str() {
+        "int" => {
+            let (old_width, new_width) = (integer_width(old), integer_width(new));
+            match (old.unsigned, new.unsigned) {
+                (false, false) | (true, true) => new_width >= old_width,
+                // 无符号转有符号需要更宽的类型才能容纳上限
+                (true, false) => new_width > old_width,
+                // 有符号转无符号会丢失负数
+                (false, true) => false,
             }
         }
+        "float" => float_width(old) <= float_width(new),
+        "decimal" => match (old.precision, new.precision) {
+            // 新类型不限精度
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(old_p), Some(new_p)) => {
+                let (old_s, new_s) = (old.scale.unwrap_or(0), new.scale.unwrap_or(0));
+                new_s >= old_s && new_p.saturating_sub(new_s) >= old_p.saturating_sub(old_s)
+            }
+        },
+        "text" | "string" => match (text_rank(old), text_rank(new)) {
+            (Some(old_rank), Some(new_rank)) => new_rank >= old_rank,
+            _ => false,
+        },
+        _ => false,
+    }
+}
 
-        changes
+/// 整数原生类型的字节宽度
+fn integer_width(col: &ColumnInfo) -> u8 {
+    match col.native_type.to_lowercase().as_str() {
+        "tinyint" => 1,
+        "smallint" | "int2" | "smallserial" => 2,
+        "mediumint" => 3,
+        "int" | "integer" | "int4" | "serial" | "year" => 4,
+        _ => 8,
     }
+}
 
-    /// 检查类型是否兼容
-    fn is_type_compatible(&self, old_type: &str, new_type: &str) -> bool {
-        // 类型扩展（如 int -> bigint）通常兼容
-        // 类型缩小（如 bigint -> int）通常不兼容
-        match (old_type, new_type) {
-            // 同类型
-            (a, b) if a == b => true,
-            // 整数扩展
-            ("int", "int") => true,
-            // 浮点扩展
-            ("float", "float") => true,
-            // 其他情况视为不兼容
-            _ => false,
-        }
+fn float_width(col: &ColumnInfo) -> u8 {
+    match col.native_type.to_lowercase().as_str() {
+        "real" | "float4" | "float" => 4,
+        _ => 8,
+    }
+}
+
+/// 字符串类型的容量等级，不认识的类型返回 None
+fn text_rank(col: &ColumnInfo) -> Option<u8> {
+    match col.native_type.to_lowercase().as_str() {
+        "tinytext" => Some(1),
+        "char" | "character" | "bpchar" | "varchar" | "character varying" => Some(2),
+        "text" => Some(3),
+        "mediumtext" => Some(4),
+        "longtext" => Some(5),
+        _ => None,
     }
 }
 
@@ -269,6 +323,64 @@ mod tests {
         assert!(matches!(result, CompatibilityResult::Breaking { .. }));
     }
 
+    #[test]
+    fn test_widening_native_type_is_forward_compatible() {
+        let checker = EvolutionChecker::new(true);
+        let mut old = create_test_schema(vec![("id", "int", false), ("amount", "decimal", true)]);
+        old.columns[1].precision = Some(10);
+        old.columns[1].scale = Some(2);
+        let mut new = old.clone();
+        new.columns[0].native_type = "bigint".to_string();
+        new.columns[1].precision = Some(12);
+        new.columns[1].scale = Some(4);
+
+        let changes = checker.detect_changes(&old, &new);
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(
+            checker.check(Some(&old), &new),
+            CompatibilityResult::ForwardCompatible { .. }
+        ));
+
+        // 反过来是收窄
+        assert!(checker.check(Some(&new), &old).is_breaking());
+    }
+
+    #[test]
+    fn test_is_widening() {
+        let column = |native: &str, logical: &str| {
+            ColumnInfo::new("c".to_string(), native.to_string(), logical.to_string())
+        };
+        let mut unsigned_int = column("int", "int");
+        unsigned_int.unsigned = true;
+
+        assert!(is_widening(
+            &column("smallint", "int"),
+            &column("int", "int")
+        ));
+        assert!(!is_widening(
+            &column("bigint", "int"),
+            &column("int", "int")
+        ));
+        assert!(is_widening(&unsigned_int, &column("bigint", "int")));
+        assert!(!is_widening(&unsigned_int, &column("int", "int")));
+        assert!(is_widening(
+            &column("real", "float"),
+            &column("double precision", "float")
+        ));
+        assert!(is_widening(
+            &column("varchar", "text"),
+            &column("text", "text")
+        ));
+        assert!(!is_widening(
+            &column("text", "text"),
+            &column("varchar", "text")
+        ));
+        assert!(!is_widening(
+            &column("int", "int"),
+            &column("varchar", "text")
+        ));
+    }
+
     #[test]
     fn test_new_schema_compatible() {
         let checker = EvolutionChecker::new(true);
@@ -37,7 +37,7 @@ impl RdbmsDiscoverer {
         }
     }
 
-    async fn query_columns_postgres(&self) -> Result<Vec<ColumnInfo>> {
+    async fn query_columns_postgres(&self, table_name: &str) -> Result<Vec<ColumnInfo>> {
         let sql = format!(
             r#"
             SELECT
@@ -46,14 +46,14 @@ impl RdbmsDiscoverer {
                 c.is_nullable,
                 c.ordinal_position,
                 c.character_maximum_length,
-                c.numeric_precision,
-                c.numeric_scale,
+                c.numeric_precision::int4 AS numeric_precision,
+                c.numeric_scale::int4 AS numeric_scale,
                 c.column_default
             FROM information_schema.columns c
             WHERE c.table_name = '{}'
             ORDER BY c.ordinal_position
             "#,
-            self.table_name
+            table_name
         );
 
         // 使用 sqlx 直接查询
@@ -83,9 +83,16 @@ impl RdbmsDiscoverer {
             let nullable: String = row.try_get("is_nullable")?;
             let ordinal: i32 = row.try_get("ordinal_position")?;
             let default_value: Option<String> = row.try_get("column_default")?;
+            let numeric_precision: Option<i32> = row.try_get("numeric_precision")?;
+            let numeric_scale: Option<i32> = row.try_get("numeric_scale")?;
 
             let logical_type = self.type_mapper.map_to_logical(&native_type);
-            let (precision, scale) = self.type_mapper.extract_precision_scale(&native_type);
+            let (precision, scale) = decimal_precision_scale(
+                &logical_type,
+                numeric_precision.map(i64::from),
+                numeric_scale.map(i64::from),
+                self.type_mapper.extract_precision_scale(&native_type),
+            );
 
             columns.push(ColumnInfo {
                 name,
@@ -97,37 +104,46 @@ impl RdbmsDiscoverer {
                 ordinal: ordinal as u32,
                 is_primary_key: false, // 将在后续步骤设置
                 default_value,
+                unsigned: false,
+                enum_values: Vec::new(),
             });
         }
 
         Ok(columns)
     }
 
-    async fn query_columns_mysql(&self) -> Result<Vec<ColumnInfo>> {
-        let sql = format!(
-            r#"
+    /// 查询 MySQL 列信息；`database` 为空时使用连接的默认库
+    async fn query_columns_mysql(
+        &self,
+        database: Option<&str>,
+        table_name: &str,
+    ) -> Result<Vec<ColumnInfo>> {
+        let sql = r#"
             SELECT
                 c.COLUMN_NAME as column_name,
                 c.DATA_TYPE as data_type,
+                c.COLUMN_TYPE as column_type,
+                c.COLUMN_KEY as column_key,
                 c.IS_NULLABLE as is_nullable,
                 c.ORDINAL_POSITION as ordinal_position,
                 c.CHARACTER_MAXIMUM_LENGTH as character_maximum_length,
-                c.NUMERIC_PRECISION as numeric_precision,
-                c.NUMERIC_SCALE as numeric_scale,
+                CAST(c.NUMERIC_PRECISION AS SIGNED) as numeric_precision,
+                CAST(c.NUMERIC_SCALE AS SIGNED) as numeric_scale,
                 c.COLUMN_DEFAULT as column_default
             FROM information_schema.COLUMNS c
-            WHERE c.TABLE_NAME = '{}'
-            AND c.TABLE_SCHEMA = DATABASE()
+            WHERE c.TABLE_NAME = ?
+            AND c.TABLE_SCHEMA = COALESCE(?, DATABASE())
             ORDER BY c.ORDINAL_POSITION
-            "#,
-            self.table_name
-        );
+            "#;
 
         let columns = match self.pool.as_ref() {
             RdbmsPool::Postgres(_) => {
                 bail!("MySQL pool expected")
             }
-            RdbmsPool::Mysql(p) => self.query_columns_sqlx_my(p, &sql).await?,
+            RdbmsPool::Mysql(p) => {
+                self.query_columns_sqlx_my(p, sql, database, table_name)
+                    .await?
+            }
         };
 
         Ok(columns)
@@ -137,21 +153,37 @@ impl RdbmsDiscoverer {
         &self,
         pool: &sqlx::MySqlPool,
         sql: &str,
+        database: Option<&str>,
+        table_name: &str,
     ) -> Result<Vec<ColumnInfo>> {
         use sqlx::Row;
 
-        let rows = sqlx::query(sql).fetch_all(pool).await?;
+        let rows = sqlx::query(sql)
+            .bind(table_name)
+            .bind(database)
+            .fetch_all(pool)
+            .await?;
 
         let mut columns = Vec::new();
         for row in rows {
             let name: String = row.try_get("column_name")?;
             let native_type: String = row.try_get("data_type")?;
+            let column_type: String = row.try_get("column_type")?;
+            let column_key: String = row.try_get("column_key")?;
             let nullable: String = row.try_get("is_nullable")?;
             let ordinal: u32 = row.try_get("ordinal_position")?;
             let default_value: Option<String> = row.try_get("column_default")?;
+            let numeric_precision: Option<i64> = row.try_get("numeric_precision")?;
+            let numeric_scale: Option<i64> = row.try_get("numeric_scale")?;
 
             let logical_type = self.type_mapper.map_to_logical(&native_type);
-            let (precision, scale) = self.type_mapper.extract_precision_scale(&native_type);
+            let (precision, scale) = decimal_precision_scale(
+                &logical_type,
+                numeric_precision,
+                numeric_scale,
+                self.type_mapper.extract_precision_scale(&column_type),
+            );
+            let (unsigned, enum_values) = parse_mysql_column_type(&column_type);
 
             columns.push(ColumnInfo {
                 name,
@@ -161,15 +193,44 @@ impl RdbmsDiscoverer {
                 precision,
                 scale,
                 ordinal,
-                is_primary_key: false,
+                is_primary_key: column_key == "PRI",
                 default_value,
+                unsigned,
+                enum_values,
             });
         }
 
         Ok(columns)
     }
 
-    async fn query_primary_keys(&self) -> Result<Vec<String>> {
+    /// 发现 MySQL 指定库下表的结构
+    ///
+    /// binlog 的 TableMapEvent 只携带库名和表名，列名、ENUM/SET 标签和符号位
+    /// 需要从 information_schema 按序号补齐。
+    pub async fn discover_mysql_table(&self, database: &str, table: &str) -> Result<TableSchema> {
+        if self.db_kind != DatabaseKind::Mysql {
+            bail!("discover_mysql_table 只支持 MySQL 连接");
+        }
+
+        let columns = self.query_columns_mysql(Some(database), table).await?;
+        if columns.is_empty() {
+            bail!("Table '{}.{}' not found or has no columns", database, table);
+        }
+
+        let primary_keys = columns
+            .iter()
+            .filter(|col| col.is_primary_key)
+            .map(|col| col.name.clone())
+            .collect();
+
+        Ok(
+            TableSchema::new(format!("{}.{}", database, table), "mysql".to_string())
+                .with_columns(columns)
+                .with_primary_keys(primary_keys),
+        )
+    }
+
+    async fn query_primary_keys(&self, table_name: &str) -> Result<Vec<String>> {
         let sql = match self.db_kind {
             DatabaseKind::Postgres => format!(
                 r#"
@@ -183,7 +244,7 @@ impl RdbmsDiscoverer {
                 AND tc.table_schema = 'public'
                 ORDER BY kcu.ordinal_position
                 "#,
-                self.table_name
+                table_name
             ),
             DatabaseKind::Mysql => format!(
                 r#"
@@ -191,9 +252,10 @@ impl RdbmsDiscoverer {
                 FROM information_schema.KEY_COLUMN_USAGE
                 WHERE CONSTRAINT_NAME = 'PRIMARY'
                     AND TABLE_NAME = '{}'
+                    AND TABLE_SCHEMA = DATABASE()
                 ORDER BY ORDINAL_POSITION
                 "#,
-                self.table_name
+                table_name
             ),
         };
 
@@ -239,8 +301,8 @@ impl MetadataDiscoverer for RdbmsDiscoverer {
 
         // 查询列信息
         let mut columns = match self.db_kind {
-            DatabaseKind::Postgres => self.query_columns_postgres().await?,
-            DatabaseKind::Mysql => self.query_columns_mysql().await?,
+            DatabaseKind::Postgres => self.query_columns_postgres(table_name).await?,
+            DatabaseKind::Mysql => self.query_columns_mysql(None, table_name).await?,
         };
 
         if columns.is_empty() {
@@ -248,7 +310,7 @@ impl MetadataDiscoverer for RdbmsDiscoverer {
         }
 
         // 查询主键
-        let primary_keys = self.query_primary_keys().await?;
+        let primary_keys = self.query_primary_keys(table_name).await?;
         let pk_set: std::collections::HashSet<_> = primary_keys.iter().cloned().collect();
 
         // 设置主键标记
@@ -285,10 +347,12 @@ impl MetadataDiscoverer for RdbmsDiscoverer {
     async fn table_exists(&self, table_name: &str) -> Result<bool> {
         let sql = match self.db_kind {
             DatabaseKind::Postgres => {
-                "SELECT 1 FROM information_schema.tables WHERE table_name = $1 LIMIT 1"
+                "SELECT 1 FROM information_schema.tables \
+                 WHERE table_name = $1 AND table_schema = ANY (current_schemas(false)) LIMIT 1"
             }
             DatabaseKind::Mysql => {
-                "SELECT 1 FROM information_schema.TABLES WHERE TABLE_NAME = ? LIMIT 1"
+                "SELECT 1 FROM information_schema.TABLES \
+                 WHERE TABLE_NAME = ? AND TABLE_SCHEMA = DATABASE() LIMIT 1"
             }
         };
 
@@ -301,7 +365,7 @@ impl MetadataDiscoverer for RdbmsDiscoverer {
                 row.is_some()
             }
             RdbmsPool::Mysql(p) => {
-                let row: Option<(i32,)> = sqlx::query_as(sql)
+                let row: Option<(i64,)> = sqlx::query_as(sql)
                     .bind(table_name)
                     .fetch_optional(p)
                     .await?;
@@ -337,6 +401,86 @@ impl MetadataDiscoverer for RdbmsDiscoverer {
     }
 }
 
+/// DECIMAL 列的精度与标度：优先取 information_schema 的数值，缺失时从类型串解析；
+/// 其它列的 numeric_precision 是整数/浮点宽度，不保留
+fn decimal_precision_scale(
+    logical_type: &str,
+    numeric_precision: Option<i64>,
+    numeric_scale: Option<i64>,
+    parsed: (Option<u32>, Option<u32>),
+) -> (Option<u32>, Option<u32>) {
+    if logical_type != "decimal" {
+        return (None, None);
+    }
+    let to_u32 = |v: Option<i64>| v.and_then(|n| u32::try_from(n).ok());
+    (
+        to_u32(numeric_precision).or(parsed.0),
+        to_u32(numeric_scale).or(parsed.1),
+    )
+}
+
+/// 从 `decimal(10,2)` / `numeric(10)` 这样的类型串解析精度与标度
+fn parse_precision_scale(type_str: &str) -> (Option<u32>, Option<u32>) {
+    let Some(args) = type_str
+        .split_once('(')
+        .and_then(|(_, rest)| rest.split_once(')'))
+        .map(|(args, _)| args)
+    else {
+        return (None, None);
+    };
+    let mut parts = args.split(',').map(|p| p.trim().parse::<u32>().ok());
+    let precision = parts.next().flatten();
+    let scale = parts.next().flatten();
+    (precision, scale)
+}
+
+/// 解析 MySQL `COLUMN_TYPE`，返回 (是否 UNSIGNED, ENUM/SET 标签)
+///
+/// 例如 `int(10) unsigned` → (true, [])，`enum('a','b')` → (false, ["a", "b"])。
+pub fn parse_mysql_column_type(column_type: &str) -> (bool, Vec<String>) {
+    let lower = column_type.to_lowercase();
+    let unsigned = lower.contains(" unsigned");
+
+    let is_enum_or_set = lower.starts_with("enum(") || lower.starts_with("set(");
+    let labels = match (
+        is_enum_or_set,
+        column_type.find('('),
+        column_type.rfind(')'),
+    ) {
+        (true, Some(start), Some(end)) if start < end => {
+            parse_quoted_list(&column_type[start + 1..end])
+        }
+        _ => Vec::new(),
+    };
+
+    (unsigned, labels)
+}
+
+/// 解析 `'a','b''c'` 形式的单引号列表（`''` 为转义的单引号）
+fn parse_quoted_list(list: &str) -> Vec<String> {
+    let mut labels = Vec::new();
+    let mut current = String::new();
+    let mut in_quote = false;
+    let mut chars = list.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quote) {
+            ('\'', true) if chars.peek() == Some(&'\'') => {
+                current.push('\'');
+                chars.next();
+            }
+            ('\'', true) => {
+                labels.push(std::mem::take(&mut current));
+                in_quote = false;
+            }
+            ('\'', false) => in_quote = true,
+            (c, true) => current.push(c),
+            _ => {}
+        }
+    }
+    labels
+}
+
 /// PostgreSQL 类型映射器
 struct PostgresTypeMapper;
 
@@ -378,11 +522,21 @@ impl TypeMapper for PostgresTypeMapper {
             return "decimal".to_string();
         }
 
-        // Timestamp
-        if upper.starts_with("TIMESTAMP") || upper.starts_with("DATE") || upper.starts_with("TIME")
-        {
+        // Date / Time / Timestamp
+        if upper.starts_with("TIMESTAMP") {
             return "timestamp".to_string();
         }
+        if upper == "DATE" {
+            return "date".to_string();
+        }
+        if upper.starts_with("TIME") {
+            return "time".to_string();
+        }
+
+        // Binary
+        if upper == "BYTEA" {
+            return "bytes".to_string();
+        }
 
         // JSON
         if matches!(upper.as_str(), "JSON" | "JSONB") {
@@ -393,8 +547,8 @@ impl TypeMapper for PostgresTypeMapper {
         "text".to_string()
     }
 
-    fn extract_precision_scale(&self, _native_type: &str) -> (Option<u32>, Option<u32>) {
-        (None, None)
+    fn extract_precision_scale(&self, native_type: &str) -> (Option<u32>, Option<u32>) {
+        parse_precision_scale(native_type)
     }
 }
 
@@ -428,12 +582,21 @@ impl TypeMapper for MysqlTypeMapper {
             return "decimal".to_string();
         }
 
-        // Timestamp
+        // Date / Time / Timestamp
+        match lower.as_str() {
+            "date" => return "date".to_string(),
+            "time" => return "time".to_string(),
+            "year" => return "int".to_string(),
+            "datetime" | "timestamp" => return "timestamp".to_string(),
+            _ => {}
+        }
+
+        // Binary
         if matches!(
             lower.as_str(),
-            "date" | "time" | "datetime" | "timestamp" | "year"
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob"
         ) {
-            return "timestamp".to_string();
+            return "bytes".to_string();
         }
 
         // JSON
@@ -445,8 +608,8 @@ impl TypeMapper for MysqlTypeMapper {
         "text".to_string()
     }
 
-    fn extract_precision_scale(&self, _native_type: &str) -> (Option<u32>, Option<u32>) {
-        (None, None)
+    fn extract_precision_scale(&self, native_type: &str) -> (Option<u32>, Option<u32>) {
+        parse_precision_scale(native_type)
     }
 }
 
@@ -464,6 +627,13 @@ mod tests {
         assert_eq!(mapper.map_to_logical("boolean"), "bool");
         assert_eq!(mapper.map_to_logical("numeric(10,2)"), "decimal");
         assert_eq!(mapper.map_to_logical("timestamp"), "timestamp");
+        assert_eq!(
+            mapper.map_to_logical("timestamp with time zone"),
+            "timestamp"
+        );
+        assert_eq!(mapper.map_to_logical("date"), "date");
+        assert_eq!(mapper.map_to_logical("time without time zone"), "time");
+        assert_eq!(mapper.map_to_logical("bytea"), "bytes");
         assert_eq!(mapper.map_to_logical("jsonb"), "json");
         assert_eq!(mapper.map_to_logical("varchar(255)"), "text");
     }
@@ -477,7 +647,50 @@ mod tests {
         assert_eq!(mapper.map_to_logical("double"), "float");
         assert_eq!(mapper.map_to_logical("decimal(10,2)"), "decimal");
         assert_eq!(mapper.map_to_logical("datetime"), "timestamp");
+        assert_eq!(mapper.map_to_logical("date"), "date");
+        assert_eq!(mapper.map_to_logical("year"), "int");
+        assert_eq!(mapper.map_to_logical("longblob"), "bytes");
         assert_eq!(mapper.map_to_logical("json"), "json");
         assert_eq!(mapper.map_to_logical("varchar"), "text");
     }
+
+    #[test]
+    fn test_parse_mysql_column_type() {
+        assert_eq!(parse_mysql_column_type("int(10) unsigned"), (true, vec![]));
+        assert_eq!(parse_mysql_column_type("bigint"), (false, vec![]));
+        assert_eq!(
+            parse_mysql_column_type("enum('new','paid','it''s done')"),
+            (
+                false,
+                vec![
+                    "new".to_string(),
+                    "paid".to_string(),
+                    "it's done".to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            parse_mysql_column_type("set('a,b','c')"),
+            (false, vec!["a,b".to_string(), "c".to_string()])
+        );
+    }
+    #[test]
+    fn test_decimal_precision_scale() {
+        assert_eq!(parse_precision_scale("decimal(10,2)"), (Some(10), Some(2)));
+        assert_eq!(parse_precision_scale("numeric(12)"), (Some(12), None));
+        assert_eq!(parse_precision_scale("numeric"), (None, None));
+
+        assert_eq!(
+            decimal_precision_scale("decimal", Some(18), Some(4), (None, None)),
+            (Some(18), Some(4))
+        );
+        assert_eq!(
+            decimal_precision_scale("decimal", None, None, (Some(10), Some(2))),
+            (Some(10), Some(2))
+        );
+        assert_eq!(
+            decimal_precision_scale("int", Some(19), Some(0), (None, None)),
+            (None, None)
+        );
+    }
 }
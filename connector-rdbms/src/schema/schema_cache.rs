@@ -6,7 +6,7 @@ use std::path::Path;
 use std::sync::Arc;
 use tracing::{debug, info};
 
-use super::{ColumnInfo, SchemaChange, TableSchema};
+use super::{EvolutionChecker, SchemaChange, TableSchema};
 
 const SCHEMA_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("schemas");
 const VERSION_TABLE: TableDefinition<&str, u64> = TableDefinition::new("versions");
@@ -135,7 +135,7 @@ impl SchemaCache {
                 Ok(vec![])
             }
             Some(old_schema) => {
-                let changes = self.compare_schemas(&old_schema, new_schema);
+                let changes = EvolutionChecker::default().detect_changes(&old_schema, new_schema);
                 if !changes.is_empty() {
                     info!(
                         "Detected {} schema changes for {}",
@@ -148,54 +148,6 @@ impl SchemaCache {
         }
     }
 
-    /// 比较两个 Schema 的差异
-    fn compare_schemas(&self, old: &TableSchema, new: &TableSchema) -> Vec<SchemaChange> {
-        let mut changes = Vec::new();
-
-        let old_columns: std::collections::HashMap<&str, &ColumnInfo> =
-            old.columns.iter().map(|c| (c.name.as_str(), c)).collect();
-
-        let new_columns: std::collections::HashMap<&str, &ColumnInfo> =
-            new.columns.iter().map(|c| (c.name.as_str(), c)).collect();
-
-        // 检查新增列
-        for (name, col) in &new_columns {
-            if !old_columns.contains_key(*name) {
-                changes.push(SchemaChange::ColumnAdded((*col).clone()));
-            }
-        }
-
-        // 检查删除列
-        for name in old_columns.keys() {
-            if !new_columns.contains_key(*name) {
-                changes.push(SchemaChange::ColumnRemoved((*name).to_string()));
-            }
-        }
-
-        // 检查类型变更
-        for (name, new_col) in &new_columns {
-            if let Some(old_col) = old_columns.get(*name) {
-                if old_col.logical_type != new_col.logical_type {
-                    changes.push(SchemaChange::ColumnTypeChanged {
-                        column_name: (*name).to_string(),
-                        old_type: old_col.logical_type.clone(),
-                        new_type: new_col.logical_type.clone(),
-                    });
-                }
-
-                if old_col.nullable != new_col.nullable {
-                    changes.push(SchemaChange::NullabilityChanged {
-                        column_name: (*name).to_string(),
-                        old_nullable: old_col.nullable,
-                        new_nullable: new_col.nullable,
-                    });
-                }
-            }
-        }
-
-        changes
-    }
-
     /// 检查表是否有缓存
     pub fn has_cache(&self, table_name: &str) -> Result<bool> {
         Ok(self.get_latest_version(table_name)?.is_some())
@@ -231,6 +183,7 @@ impl SchemaCache {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::ColumnInfo;
 
     #[test]
     fn test_in_memory_cache() {
@@ -0,0 +1,175 @@
+//! Staging 表整表重载
+//!
+//! 数据先写入按目标表结构创建的 staging 表，全部写入成功后替换目标表：
+//! MySQL 用一条 `RENAME TABLE` 同时改名，PostgreSQL 在同一事务内改名。
+//! 替换前读者始终看到完整的旧数据。
+//!
+//! PostgreSQL 的 `LIKE ... INCLUDING ALL` 会复制 serial 列的默认值，staging 表与目标表共用
+//! 同一个序列，序列归属目标表的列；替换时先把序列归属改到新表，再删除旧表。
+//!
+//! 限制：视图与外键按对象而不是表名引用目标表，替换后仍指向旧表，删除旧表会失败
+//! （MySQL 的外键同样跟随改名后的旧表），被视图或外键引用的表不能使用 staging。
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use crate::pool::{DatabaseKind, RdbmsPool};
+
+/// staging 表名中的标记，完整表名为 `<table>__relus_stage_<run>`
+pub const STAGING_MARKER: &str = "__relus_stage_";
+
+/// 替换时旧表改名使用的标记
+const RETIRED_MARKER: &str = "__relus_old_";
+
+/// 本次运行使用的 staging 表名
+pub fn staging_table_name(table: &str, run_id: &str) -> String {
+    format!("{}{}{}", table, STAGING_MARKER, run_id)
+}
+
+/// 先删除同名残留表，再按目标表结构建 staging 表（包含索引、约束与默认值）
+pub fn create_staging_sql(kind: DatabaseKind, table: &str, stage: &str) -> Vec<String> {
+    let create = match kind {
+        DatabaseKind::Postgres => format!("CREATE TABLE {} (LIKE {} INCLUDING ALL)", stage, table),
+        DatabaseKind::Mysql => format!("CREATE TABLE {} LIKE {}", stage, table),
+    };
+    vec![drop_staging_sql(stage), create]
+}
+
+/// 用 staging 表替换目标表并删除旧表
+///
+/// PostgreSQL 的 `RENAME TO` 只接受不带 schema 的新表名；删除旧表前把旧表列拥有的序列
+/// （serial 列）改为归属新表的同名列，否则 `DROP TABLE` 会因新表默认值依赖该序列而失败。
+pub fn swap_staging_sql(kind: DatabaseKind, table: &str, stage: &str) -> Vec<String> {
+    let retired = stage.replacen(STAGING_MARKER, RETIRED_MARKER, 1);
+    match kind {
+        DatabaseKind::Postgres => vec![
+            format!("ALTER TABLE {} RENAME TO {}", table, unqualified(&retired)),
+            format!("ALTER TABLE {} RENAME TO {}", stage, unqualified(table)),
+            transfer_owned_sequences_sql(&retired, table),
+            format!("DROP TABLE {}", retired),
+        ],
+        DatabaseKind::Mysql => vec![
+            format!(
+                "RENAME TABLE {} TO {}, {} TO {}",
+                table, retired, stage, table
+            ),
+            format!("DROP TABLE {}", retired),
+        ],
+    }
+}
+
+/// 把 `from` 表列拥有的序列改为归属 `to` 表的同名列（PostgreSQL）
+fn transfer_owned_sequences_sql(from: &str, to: &str) -> String {
+    format!(
+        "DO $$ DECLARE r record; BEGIN \
+         FOR r IN SELECT d.objid::regclass AS seq, a.attname AS col \
+         FROM pg_depend d \
+         JOIN pg_class s ON s.oid = d.objid AND s.relkind = 'S' \
+         JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid \
+         WHERE d.classid = 'pg_class'::regclass AND d.refclassid = 'pg_class'::regclass \
+         AND d.refobjid = '{}'::regclass AND d.deptype = 'a' \
+         LOOP EXECUTE format('ALTER SEQUENCE %s OWNED BY %s.%I', r.seq, '{}', r.col); END LOOP; \
+         END $$",
+        from.replace('\'', "''"),
+        to.replace('\'', "''")
+    )
+}
+
+pub fn drop_staging_sql(stage: &str) -> String {
+    format!("DROP TABLE IF EXISTS {}", stage)
+}
+
+fn unqualified(table: &str) -> &str {
+    table.rsplit('.').next().unwrap_or(table)
+}
+
+/// 创建本次运行的 staging 表
+pub async fn create_staging_table(pool: &RdbmsPool, table: &str, stage: &str) -> Result<()> {
+    let executor = pool.executor();
+    for sql in create_staging_sql(pool.kind(), table, stage) {
+        executor.execute(&sql).await?;
+    }
+    info!("[Staging] 目标表 {} 写入 staging 表 {}", table, stage);
+    Ok(())
+}
+
+/// 用 staging 表替换目标表；PostgreSQL 中改名与删除旧表在同一事务内完成
+pub async fn swap_staging_table(pool: &RdbmsPool, table: &str, stage: &str) -> Result<()> {
+    let executor = pool.executor();
+    let statements = swap_staging_sql(pool.kind(), table, stage);
+    let transactional = pool.kind() == DatabaseKind::Postgres;
+
+    if transactional {
+        executor.begin().await?;
+    }
+    for sql in &statements {
+        info!("[Staging] 执行: {}", sql);
+        if let Err(e) = executor.execute(sql).await {
+            if transactional {
+                if let Err(rollback_err) = executor.rollback().await {
+                    warn!("[Staging] 替换事务回滚失败: {}", rollback_err);
+                }
+            }
+            return Err(e);
+        }
+    }
+    if transactional {
+        executor.commit().await?;
+    }
+    info!("[Staging] staging 表 {} 已替换目标表 {}", stage, table);
+    Ok(())
+}
+
+/// 删除 staging 表（任务失败或取消时清理）
+pub async fn drop_staging_table(pool: &RdbmsPool, stage: &str) -> Result<()> {
+    pool.executor().execute(&drop_staging_sql(stage)).await?;
+    info!("[Staging] 已删除 staging 表 {}", stage);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_statements_per_database() {
+        let stage = staging_table_name("public.users", "20240102030405");
+        assert_eq!(stage, "public.users__relus_stage_20240102030405");
+        assert_eq!(
+            create_staging_sql(DatabaseKind::Postgres, "public.users", &stage)[1],
+            "CREATE TABLE public.users__relus_stage_20240102030405 (LIKE public.users INCLUDING ALL)"
+        );
+        let swap = swap_staging_sql(DatabaseKind::Postgres, "public.users", &stage);
+        assert_eq!(swap.len(), 4);
+        assert_eq!(
+            swap[..2],
+            [
+                "ALTER TABLE public.users RENAME TO users__relus_old_20240102030405",
+                "ALTER TABLE public.users__relus_stage_20240102030405 RENAME TO users",
+            ]
+        );
+        assert_eq!(swap[3], "DROP TABLE public.users__relus_old_20240102030405");
+
+        let stage = staging_table_name("users", "1");
+        assert_eq!(
+            swap_staging_sql(DatabaseKind::Mysql, "users", &stage),
+            vec![
+                "RENAME TABLE users TO users__relus_old_1, users__relus_stage_1 TO users",
+                "DROP TABLE users__relus_old_1",
+            ]
+        );
+    }
+
+    #[test]
+    fn serial_sequences_move_to_new_table_before_drop() {
+        // id serial 的默认值 nextval('users_id_seq') 被复制到 staging 表，序列仍归属旧表的 id 列
+        let stage = staging_table_name("users", "1");
+        let swap = swap_staging_sql(DatabaseKind::Postgres, "users", &stage);
+        let transfer = &swap[2];
+        assert!(transfer.starts_with("DO $$"));
+        assert!(transfer
+            .contains("AND d.refobjid = 'users__relus_old_1'::regclass AND d.deptype = 'a'"));
+        assert!(transfer.contains("'ALTER SEQUENCE %s OWNED BY %s.%I', r.seq, 'users', r.col"));
+        assert!(swap[3].starts_with("DROP TABLE users__relus_old_1"));
+    }
+}
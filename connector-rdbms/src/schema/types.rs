@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-use relus_common::constant::schema::DEFAULT_SCHEMA_CACHE_DIR;
+pub use relus_common::job_config::SchemaDiscoveryConfig;
 
 /// 表结构信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,7 +79,7 @@ pub struct ColumnInfo {
     pub name: String,
     /// 原生数据库类型
     pub native_type: String,
-    /// 逻辑类型 (int / float / bool / decimal / timestamp / json / text)
+    /// 逻辑类型 (int / float / bool / decimal / date / time / timestamp / json / bytes / text)
     pub logical_type: String,
     /// 是否可空
     pub nullable: bool,
@@ -93,6 +93,12 @@ pub struct ColumnInfo {
     pub is_primary_key: bool,
     /// 默认值
     pub default_value: Option<String>,
+    /// 是否为无符号整数 (MySQL UNSIGNED)
+    #[serde(default)]
+    pub unsigned: bool,
+    /// ENUM / SET 的取值标签，按定义顺序
+    #[serde(default)]
+    pub enum_values: Vec<String>,
 }
 
 impl ColumnInfo {
@@ -107,6 +113,8 @@ impl ColumnInfo {
             ordinal: 0,
             is_primary_key: false,
             default_value: None,
+            unsigned: false,
+            enum_values: Vec::new(),
         }
     }
 
@@ -129,6 +137,26 @@ impl ColumnInfo {
         self.is_primary_key = is_pk;
         self
     }
+
+    /// 用于比较和展示的类型描述：原生类型（为空时用逻辑类型），DECIMAL 附带精度
+    pub fn type_label(&self) -> String {
+        let base = if self.native_type.is_empty() {
+            self.logical_type.as_str()
+        } else {
+            self.native_type.as_str()
+        };
+        let mut label = match (self.precision, self.scale) {
+            (Some(p), Some(s)) if self.logical_type == "decimal" => {
+                format!("{}({},{})", base, p, s)
+            }
+            (Some(p), None) if self.logical_type == "decimal" => format!("{}({})", base, p),
+            _ => base.to_string(),
+        };
+        if self.unsigned {
+            label.push_str(" unsigned");
+        }
+        label
+    }
 }
 
 /// Schema 变更类型
@@ -154,6 +182,33 @@ pub enum SchemaChange {
     },
 }
 
+impl std::fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaChange::NoChange => write!(f, "无变更"),
+            SchemaChange::ColumnAdded(col) => {
+                write!(f, "新增列 {} ({})", col.name, col.type_label())
+            }
+            SchemaChange::ColumnRemoved(name) => write!(f, "删除列 {}", name),
+            SchemaChange::ColumnTypeChanged {
+                column_name,
+                old_type,
+                new_type,
+            } => write!(f, "列 {} 类型 {} -> {}", column_name, old_type, new_type),
+            SchemaChange::NullabilityChanged {
+                column_name,
+                new_nullable,
+                ..
+            } => write!(
+                f,
+                "列 {} 改为{}",
+                column_name,
+                if *new_nullable { "可空" } else { "非空" }
+            ),
+        }
+    }
+}
+
 /// 兼容性检查结果
 #[derive(Debug, Clone)]
 pub enum CompatibilityResult {
@@ -185,30 +240,6 @@ impl CompatibilityResult {
     }
 }
 
-/// Schema 发现配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SchemaDiscoveryConfig {
-    /// 是否启用自动发现
-    pub enabled: bool,
-    /// 缓存目录
-    pub cache_dir: Option<String>,
-    /// 是否检测 Schema 演进
-    pub check_evolution: bool,
-    /// 破坏性变更时是否中断任务
-    pub break_on_incompatible: bool,
-}
-
-impl Default for SchemaDiscoveryConfig {
-    fn default() -> Self {
-        Self {
-            enabled: false,
-            cache_dir: Some(DEFAULT_SCHEMA_CACHE_DIR.to_string()),
-            check_evolution: true,
-            break_on_incompatible: true,
-        }
-    }
-}
-
 /// 类型映射规则
 #[derive(Debug, Clone)]
 pub struct TypeMappingRule {
@@ -22,9 +22,11 @@
 //! - `execute_rdbms_write`：writer 调用的统一执行入口。
 
 use anyhow::{bail, Result};
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
+use relus_common::data_source_config::HistoryColumns;
 use relus_common::job_config::WriteMode;
 use relus_common::types::UnifiedValue;
 
@@ -44,6 +46,15 @@ pub trait SqlBackend {
     /// 当前后端执行 upsert 时是否必须显式指定冲突列。
     const REQUIRES_UPSERT_KEYS: bool;
 
+    /// 单条 SQL 允许的最大绑定参数个数。
+    const MAX_BIND_PARAMS: usize;
+
+    /// 当前后端是否支持 `TABLESAMPLE` 采样。
+    const SUPPORTS_TABLESAMPLE: bool;
+
+    /// NULL 安全的相等比较运算符，两侧都为 NULL 时为真。
+    const NULL_SAFE_EQ: &'static str;
+
     /// 将逻辑 identifier 转成当前后端可用的 SQL identifier。
     fn identifier(identifier: &str) -> String;
 
@@ -59,6 +70,9 @@ pub trait SqlBackend {
 
 impl SqlBackend for PostgresBackend {
     const REQUIRES_UPSERT_KEYS: bool = true;
+    const MAX_BIND_PARAMS: usize = u16::MAX as usize;
+    const SUPPORTS_TABLESAMPLE: bool = true;
+    const NULL_SAFE_EQ: &'static str = " IS NOT DISTINCT FROM ";
 
     fn identifier(identifier: &str) -> String {
         identifier
@@ -99,6 +113,9 @@ impl SqlBackend for PostgresBackend {
 
 impl SqlBackend for MysqlBackend {
     const REQUIRES_UPSERT_KEYS: bool = false;
+    const MAX_BIND_PARAMS: usize = u16::MAX as usize;
+    const SUPPORTS_TABLESAMPLE: bool = false;
+    const NULL_SAFE_EQ: &'static str = " <=> ";
 
     fn identifier(identifier: &str) -> String {
         identifier.to_string()
@@ -174,6 +191,67 @@ pub struct PkRangeSelect<'a> {
     pub inclusive_end: bool,
 }
 
+/// Keyset 分页查询参数。
+///
+/// 用于 reader 按（复合）有序键拆分任务时生成形如
+/// `(k1, k2) > (lower) AND (k1, k2) <= (upper) ORDER BY k1, k2` 的查询。
+/// 边界值是按 `keys` 顺序渲染好的 SQL 字面量，缺省表示该侧不设界。
+#[derive(Debug, Clone, Copy)]
+pub struct KeysetSelect<'a> {
+    /// 查询表名。
+    pub table: &'a str,
+    /// 查询列列表，通常来自 reader 配置。
+    pub columns: &'a str,
+    /// 额外 where 条件，会和键范围条件用 AND 拼接。
+    pub where_clause: Option<&'a str>,
+    /// 有序键列。
+    pub keys: &'a [String],
+    /// 下界（不含）。
+    pub lower: Option<&'a [String]>,
+    /// 上界（含）。
+    pub upper: Option<&'a [String]>,
+}
+
+/// 水位增量查询参数。
+///
+/// 按 `(column, keys...)` 排序，同一水位值内的行按 `keys` 确定先后，
+/// 从复合下界继续读取时不会跳过与上次水位相等的行。
+#[derive(Debug, Clone, Copy)]
+pub struct WatermarkSelect<'a> {
+    /// 查询表名。
+    pub table: &'a str,
+    /// 查询列列表，通常来自 reader 配置。
+    pub columns: &'a str,
+    /// 额外 where 条件，会和水位条件用 AND 拼接。
+    pub where_clause: Option<&'a str>,
+    /// 水位列。
+    pub column: &'a str,
+    /// 同一水位值内的排序键。
+    pub keys: &'a [String],
+    /// 下界，缺省表示全量读取。
+    pub lower: Option<WatermarkLower<'a>>,
+}
+
+/// 水位增量查询的下界，值为渲染好的 SQL 字面量。
+#[derive(Debug, Clone, Copy)]
+pub enum WatermarkLower<'a> {
+    /// `(column, keys...) > (values...)`（不含），按 `(column, keys...)` 顺序给值。
+    After(&'a [String]),
+    /// `column >= value`（含），重读该水位上的所有行。
+    From(&'a str),
+}
+
+/// 建表语句中的一列。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDef {
+    /// 列名。
+    pub name: String,
+    /// 目标端列类型，已按方言翻译。
+    pub sql_type: String,
+    /// 是否可空。
+    pub nullable: bool,
+}
+
 impl<DB> Default for SqlBuilder<DB> {
     fn default() -> Self {
         Self {
@@ -231,10 +309,90 @@ impl<DB: SqlBackend> SqlBuilder<DB> {
         SelectSqlBuilder::<DB>::limit_offset_select(table, columns, where_clause, limit, offset)
     }
 
+    pub fn keyset_select(&self, range: KeysetSelect<'_>) -> String {
+        SelectSqlBuilder::<DB>::keyset_select(range)
+    }
+
+    pub fn keyset_boundary(
+        &self,
+        table: &str,
+        keys: &[String],
+        where_clause: Option<&str>,
+        lower: Option<&[String]>,
+        offset: usize,
+    ) -> String {
+        SelectSqlBuilder::<DB>::keyset_boundary(table, keys, where_clause, lower, offset)
+    }
+
+    pub fn watermark_select(&self, select: WatermarkSelect<'_>) -> String {
+        SelectSqlBuilder::<DB>::watermark_select(select)
+    }
+
+    pub fn null_keys_select(
+        &self,
+        table: &str,
+        columns: &str,
+        where_clause: Option<&str>,
+        keys: &[String],
+    ) -> String {
+        SelectSqlBuilder::<DB>::null_keys_select(table, columns, where_clause, keys)
+    }
+
+    /// 按百分比采样有序键；后端不支持 `TABLESAMPLE` 时返回 `None`
+    pub fn key_sample(
+        &self,
+        table: &str,
+        keys: &[String],
+        where_clause: Option<&str>,
+        percent: f64,
+    ) -> Option<String> {
+        DB::SUPPORTS_TABLESAMPLE
+            .then(|| SelectSqlBuilder::<DB>::key_sample(table, keys, where_clause, percent))
+    }
+
     pub fn literal_string(value: &str) -> String {
         format!("'{}'", value.replace('\'', "''"))
     }
 
+    /// `CREATE TABLE IF NOT EXISTS`，主键非空时追加 `PRIMARY KEY`
+    pub fn create_table(
+        &self,
+        table: &str,
+        columns: &[ColumnDef],
+        primary_keys: &[String],
+    ) -> String {
+        let mut defs: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                let mut def = format!("{} {}", DB::identifier(&column.name), column.sql_type);
+                if !column.nullable {
+                    def.push_str(" NOT NULL");
+                }
+                def
+            })
+            .collect();
+        if !primary_keys.is_empty() {
+            let keys: Vec<String> = primary_keys.iter().map(|k| DB::identifier(k)).collect();
+            defs.push(format!("PRIMARY KEY ({})", keys.join(", ")));
+        }
+        // 表名与写入 SQL 一致不加引号，避免大小写折叠不一致
+        format!("CREATE TABLE IF NOT EXISTS {} ({})", table, defs.join(", "))
+    }
+
+    /// `ALTER TABLE ... ADD COLUMN`
+    pub fn add_column(&self, table: &str, column: &ColumnDef) -> String {
+        let mut sql = format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table,
+            DB::identifier(&column.name),
+            column.sql_type
+        );
+        if !column.nullable {
+            sql.push_str(" NOT NULL");
+        }
+        sql
+    }
+
     pub fn column_list(&self, columns: &str) -> String {
         DB::column_list(columns)
     }
@@ -321,6 +479,120 @@ impl RdbmsSqlBuilder {
         }
     }
 
+    pub fn keyset_select(&self, range: KeysetSelect<'_>) -> String {
+        match self {
+            Self::Postgres(builder) => builder.keyset_select(range),
+            Self::Mysql(builder) => builder.keyset_select(range),
+        }
+    }
+
+    pub fn keyset_boundary(
+        &self,
+        table: &str,
+        keys: &[String],
+        where_clause: Option<&str>,
+        lower: Option<&[String]>,
+        offset: usize,
+    ) -> String {
+        match self {
+            Self::Postgres(builder) => {
+                builder.keyset_boundary(table, keys, where_clause, lower, offset)
+            }
+            Self::Mysql(builder) => {
+                builder.keyset_boundary(table, keys, where_clause, lower, offset)
+            }
+        }
+    }
+
+    pub fn watermark_select(&self, select: WatermarkSelect<'_>) -> String {
+        match self {
+            Self::Postgres(builder) => builder.watermark_select(select),
+            Self::Mysql(builder) => builder.watermark_select(select),
+        }
+    }
+
+    pub fn null_keys_select(
+        &self,
+        table: &str,
+        columns: &str,
+        where_clause: Option<&str>,
+        keys: &[String],
+    ) -> String {
+        match self {
+            Self::Postgres(builder) => builder.null_keys_select(table, columns, where_clause, keys),
+            Self::Mysql(builder) => builder.null_keys_select(table, columns, where_clause, keys),
+        }
+    }
+
+    pub fn key_sample(
+        &self,
+        table: &str,
+        keys: &[String],
+        where_clause: Option<&str>,
+        percent: f64,
+    ) -> Option<String> {
+        match self {
+            Self::Postgres(builder) => builder.key_sample(table, keys, where_clause, percent),
+            Self::Mysql(builder) => builder.key_sample(table, keys, where_clause, percent),
+        }
+    }
+
+    pub fn literal_string(&self, value: &str) -> String {
+        match self {
+            Self::Postgres(_) => SqlBuilder::<PostgresBackend>::literal_string(value),
+            Self::Mysql(_) => SqlBuilder::<MysqlBackend>::literal_string(value),
+        }
+    }
+
+    pub fn create_table(
+        &self,
+        table: &str,
+        columns: &[ColumnDef],
+        primary_keys: &[String],
+    ) -> String {
+        match self {
+            Self::Postgres(builder) => builder.create_table(table, columns, primary_keys),
+            Self::Mysql(builder) => builder.create_table(table, columns, primary_keys),
+        }
+    }
+
+    pub fn add_column(&self, table: &str, column: &ColumnDef) -> String {
+        match self {
+            Self::Postgres(builder) => builder.add_column(table, column),
+            Self::Mysql(builder) => builder.add_column(table, column),
+        }
+    }
+
+    /// 修改列类型，`column.nullable` 为 true 时同时放开 NOT NULL
+    ///
+    /// MySQL 的 `MODIFY COLUMN` 会重新定义整列，列上的默认值和注释不会保留。
+    pub fn modify_column(&self, table: &str, column: &ColumnDef) -> String {
+        match self {
+            Self::Postgres(_) => {
+                let name = PostgresBackend::identifier(&column.name);
+                let mut sql = format!(
+                    "ALTER TABLE {} ALTER COLUMN {} TYPE {}",
+                    table, name, column.sql_type
+                );
+                if column.nullable {
+                    sql.push_str(&format!(", ALTER COLUMN {} DROP NOT NULL", name));
+                }
+                sql
+            }
+            Self::Mysql(_) => format!(
+                "ALTER TABLE {} MODIFY COLUMN {} {}{}",
+                table,
+                MysqlBackend::identifier(&column.name),
+                column.sql_type,
+                if column.nullable {
+                    " NULL"
+                } else {
+                    " NOT NULL"
+                }
+            ),
+        }
+    }
+
     pub fn column_list(&self, columns: &str) -> String {
         match self {
             Self::Postgres(builder) => builder.column_list(columns),
@@ -379,6 +651,105 @@ impl<DB: SqlBackend> SelectSqlBuilder<DB> {
             .limit_offset(limit, offset)
             .build()
     }
+
+    fn key_list(keys: &[String]) -> String {
+        keys.iter()
+            .map(|key| DB::identifier(key))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn keys_not_null(keys: &[String]) -> String {
+        keys.iter()
+            .map(|key| format!("{} IS NOT NULL", DB::identifier(key)))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
+    fn keyset_select(range: KeysetSelect<'_>) -> String {
+        let key_list = Self::key_list(range.keys);
+        let mut select = Self::select(range.columns, range.table)
+            .where_raw_opt(range.where_clause)
+            .where_raw(Self::keys_not_null(range.keys));
+        if let Some(lower) = range.lower {
+            select = select.where_raw(format!("({}) > ({})", key_list, lower.join(", ")));
+        }
+        if let Some(upper) = range.upper {
+            select = select.where_raw(format!("({}) <= ({})", key_list, upper.join(", ")));
+        }
+        select.order_by(key_list).build()
+    }
+
+    /// 从 `lower` 之后跳过 `offset` 行取下一个键，作为分片上界
+    fn keyset_boundary(
+        table: &str,
+        keys: &[String],
+        where_clause: Option<&str>,
+        lower: Option<&[String]>,
+        offset: usize,
+    ) -> String {
+        let key_list = Self::key_list(keys);
+        let mut select = Self::select(&key_list, table)
+            .where_raw_opt(where_clause)
+            .where_raw(Self::keys_not_null(keys));
+        if let Some(lower) = lower {
+            select = select.where_raw(format!("({}) > ({})", key_list, lower.join(", ")));
+        }
+        select.order_by(key_list).limit_offset(1, offset).build()
+    }
+
+    fn watermark_select(select: WatermarkSelect<'_>) -> String {
+        let order: Vec<String> = std::iter::once(select.column.to_string())
+            .chain(select.keys.iter().cloned())
+            .collect();
+        let key_list = Self::key_list(&order);
+        let column = DB::identifier(select.column);
+        let mut builder = Self::select(select.columns, select.table)
+            .where_raw_opt(select.where_clause)
+            .where_raw(format!("{} IS NOT NULL", column));
+        match select.lower {
+            Some(WatermarkLower::After(values)) => {
+                builder = builder.where_raw(format!("({}) > ({})", key_list, values.join(", ")));
+            }
+            Some(WatermarkLower::From(value)) => {
+                builder = builder.where_raw(format!("{} >= {}", column, value));
+            }
+            None => {}
+        }
+        builder.order_by(key_list).build()
+    }
+
+    fn null_keys_select(
+        table: &str,
+        columns: &str,
+        where_clause: Option<&str>,
+        keys: &[String],
+    ) -> String {
+        let any_null = keys
+            .iter()
+            .map(|key| format!("{} IS NULL", DB::identifier(key)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        Self::select(columns, table)
+            .where_raw_opt(where_clause)
+            .where_raw(any_null)
+            .build()
+    }
+
+    fn key_sample(
+        table: &str,
+        keys: &[String],
+        where_clause: Option<&str>,
+        percent: f64,
+    ) -> String {
+        let key_list = Self::key_list(keys);
+        let sampled_table = format!("{} TABLESAMPLE SYSTEM ({})", table, percent);
+        Self::select(&key_list, &sampled_table)
+            .where_raw_opt(where_clause)
+            .where_raw(Self::keys_not_null(keys))
+            .order_by(key_list)
+            .build()
+    }
 }
 
 /// 可链式追加条件和分页的 SELECT SQL 构建结果。
@@ -389,6 +760,7 @@ impl<DB: SqlBackend> SelectSqlBuilder<DB> {
 pub struct SelectBuilder {
     source: SelectSource,
     wheres: Vec<String>,
+    order_by: Option<String>,
     limit_offset: Option<(usize, usize)>,
 }
 
@@ -406,6 +778,7 @@ impl SelectBuilder {
                 table: table.to_string(),
             },
             wheres: Vec::new(),
+            order_by: None,
             limit_offset: None,
         }
     }
@@ -414,6 +787,7 @@ impl SelectBuilder {
         Self {
             source: SelectSource::Raw(query.trim().to_string()),
             wheres: Vec::new(),
+            order_by: None,
             limit_offset: None,
         }
     }
@@ -433,6 +807,11 @@ impl SelectBuilder {
         }
     }
 
+    pub fn order_by(mut self, columns: impl Into<String>) -> Self {
+        self.order_by = Some(columns.into());
+        self
+    }
+
     pub fn limit_offset(mut self, limit: usize, offset: usize) -> Self {
         self.limit_offset = Some((limit, offset));
         self
@@ -455,6 +834,11 @@ impl SelectBuilder {
             sql.push_str(&conditions);
         }
 
+        if let Some(order_by) = self.order_by {
+            sql.push_str(" ORDER BY ");
+            sql.push_str(&order_by);
+        }
+
         if let Some((limit, offset)) = self.limit_offset {
             sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
         }
@@ -491,6 +875,23 @@ impl WriteRows {
     }
 }
 
+/// 目标表的列类型，PostgreSQL 批量语句据此给派生表 `v` 中的每个值加类型转换
+///
+/// 绑定参数带着驱动推断的类型（NULL 按 text 绑定），`VALUES` 中同一列混有 NULL 和数值时
+/// 无法统一类型，整列为 NULL 时也不能赋给非文本列。没有记录类型的列不加转换。
+#[derive(Debug, Clone, Default)]
+struct ColumnTypes(HashMap<String, String>);
+
+impl ColumnTypes {
+    fn new(types: Vec<(String, String)>) -> Self {
+        Self(types.into_iter().collect())
+    }
+
+    fn get(&self, column: &str) -> Option<&str> {
+        self.0.get(column).map(String::as_str)
+    }
+}
+
 /// Stateless write-side SQL builder using compile-time backend dispatch.
 struct WriteSqlBuilder<DB> {
     _backend: PhantomData<DB>,
@@ -507,12 +908,16 @@ impl<DB> Default for WriteSqlBuilder<DB> {
 impl<DB: SqlBackend> WriteSqlBuilder<DB>
 where
     for<'a> ConflictFragment<'a, DB>: QueryFragment<DB>,
+    for<'a> BatchUpdateStatement<'a, DB>: QueryFragment<DB>,
+    for<'a> RowsSource<'a, DB>: QueryFragment<DB>,
+    for<'a> Scd2CloseStatement<'a, DB>: QueryFragment<DB>,
 {
     pub fn build_many(
         table: &str,
         mode: WriteMode,
         key_columns: &[String],
         columns: &[String],
+        types: &ColumnTypes,
         rows: &[Vec<UnifiedValue>],
     ) -> Result<BuiltWriteQuery> {
         ensure_row_width(rows, columns.len())?;
@@ -527,10 +932,140 @@ where
                 let parts = build_upsert_sql_with_backend::<DB>(table, columns, &keys, &nonkeys)?;
                 build_values_query_with_backend::<DB>(&parts.prefix, rows, &parts.suffix)
             }
-            WriteMode::Update | WriteMode::Delete => {
-                bail!("{} 模式不支持批量 VALUES SQL", mode.as_str())
+            WriteMode::Update => build_batch_update_query_with_backend::<DB>(
+                table,
+                columns,
+                types,
+                rows,
+                key_columns,
+            ),
+            WriteMode::Delete => {
+                build_batch_delete_query_with_backend::<DB>(table, columns, rows, key_columns)
+            }
+            WriteMode::SoftDelete | WriteMode::Scd2 => {
+                bail!("{} 模式需要配置历史列", mode.as_str())
+            }
+        }
+    }
+
+    /// soft_delete：`UPDATE t SET deleted_at = CURRENT_TIMESTAMP WHERE k IN (...)`
+    pub fn build_soft_delete(
+        table: &str,
+        key_columns: &[String],
+        history: &HistoryColumns,
+        columns: &[String],
+        rows: &[Vec<UnifiedValue>],
+    ) -> Result<BuiltWriteQuery> {
+        let (keys, key_rows) = key_rows("soft_delete", columns, rows, key_columns)?;
+        let mut ctx = BuildCtx::<DB>::new();
+        SoftDeleteStatement {
+            table,
+            deleted_at: &history.deleted_at,
+            keys: &keys,
+            key_rows: &key_rows,
+        }
+        .walk_ast(&mut ctx);
+        Ok(ctx.finish_query())
+    }
+
+    /// scd2：先关闭跟踪列发生变化的当前版本，再为没有当前版本的 key 插入新版本
+    ///
+    /// 返回按顺序执行的 SQL；`rows` 中的 key 不能重复。
+    pub fn build_scd2(
+        table: &str,
+        key_columns: &[String],
+        history: &HistoryColumns,
+        columns: &[String],
+        types: &ColumnTypes,
+        rows: &[Vec<UnifiedValue>],
+    ) -> Result<Vec<BuiltWriteQuery>> {
+        ensure_row_width(rows, columns.len())?;
+        let (keys, _) = key_rows("scd2", columns, rows, key_columns)?;
+        let (_, nonkeys) = split_keys_nonkeys(columns, &keys);
+        let tracked = if history.tracked_columns.is_empty() {
+            nonkeys
+        } else {
+            for column in &history.tracked_columns {
+                if !columns.contains(column) {
+                    bail!("scd2 跟踪列 '{}' 不在写入数据中", column);
+                }
+            }
+            history.tracked_columns.clone()
+        };
+
+        let mut queries = Vec::with_capacity(2);
+        // 没有跟踪列时版本不会变化，只为新 key 插入首个版本
+        if !tracked.is_empty() {
+            let mut ctx = BuildCtx::<DB>::new();
+            Scd2CloseStatement::<DB> {
+                table,
+                history,
+                columns,
+                types,
+                keys: &keys,
+                tracked: Some(&tracked),
+                rows,
+                _backend: PhantomData,
             }
+            .walk_ast(&mut ctx);
+            queries.push(ctx.finish_query());
+        }
+
+        let mut ctx = BuildCtx::<DB>::new();
+        Scd2InsertStatement::<DB> {
+            table,
+            history,
+            columns,
+            types,
+            keys: &keys,
+            rows,
+            _backend: PhantomData,
+        }
+        .walk_ast(&mut ctx);
+        queries.push(ctx.finish_query());
+        Ok(queries)
+    }
+
+    /// 关闭当前版本且不插入新版本，用于 scd2 任务中的删除
+    pub fn build_scd2_close(
+        table: &str,
+        key_columns: &[String],
+        history: &HistoryColumns,
+        columns: &[String],
+        types: &ColumnTypes,
+        rows: &[Vec<UnifiedValue>],
+    ) -> Result<BuiltWriteQuery> {
+        ensure_row_width(rows, columns.len())?;
+        let (keys, _) = key_rows("scd2", columns, rows, key_columns)?;
+        let mut ctx = BuildCtx::<DB>::new();
+        Scd2CloseStatement::<DB> {
+            table,
+            history,
+            columns,
+            types,
+            keys: &keys,
+            tracked: None,
+            rows,
+            _backend: PhantomData,
         }
+        .walk_ast(&mut ctx);
+        Ok(ctx.finish_query())
+    }
+
+    /// 单条 SQL 最多容纳的行数：不超过 `batch_size`，且绑定参数不超过驱动上限
+    pub fn rows_per_statement(
+        mode: WriteMode,
+        key_columns: &[String],
+        columns: &[String],
+        batch_size: usize,
+    ) -> usize {
+        let params_per_row = match mode {
+            WriteMode::Delete | WriteMode::SoftDelete => {
+                split_keys_nonkeys(columns, key_columns).0.len()
+            }
+            _ => columns.len(),
+        };
+        (DB::MAX_BIND_PARAMS / params_per_row.max(1)).clamp(1, batch_size.max(1))
     }
 
     pub fn build_one(
@@ -541,15 +1076,23 @@ where
         row: &[UnifiedValue],
     ) -> Result<BuiltWriteQuery> {
         match mode {
-            WriteMode::Insert | WriteMode::Upsert => {
-                Self::build_many(table, mode, key_columns, columns, &[row.to_vec()])
-            }
+            WriteMode::Insert | WriteMode::Upsert => Self::build_many(
+                table,
+                mode,
+                key_columns,
+                columns,
+                &ColumnTypes::default(),
+                &[row.to_vec()],
+            ),
             WriteMode::Update => {
                 build_update_query_with_backend::<DB>(table, columns, row, key_columns)
             }
             WriteMode::Delete => {
                 build_delete_query_with_backend::<DB>(table, columns, row, key_columns)
             }
+            WriteMode::SoftDelete | WriteMode::Scd2 => {
+                bail!("{} 模式需要配置历史列", mode.as_str())
+            }
         }
     }
 }
@@ -792,21 +1335,389 @@ impl<DB: SqlBackend> QueryFragment<DB> for DeleteValuesStatement<'_> {
     }
 }
 
-struct BatchValuesStatement<'a> {
-    base_sql: &'a str,
+/// 批量 UPDATE：按 key 关联一组行值，只更新非主键列
+struct BatchUpdateStatement<'a, DB> {
+    table: &'a str,
+    columns: &'a [String],
+    types: &'a ColumnTypes,
+    keys: &'a [String],
+    nonkeys: &'a [String],
     rows: &'a [Vec<UnifiedValue>],
-    suffix: &'a str,
+    _backend: PhantomData<DB>,
 }
 
-impl<DB: SqlBackend> QueryFragment<DB> for BatchValuesStatement<'_> {
-    fn walk_ast(&self, out: &mut BuildCtx<DB>) {
-        out.push_sql(self.base_sql);
-        out.push_sql(" VALUES ");
-
-        for (row_index, row) in self.rows.iter().enumerate() {
-            if row_index > 0 {
-                out.push_sql(", ");
-            }
+/// `t.k1 = v.k1 AND t.k2 = v.k2`
+fn walk_join_keys<DB: SqlBackend>(keys: &[String], out: &mut BuildCtx<DB>) {
+    walk_column_match(keys, "t", " = ", out);
+}
+
+/// `{alias}.c1 {op} v.c1 AND {alias}.c2 {op} v.c2`
+fn walk_column_match<DB: SqlBackend>(
+    columns: &[String],
+    alias: &str,
+    op: &str,
+    out: &mut BuildCtx<DB>,
+) {
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            out.push_sql(" AND ");
+        }
+        out.push_sql(alias);
+        out.push_sql(".");
+        out.push_ident(column);
+        out.push_sql(op);
+        out.push_sql("v.");
+        out.push_ident(column);
+    }
+}
+
+/// 由批次行值构成的派生表 `v`
+struct RowsSource<'a, DB> {
+    columns: &'a [String],
+    types: &'a ColumnTypes,
+    rows: &'a [Vec<UnifiedValue>],
+    _backend: PhantomData<DB>,
+}
+
+/// `(VALUES (CAST($1 AS type), ...), ...) AS v (cols)`
+impl QueryFragment<PostgresBackend> for RowsSource<'_, PostgresBackend> {
+    fn walk_ast(&self, out: &mut BuildCtx<PostgresBackend>) {
+        out.push_sql("(VALUES ");
+        for (row_index, row) in self.rows.iter().enumerate() {
+            if row_index > 0 {
+                out.push_sql(", ");
+            }
+            out.push_sql("(");
+            for (col_index, (column, value)) in self.columns.iter().zip(row).enumerate() {
+                if col_index > 0 {
+                    out.push_sql(", ");
+                }
+                match self.types.get(column) {
+                    Some(ty) => {
+                        out.push_sql("CAST(");
+                        out.push_bind(value.clone());
+                        out.push_sql(" AS ");
+                        out.push_sql(ty);
+                        out.push_sql(")");
+                    }
+                    None => out.push_bind(value.clone()),
+                }
+            }
+            out.push_sql(")");
+        }
+        out.push_sql(") AS v (");
+        ColumnList {
+            columns: self.columns,
+        }
+        .walk_ast(out);
+        out.push_sql(")");
+    }
+}
+
+/// `(SELECT ? AS c UNION ALL SELECT ?) AS v`
+impl QueryFragment<MysqlBackend> for RowsSource<'_, MysqlBackend> {
+    fn walk_ast(&self, out: &mut BuildCtx<MysqlBackend>) {
+        out.push_sql("(");
+        for (row_index, row) in self.rows.iter().enumerate() {
+            out.push_sql(if row_index == 0 {
+                "SELECT "
+            } else {
+                " UNION ALL SELECT "
+            });
+            for (col_index, (column, value)) in self.columns.iter().zip(row).enumerate() {
+                if col_index > 0 {
+                    out.push_sql(", ");
+                }
+                out.push_bind(value.clone());
+                if row_index == 0 {
+                    out.push_sql(" AS ");
+                    out.push_ident(column);
+                }
+            }
+        }
+        out.push_sql(") AS v");
+    }
+}
+
+/// `UPDATE t SET c = v.c FROM (VALUES ...) AS v (cols) WHERE t.k = v.k`
+impl QueryFragment<PostgresBackend> for BatchUpdateStatement<'_, PostgresBackend> {
+    fn walk_ast(&self, out: &mut BuildCtx<PostgresBackend>) {
+        out.push_sql("UPDATE ");
+        out.push_table(self.table);
+        out.push_sql(" AS t SET ");
+        for (i, column) in self.nonkeys.iter().enumerate() {
+            if i > 0 {
+                out.push_sql(", ");
+            }
+            out.push_ident(column);
+            out.push_sql(" = v.");
+            out.push_ident(column);
+        }
+        out.push_sql(" FROM ");
+        RowsSource::<PostgresBackend> {
+            columns: self.columns,
+            types: self.types,
+            rows: self.rows,
+            _backend: PhantomData,
+        }
+        .walk_ast(out);
+        out.push_sql(" WHERE ");
+        walk_join_keys(self.keys, out);
+    }
+}
+
+/// `UPDATE t JOIN (SELECT ? AS c UNION ALL SELECT ?) AS v ON t.k = v.k SET t.c = v.c`
+impl QueryFragment<MysqlBackend> for BatchUpdateStatement<'_, MysqlBackend> {
+    fn walk_ast(&self, out: &mut BuildCtx<MysqlBackend>) {
+        out.push_sql("UPDATE ");
+        out.push_table(self.table);
+        out.push_sql(" AS t JOIN ");
+        RowsSource::<MysqlBackend> {
+            columns: self.columns,
+            types: self.types,
+            rows: self.rows,
+            _backend: PhantomData,
+        }
+        .walk_ast(out);
+        out.push_sql(" ON ");
+        walk_join_keys(self.keys, out);
+        out.push_sql(" SET ");
+        for (i, column) in self.nonkeys.iter().enumerate() {
+            if i > 0 {
+                out.push_sql(", ");
+            }
+            out.push_sql("t.");
+            out.push_ident(column);
+            out.push_sql(" = v.");
+            out.push_ident(column);
+        }
+    }
+}
+
+/// 批量 DELETE：单 key 用 `k IN (...)`，复合 key 用 `(k1, k2) IN ((...), ...)`
+struct BatchDeleteStatement<'a> {
+    table: &'a str,
+    keys: &'a [String],
+    key_rows: &'a [Vec<UnifiedValue>],
+}
+
+impl<DB: SqlBackend> QueryFragment<DB> for BatchDeleteStatement<'_> {
+    fn walk_ast(&self, out: &mut BuildCtx<DB>) {
+        out.push_sql("DELETE FROM ");
+        out.push_table(self.table);
+        out.push_sql(" WHERE ");
+        KeysIn {
+            keys: self.keys,
+            key_rows: self.key_rows,
+        }
+        .walk_ast(out);
+    }
+}
+
+/// 软删除：`UPDATE t SET deleted_at = CURRENT_TIMESTAMP WHERE k IN (...)`
+struct SoftDeleteStatement<'a> {
+    table: &'a str,
+    deleted_at: &'a str,
+    keys: &'a [String],
+    key_rows: &'a [Vec<UnifiedValue>],
+}
+
+impl<DB: SqlBackend> QueryFragment<DB> for SoftDeleteStatement<'_> {
+    fn walk_ast(&self, out: &mut BuildCtx<DB>) {
+        out.push_sql("UPDATE ");
+        out.push_table(self.table);
+        out.push_sql(" SET ");
+        out.push_ident(self.deleted_at);
+        out.push_sql(" = CURRENT_TIMESTAMP WHERE ");
+        KeysIn {
+            keys: self.keys,
+            key_rows: self.key_rows,
+        }
+        .walk_ast(out);
+    }
+}
+
+/// `k IN (...)` 或 `(k1, k2) IN ((...), ...)`
+struct KeysIn<'a> {
+    keys: &'a [String],
+    key_rows: &'a [Vec<UnifiedValue>],
+}
+
+impl<DB: SqlBackend> QueryFragment<DB> for KeysIn<'_> {
+    fn walk_ast(&self, out: &mut BuildCtx<DB>) {
+        let composite = self.keys.len() > 1;
+        if composite {
+            out.push_sql("(");
+        }
+        ColumnList { columns: self.keys }.walk_ast(out);
+        out.push_sql(if composite { ") IN (" } else { " IN (" });
+        for (row_index, row) in self.key_rows.iter().enumerate() {
+            if row_index > 0 {
+                out.push_sql(", ");
+            }
+            if composite {
+                out.push_sql("(");
+            }
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    out.push_sql(", ");
+                }
+                out.push_bind(value.clone());
+            }
+            if composite {
+                out.push_sql(")");
+            }
+        }
+        out.push_sql(")");
+    }
+}
+
+/// scd2 关闭当前版本：`valid_to = CURRENT_TIMESTAMP, is_current = FALSE`
+///
+/// `tracked` 为 Some 时只关闭跟踪列与新值不同的版本，为 None 时关闭所有匹配 key 的当前版本。
+struct Scd2CloseStatement<'a, DB> {
+    table: &'a str,
+    history: &'a HistoryColumns,
+    columns: &'a [String],
+    types: &'a ColumnTypes,
+    keys: &'a [String],
+    tracked: Option<&'a [String]>,
+    rows: &'a [Vec<UnifiedValue>],
+    _backend: PhantomData<DB>,
+}
+
+impl<DB: SqlBackend> Scd2CloseStatement<'_, DB> {
+    /// `t.is_current = TRUE AND NOT (t.c1 <=> v.c1 AND ...)`
+    fn walk_filter(&self, out: &mut BuildCtx<DB>) {
+        out.push_sql("t.");
+        out.push_ident(&self.history.is_current);
+        out.push_sql(" = TRUE");
+        if let Some(tracked) = self.tracked {
+            out.push_sql(" AND NOT (");
+            walk_column_match(tracked, "t", DB::NULL_SAFE_EQ, out);
+            out.push_sql(")");
+        }
+    }
+}
+
+/// `UPDATE t SET ... FROM (VALUES ...) AS v (cols) WHERE t.k = v.k AND ...`
+impl QueryFragment<PostgresBackend> for Scd2CloseStatement<'_, PostgresBackend> {
+    fn walk_ast(&self, out: &mut BuildCtx<PostgresBackend>) {
+        out.push_sql("UPDATE ");
+        out.push_table(self.table);
+        out.push_sql(" AS t SET ");
+        out.push_ident(&self.history.valid_to);
+        out.push_sql(" = CURRENT_TIMESTAMP, ");
+        out.push_ident(&self.history.is_current);
+        out.push_sql(" = FALSE FROM ");
+        RowsSource::<PostgresBackend> {
+            columns: self.columns,
+            types: self.types,
+            rows: self.rows,
+            _backend: PhantomData,
+        }
+        .walk_ast(out);
+        out.push_sql(" WHERE ");
+        walk_join_keys(self.keys, out);
+        out.push_sql(" AND ");
+        self.walk_filter(out);
+    }
+}
+
+/// `UPDATE t JOIN (...) AS v ON t.k = v.k SET ... WHERE ...`
+impl QueryFragment<MysqlBackend> for Scd2CloseStatement<'_, MysqlBackend> {
+    fn walk_ast(&self, out: &mut BuildCtx<MysqlBackend>) {
+        out.push_sql("UPDATE ");
+        out.push_table(self.table);
+        out.push_sql(" AS t JOIN ");
+        RowsSource::<MysqlBackend> {
+            columns: self.columns,
+            types: self.types,
+            rows: self.rows,
+            _backend: PhantomData,
+        }
+        .walk_ast(out);
+        out.push_sql(" ON ");
+        walk_join_keys(self.keys, out);
+        out.push_sql(" SET t.");
+        out.push_ident(&self.history.valid_to);
+        out.push_sql(" = CURRENT_TIMESTAMP, t.");
+        out.push_ident(&self.history.is_current);
+        out.push_sql(" = FALSE WHERE ");
+        self.walk_filter(out);
+    }
+}
+
+/// scd2 插入新版本：只为没有当前版本的 key 插入，`valid_to` 留空
+struct Scd2InsertStatement<'a, DB> {
+    table: &'a str,
+    history: &'a HistoryColumns,
+    columns: &'a [String],
+    types: &'a ColumnTypes,
+    keys: &'a [String],
+    rows: &'a [Vec<UnifiedValue>],
+    _backend: PhantomData<DB>,
+}
+
+impl<DB: SqlBackend> QueryFragment<DB> for Scd2InsertStatement<'_, DB>
+where
+    for<'a> RowsSource<'a, DB>: QueryFragment<DB>,
+{
+    fn walk_ast(&self, out: &mut BuildCtx<DB>) {
+        out.push_sql("INSERT INTO ");
+        out.push_table(self.table);
+        out.push_sql(" (");
+        ColumnList {
+            columns: self.columns,
+        }
+        .walk_ast(out);
+        out.push_sql(", ");
+        out.push_ident(&self.history.valid_from);
+        out.push_sql(", ");
+        out.push_ident(&self.history.is_current);
+        out.push_sql(") SELECT ");
+        for column in self.columns {
+            out.push_sql("v.");
+            out.push_ident(column);
+            out.push_sql(", ");
+        }
+        out.push_sql("CURRENT_TIMESTAMP, TRUE FROM ");
+        RowsSource::<DB> {
+            columns: self.columns,
+            types: self.types,
+            rows: self.rows,
+            _backend: PhantomData,
+        }
+        .walk_ast(out);
+        out.push_sql(" WHERE NOT EXISTS (SELECT 1 FROM ");
+        out.push_table(self.table);
+        out.push_sql(" AS c WHERE ");
+        walk_column_match(self.keys, "c", " = ", out);
+        out.push_sql(" AND c.");
+        out.push_ident(&self.history.is_current);
+        out.push_sql(" = TRUE)");
+    }
+}
+
+struct BatchValuesStatement<'a> {
+    base_sql: &'a str,
+    rows: &'a [Vec<UnifiedValue>],
+    suffix: &'a str,
+}
+
+impl<DB: SqlBackend> QueryFragment<DB> for BatchValuesStatement<'_> {
+    fn walk_ast(&self, out: &mut BuildCtx<DB>) {
+        out.push_sql(self.base_sql);
+        out.push_sql(if self.base_sql.is_empty() {
+            "VALUES "
+        } else {
+            " VALUES "
+        });
+
+        for (row_index, row) in self.rows.iter().enumerate() {
+            if row_index > 0 {
+                out.push_sql(", ");
+            }
 
             out.push_sql("(");
             for (col_index, value) in row.iter().enumerate() {
@@ -946,6 +1857,133 @@ fn build_delete_query_with_backend<DB: SqlBackend>(
     Ok(ctx.finish_query())
 }
 
+fn build_batch_update_query_with_backend<DB: SqlBackend>(
+    table: &str,
+    columns: &[String],
+    types: &ColumnTypes,
+    rows: &[Vec<UnifiedValue>],
+    key_columns: &[String],
+) -> Result<BuiltWriteQuery>
+where
+    for<'a> BatchUpdateStatement<'a, DB>: QueryFragment<DB>,
+{
+    if key_columns.is_empty() {
+        bail!("update 模式需要指定 key_columns");
+    }
+    if rows.is_empty() {
+        bail!("没有数据需要同步");
+    }
+
+    let (keys, nonkeys) = split_keys_nonkeys(columns, key_columns);
+    if keys.is_empty() {
+        bail!("写入数据不包含任何 key_columns {:?}", key_columns);
+    }
+    if nonkeys.is_empty() {
+        bail!("update 模式没有可更新的非 key 列");
+    }
+
+    let mut ctx = BuildCtx::<DB>::new();
+    BatchUpdateStatement::<DB> {
+        table,
+        columns,
+        types,
+        keys: &keys,
+        nonkeys: &nonkeys,
+        rows,
+        _backend: PhantomData,
+    }
+    .walk_ast(&mut ctx);
+    Ok(ctx.finish_query())
+}
+
+fn build_batch_delete_query_with_backend<DB: SqlBackend>(
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<UnifiedValue>],
+    key_columns: &[String],
+) -> Result<BuiltWriteQuery> {
+    let (keys, key_rows) = key_rows("delete", columns, rows, key_columns)?;
+
+    let mut ctx = BuildCtx::<DB>::new();
+    BatchDeleteStatement {
+        table,
+        keys: &keys,
+        key_rows: &key_rows,
+    }
+    .walk_ast(&mut ctx);
+    Ok(ctx.finish_query())
+}
+
+/// 取出批次中的 key 列及每行的 key 值
+fn key_rows(
+    mode: &str,
+    columns: &[String],
+    rows: &[Vec<UnifiedValue>],
+    key_columns: &[String],
+) -> Result<(Vec<String>, Vec<Vec<UnifiedValue>>)> {
+    if key_columns.is_empty() {
+        bail!("{} 模式需要指定 key_columns", mode);
+    }
+    if rows.is_empty() {
+        bail!("没有数据需要同步");
+    }
+
+    let (keys, _) = split_keys_nonkeys(columns, key_columns);
+    if keys.is_empty() {
+        bail!("写入数据不包含任何 key_columns {:?}", key_columns);
+    }
+    let key_rows = rows
+        .iter()
+        .map(|row| values_for_columns(columns, row, &keys))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((keys, key_rows))
+}
+
+/// 一条多行 SQL 内同一 key 不能出现两次的写入模式
+///
+/// PostgreSQL 的 `INSERT ... ON CONFLICT DO UPDATE` 遇到重复 key 直接报错
+/// （cannot affect row a second time），批量 UPDATE 只会应用其中一行，
+/// scd2 会插入两个当前版本。CDC 批次中同一 key 的 insert 与 update 都按 upsert 写入，很常见。
+fn requires_unique_keys(mode: WriteMode, key_columns: &[String]) -> bool {
+    !key_columns.is_empty()
+        && matches!(
+            mode,
+            WriteMode::Upsert | WriteMode::Update | WriteMode::Scd2
+        )
+}
+
+/// 按行数上限分段；`unique_keys` 时同一段内 key 不重复，遇到重复 key 另起一段，
+/// 各段按顺序执行，同一 key 的后一次写入覆盖前一次
+fn write_chunks<'a>(
+    columns: &[String],
+    key_columns: &[String],
+    rows: &'a [Vec<UnifiedValue>],
+    max_rows: usize,
+    unique_keys: bool,
+) -> Result<Vec<&'a [Vec<UnifiedValue>]>> {
+    if !unique_keys {
+        return Ok(rows.chunks(max_rows).collect());
+    }
+
+    let (keys, _) = split_keys_nonkeys(columns, key_columns);
+    let mut chunks = Vec::new();
+    let mut seen = HashSet::new();
+    let mut start = 0;
+    for (i, row) in rows.iter().enumerate() {
+        let key = format!("{:?}", values_for_columns(columns, row, &keys)?);
+        if i - start >= max_rows || seen.contains(&key) {
+            chunks.push(&rows[start..i]);
+            start = i;
+            seen.clear();
+        }
+        seen.insert(key);
+    }
+    if start < rows.len() {
+        chunks.push(&rows[start..]);
+    }
+    Ok(chunks)
+}
+
 fn ensure_row_width(rows: &[Vec<UnifiedValue>], expected: usize) -> Result<()> {
     for row in rows {
         ensure_row_width_for_row(row, expected)?;
@@ -991,24 +2029,33 @@ fn values_for_columns(
 ///
 /// 参数说明：
 /// - `pool`: 目标库连接池，也是运行时选择数据库后端的唯一边界。
+/// - `executor`: 执行 SQL 的 executor；writer 开启事务时传入持有事务的 executor。
 /// - `table`: 目标表名。
-/// - `mode`: 写入模式，支持 insert/upsert/update/delete。
-/// - `key_columns`: upsert/update/delete 的匹配列；insert 可为空。
+/// - `mode`: 写入模式，支持 insert/upsert/update/delete/soft_delete/scd2。
+/// - `key_columns`: upsert/update/delete/soft_delete/scd2 的匹配列；insert 可为空。
+/// - `history`: soft_delete / scd2 使用的列名。
 /// - `write_rows`: 待写入数据，`write_rows.columns` 是列顺序，
 ///   `write_rows.values[*]` 是对应 value 列表。
-/// - `batch_size`: insert/upsert 每批拼接的行数；传 0 时按 1 处理。
+/// - `batch_size`: 每条 SQL 最多拼接的行数；传 0 时按 1 处理。
 ///
 /// 执行过程：
 /// - 空数据直接返回 `Ok(0)`。
 /// - 在 `RdbmsPool` 边界 match 一次，之后进入具体后端的编译期分派。
-/// - insert/upsert 按 `batch_size` 分片，批量生成一条 VALUES SQL。
-/// - update/delete 每行生成一条带参数的 SQL。
+/// - 按 `batch_size` 和驱动绑定参数上限分片，每片生成一条批量 SQL：
+///   insert/upsert 使用多行 VALUES；update 在 PostgreSQL 上使用 `UPDATE ... FROM (VALUES ...)`，
+///   在 MySQL 上关联 `UNION ALL` 派生表；delete 使用 `WHERE (k1, k2) IN (...)`；
+///   soft_delete 按同样的 key 列表更新删除时间列；scd2 每片依次执行关闭旧版本、插入新版本两条 SQL，
+///   同一片内 key 不重复。
+/// - insert/upsert/update/delete 只有一行的分片沿用逐行 SQL。
 /// - executor 只负责执行 SQL builder 返回的 `sql + params`。
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_rdbms_write(
     pool: &Arc<RdbmsPool>,
+    executor: &dyn super::pool::DatabaseExecutor,
     table: &str,
     mode: WriteMode,
     key_columns: &[String],
+    history: &HistoryColumns,
     write_rows: WriteRows,
     batch_size: usize,
 ) -> Result<usize> {
@@ -1016,15 +2063,22 @@ pub async fn execute_rdbms_write(
         return Ok(0);
     }
 
-    let executor = pool.executor();
     // 运行时数据库类型只在连接池边界判断一次，SQL 构建进入具体后端泛型实现。
     match pool.as_ref() {
         RdbmsPool::Postgres(_) => {
+            // 批量 UPDATE 和 scd2 经过 VALUES 派生表，需要按目标列类型转换
+            let types = if matches!(mode, WriteMode::Update | WriteMode::Scd2) {
+                ColumnTypes::new(executor.fetch_column_types(table).await?)
+            } else {
+                ColumnTypes::default()
+            };
             execute_write_with_backend::<PostgresBackend>(
-                executor.as_ref(),
+                executor,
                 table,
                 mode,
                 key_columns,
+                history,
+                &types,
                 &write_rows,
                 batch_size,
             )
@@ -1032,10 +2086,12 @@ pub async fn execute_rdbms_write(
         }
         RdbmsPool::Mysql(_) => {
             execute_write_with_backend::<MysqlBackend>(
-                executor.as_ref(),
+                executor,
                 table,
                 mode,
                 key_columns,
+                history,
+                &ColumnTypes::default(),
                 &write_rows,
                 batch_size,
             )
@@ -1044,52 +2100,142 @@ pub async fn execute_rdbms_write(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn execute_write_with_backend<DB: SqlBackend>(
     executor: &dyn super::pool::DatabaseExecutor,
     table: &str,
     mode: WriteMode,
     key_columns: &[String],
+    history: &HistoryColumns,
+    types: &ColumnTypes,
     write_rows: &WriteRows,
     batch_size: usize,
 ) -> Result<usize>
 where
     for<'a> ConflictFragment<'a, DB>: QueryFragment<DB>,
+    for<'a> BatchUpdateStatement<'a, DB>: QueryFragment<DB>,
+    for<'a> RowsSource<'a, DB>: QueryFragment<DB>,
+    for<'a> Scd2CloseStatement<'a, DB>: QueryFragment<DB>,
+{
+    let columns = &write_rows.columns;
+    let rows_per_statement =
+        WriteSqlBuilder::<DB>::rows_per_statement(mode, key_columns, columns, batch_size);
+    let chunks = write_chunks(
+        columns,
+        key_columns,
+        &write_rows.values,
+        rows_per_statement,
+        requires_unique_keys(mode, key_columns),
+    )?;
+    let mut processed = 0usize;
+    for chunk in chunks {
+        let queries = match (mode, chunk) {
+            (WriteMode::SoftDelete, _) => vec![WriteSqlBuilder::<DB>::build_soft_delete(
+                table,
+                key_columns,
+                history,
+                columns,
+                chunk,
+            )?],
+            (WriteMode::Scd2, _) => WriteSqlBuilder::<DB>::build_scd2(
+                table,
+                key_columns,
+                history,
+                columns,
+                types,
+                chunk,
+            )?,
+            // 单行沿用逐行 SQL，多行合并成一条批量 SQL。
+            (_, [row]) => vec![WriteSqlBuilder::<DB>::build_one(
+                table,
+                mode,
+                key_columns,
+                columns,
+                row,
+            )?],
+            _ => vec![WriteSqlBuilder::<DB>::build_many(
+                table,
+                mode,
+                key_columns,
+                columns,
+                types,
+                chunk,
+            )?],
+        };
+        for query in queries {
+            executor
+                .execute_with_params(&query.sql, &query.params)
+                .await?;
+        }
+        processed += chunk.len();
+    }
+    Ok(processed)
+}
+
+/// 关闭 key 对应的当前版本，用于 scd2 任务中的 CDC 删除
+pub async fn execute_scd2_close(
+    pool: &Arc<RdbmsPool>,
+    executor: &dyn super::pool::DatabaseExecutor,
+    table: &str,
+    key_columns: &[String],
+    history: &HistoryColumns,
+    write_rows: WriteRows,
+    batch_size: usize,
+) -> Result<usize> {
+    if write_rows.values.is_empty() {
+        return Ok(0);
+    }
+
+    // 关闭版本只需要 key 列
+    let (keys, key_rows) = key_rows("scd2", &write_rows.columns, &write_rows.values, key_columns)?;
+    let key_rows = WriteRows::new(keys, key_rows);
+    match pool.as_ref() {
+        RdbmsPool::Postgres(_) => {
+            let types = ColumnTypes::new(executor.fetch_column_types(table).await?);
+            execute_scd2_close_with_backend::<PostgresBackend>(
+                executor, table, history, &types, &key_rows, batch_size,
+            )
+            .await
+        }
+        RdbmsPool::Mysql(_) => {
+            execute_scd2_close_with_backend::<MysqlBackend>(
+                executor,
+                table,
+                history,
+                &ColumnTypes::default(),
+                &key_rows,
+                batch_size,
+            )
+            .await
+        }
+    }
+}
+
+async fn execute_scd2_close_with_backend<DB: SqlBackend>(
+    executor: &dyn super::pool::DatabaseExecutor,
+    table: &str,
+    history: &HistoryColumns,
+    types: &ColumnTypes,
+    key_rows: &WriteRows,
+    batch_size: usize,
+) -> Result<usize>
+where
+    for<'a> ConflictFragment<'a, DB>: QueryFragment<DB>,
+    for<'a> BatchUpdateStatement<'a, DB>: QueryFragment<DB>,
+    for<'a> RowsSource<'a, DB>: QueryFragment<DB>,
+    for<'a> Scd2CloseStatement<'a, DB>: QueryFragment<DB>,
 {
+    let keys = &key_rows.columns;
+    let rows_per_statement =
+        WriteSqlBuilder::<DB>::rows_per_statement(WriteMode::Scd2, keys, keys, batch_size);
     let mut processed = 0usize;
-    let batch_size = batch_size.max(1);
-    match mode {
-        WriteMode::Insert | WriteMode::Upsert => {
-            // insert/upsert 可以把多行 values 合并成一条参数化 SQL。
-            for chunk in write_rows.values.chunks(batch_size) {
-                let query = WriteSqlBuilder::<DB>::build_many(
-                    table,
-                    mode,
-                    key_columns,
-                    &write_rows.columns,
-                    chunk,
-                )?;
-                executor
-                    .execute_with_params(&query.sql, &query.params)
-                    .await?;
-                processed += chunk.len();
-            }
-        }
-        WriteMode::Update | WriteMode::Delete => {
-            // update/delete 的 WHERE 条件依赖当前行 key 值，逐行生成并执行。
-            for row in &write_rows.values {
-                let query = WriteSqlBuilder::<DB>::build_one(
-                    table,
-                    mode,
-                    key_columns,
-                    &write_rows.columns,
-                    row,
-                )?;
-                executor
-                    .execute_with_params(&query.sql, &query.params)
-                    .await?;
-                processed += 1;
-            }
-        }
+    for chunk in key_rows.values.chunks(rows_per_statement) {
+        let query =
+            WriteSqlBuilder::<DB>::build_scd2_close(table, keys, history, keys, types, chunk)?;
+        executor
+            .execute_with_params(&query.sql, &query.params)
+            .await?;
+        processed += chunk.len();
     }
     Ok(processed)
 }
@@ -1267,6 +2413,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keyset_queries_use_row_comparison() {
+        let keys = vec!["tenant".to_string(), "id".to_string()];
+        let lower = vec!["'a'".to_string(), "10".to_string()];
+        let upper = vec!["'b'".to_string(), "5".to_string()];
+        let builder = SqlBuilder::<PostgresBackend>::new();
+
+        assert_eq!(
+            builder.keyset_select(KeysetSelect {
+                table: "orders",
+                columns: "*",
+                where_clause: Some("amount > 0"),
+                keys: &keys,
+                lower: Some(&lower),
+                upper: Some(&upper),
+            }),
+            "SELECT * FROM orders WHERE (amount > 0) \
+             AND (\"tenant\" IS NOT NULL AND \"id\" IS NOT NULL) \
+             AND ((\"tenant\", \"id\") > ('a', 10)) \
+             AND ((\"tenant\", \"id\") <= ('b', 5)) \
+             ORDER BY \"tenant\", \"id\""
+        );
+        assert_eq!(
+            builder.keyset_boundary("orders", &keys, None, Some(&lower), 99),
+            "SELECT \"tenant\", \"id\" FROM orders \
+             WHERE (\"tenant\" IS NOT NULL AND \"id\" IS NOT NULL) \
+             AND ((\"tenant\", \"id\") > ('a', 10)) \
+             ORDER BY \"tenant\", \"id\" LIMIT 1 OFFSET 99"
+        );
+        assert_eq!(
+            SqlBuilder::<MysqlBackend>::new().null_keys_select("orders", "*", None, &keys),
+            "SELECT * FROM orders WHERE (tenant IS NULL OR id IS NULL)"
+        );
+    }
+
+    #[test]
+    fn watermark_queries_order_by_compound_key() {
+        let keys = vec!["id".to_string()];
+        let after = vec!["'2024-03-01 00:00:00'".to_string(), "2".to_string()];
+        let builder = SqlBuilder::<PostgresBackend>::new();
+        let select = |lower| WatermarkSelect {
+            table: "orders",
+            columns: "*",
+            where_clause: None,
+            column: "updated_at",
+            keys: &keys,
+            lower,
+        };
+
+        assert_eq!(
+            builder.watermark_select(select(Some(WatermarkLower::After(&after)))),
+            "SELECT * FROM orders WHERE (\"updated_at\" IS NOT NULL) \
+             AND ((\"updated_at\", \"id\") > ('2024-03-01 00:00:00', 2)) \
+             ORDER BY \"updated_at\", \"id\""
+        );
+        assert_eq!(
+            SqlBuilder::<MysqlBackend>::new()
+                .watermark_select(select(Some(WatermarkLower::From("'2024-03-01 00:00:00'")))),
+            "SELECT * FROM orders WHERE (updated_at IS NOT NULL) \
+             AND (updated_at >= '2024-03-01 00:00:00') ORDER BY updated_at, id"
+        );
+        assert_eq!(
+            builder.watermark_select(select(None)),
+            "SELECT * FROM orders WHERE (\"updated_at\" IS NOT NULL) ORDER BY \"updated_at\", \"id\""
+        );
+    }
+
+    #[test]
+    fn key_sample_only_on_postgres() {
+        let keys = vec!["code".to_string()];
+
+        assert_eq!(
+            SqlBuilder::<PostgresBackend>::new().key_sample("items", &keys, None, 2.5),
+            Some(
+                "SELECT \"code\" FROM items TABLESAMPLE SYSTEM (2.5) \
+                 WHERE (\"code\" IS NOT NULL) ORDER BY \"code\""
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            SqlBuilder::<MysqlBackend>::new().key_sample("items", &keys, None, 2.5),
+            None
+        );
+    }
+
+    #[test]
+    fn create_table_quotes_columns_per_backend() {
+        let columns = vec![
+            ColumnDef {
+                name: "id".to_string(),
+                sql_type: "BIGINT".to_string(),
+                nullable: false,
+            },
+            ColumnDef {
+                name: "name".to_string(),
+                sql_type: "TEXT".to_string(),
+                nullable: true,
+            },
+        ];
+        let keys = vec!["id".to_string()];
+
+        assert_eq!(
+            RdbmsSqlBuilder::new(DatabaseKind::Postgres).create_table("users", &columns, &keys),
+            "CREATE TABLE IF NOT EXISTS users (\"id\" BIGINT NOT NULL, \"name\" TEXT, PRIMARY KEY (\"id\"))"
+        );
+        assert_eq!(
+            RdbmsSqlBuilder::new(DatabaseKind::Mysql).create_table("users", &columns, &[]),
+            "CREATE TABLE IF NOT EXISTS users (id BIGINT NOT NULL, name TEXT)"
+        );
+    }
+
     #[test]
     fn literal_string_escapes_single_quote() {
         assert_eq!(
@@ -1354,6 +2611,7 @@ mod tests {
             WriteMode::Upsert,
             &keys,
             &columns,
+            &ColumnTypes::default(),
             &rows,
         )
         .unwrap();
@@ -1392,6 +2650,7 @@ mod tests {
             WriteMode::Insert,
             &[],
             &columns,
+            &ColumnTypes::default(),
             &rows,
         )
         .unwrap();
@@ -1425,6 +2684,7 @@ mod tests {
             WriteMode::Upsert,
             &keys,
             &columns,
+            &ColumnTypes::default(),
             &rows,
         )
         .unwrap();
@@ -1455,6 +2715,7 @@ mod tests {
             WriteMode::Upsert,
             &[],
             &columns,
+            &ColumnTypes::default(),
             &rows,
         )
         .unwrap_err();
@@ -1472,6 +2733,7 @@ mod tests {
             WriteMode::Insert,
             &[],
             &columns,
+            &ColumnTypes::default(),
             &rows,
         )
         .unwrap_err();
@@ -1534,6 +2796,523 @@ mod tests {
         assert_eq!(query.params, vec![UnifiedValue::Int(1)]);
     }
 
+    fn two_users() -> (Vec<String>, Vec<Vec<UnifiedValue>>) {
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let rows = vec![
+            vec![
+                UnifiedValue::Int(1),
+                UnifiedValue::String("Alice".to_string()),
+            ],
+            vec![
+                UnifiedValue::Int(2),
+                UnifiedValue::String("Bob".to_string()),
+            ],
+        ];
+        (columns, rows)
+    }
+
+    #[test]
+    fn batched_update_joins_values_per_backend() {
+        let (columns, rows) = two_users();
+        let keys = vec!["id".to_string()];
+
+        let pg = WriteSqlBuilder::<PostgresBackend>::build_many(
+            "users",
+            WriteMode::Update,
+            &keys,
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            pg.sql,
+            "UPDATE users AS t SET \"name\" = v.\"name\" \
+             FROM (VALUES ($1, $2), ($3, $4)) AS v (\"id\", \"name\") \
+             WHERE t.\"id\" = v.\"id\""
+        );
+        assert_eq!(pg.params.len(), 4);
+
+        let my = WriteSqlBuilder::<MysqlBackend>::build_many(
+            "users",
+            WriteMode::Update,
+            &keys,
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            my.sql,
+            "UPDATE users AS t JOIN (SELECT ? AS id, ? AS name UNION ALL SELECT ?, ?) AS v \
+             ON t.id = v.id SET t.name = v.name"
+        );
+        assert_eq!(my.params, rows.concat());
+    }
+
+    #[test]
+    fn postgres_batched_update_casts_values_to_column_types() {
+        let (columns, rows) = two_users();
+        let keys = vec!["id".to_string()];
+        let types = ColumnTypes::new(vec![
+            ("id".to_string(), "integer".to_string()),
+            ("name".to_string(), "character varying".to_string()),
+        ]);
+
+        let pg = WriteSqlBuilder::<PostgresBackend>::build_many(
+            "users",
+            WriteMode::Update,
+            &keys,
+            &columns,
+            &types,
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            pg.sql,
+            "UPDATE users AS t SET \"name\" = v.\"name\" \
+             FROM (VALUES (CAST($1 AS integer), CAST($2 AS character varying)), \
+             (CAST($3 AS integer), CAST($4 AS character varying))) AS v (\"id\", \"name\") \
+             WHERE t.\"id\" = v.\"id\""
+        );
+
+        // MySQL 的派生表不需要类型转换
+        let my = WriteSqlBuilder::<MysqlBackend>::build_many(
+            "users",
+            WriteMode::Update,
+            &keys,
+            &columns,
+            &types,
+            &rows,
+        )
+        .unwrap();
+        assert!(!my.sql.contains("CAST"));
+    }
+
+    #[test]
+    fn batched_delete_uses_in_list_for_single_and_composite_keys() {
+        let (columns, rows) = two_users();
+
+        let single = WriteSqlBuilder::<PostgresBackend>::build_many(
+            "users",
+            WriteMode::Delete,
+            &["id".to_string()],
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(single.sql, "DELETE FROM users WHERE \"id\" IN ($1, $2)");
+        assert_eq!(
+            single.params,
+            vec![UnifiedValue::Int(1), UnifiedValue::Int(2)]
+        );
+
+        let composite = WriteSqlBuilder::<MysqlBackend>::build_many(
+            "users",
+            WriteMode::Delete,
+            &["name".to_string(), "id".to_string()],
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            composite.sql,
+            "DELETE FROM users WHERE (id, name) IN ((?, ?), (?, ?))"
+        );
+        assert_eq!(composite.params, rows.concat());
+    }
+
+    #[test]
+    fn soft_delete_updates_deleted_at_by_key() {
+        let (columns, rows) = two_users();
+        let history = HistoryColumns {
+            deleted_at: "removed_at".to_string(),
+            ..Default::default()
+        };
+
+        let query = WriteSqlBuilder::<PostgresBackend>::build_soft_delete(
+            "users",
+            &["id".to_string()],
+            &history,
+            &columns,
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            query.sql,
+            "UPDATE users SET \"removed_at\" = CURRENT_TIMESTAMP WHERE \"id\" IN ($1, $2)"
+        );
+        assert_eq!(
+            query.params,
+            vec![UnifiedValue::Int(1), UnifiedValue::Int(2)]
+        );
+    }
+
+    #[test]
+    fn scd2_closes_changed_versions_then_inserts_new_ones() {
+        let (columns, rows) = two_users();
+        let keys = vec!["id".to_string()];
+        let history = HistoryColumns::default();
+
+        let pg = WriteSqlBuilder::<PostgresBackend>::build_scd2(
+            "users",
+            &keys,
+            &history,
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            pg[0].sql,
+            "UPDATE users AS t SET \"valid_to\" = CURRENT_TIMESTAMP, \"is_current\" = FALSE \
+             FROM (VALUES ($1, $2), ($3, $4)) AS v (\"id\", \"name\") \
+             WHERE t.\"id\" = v.\"id\" AND t.\"is_current\" = TRUE \
+             AND NOT (t.\"name\" IS NOT DISTINCT FROM v.\"name\")"
+        );
+        assert_eq!(
+            pg[1].sql,
+            "INSERT INTO users (\"id\", \"name\", \"valid_from\", \"is_current\") \
+             SELECT v.\"id\", v.\"name\", CURRENT_TIMESTAMP, TRUE \
+             FROM (VALUES ($1, $2), ($3, $4)) AS v (\"id\", \"name\") \
+             WHERE NOT EXISTS (SELECT 1 FROM users AS c WHERE c.\"id\" = v.\"id\" AND c.\"is_current\" = TRUE)"
+        );
+        assert_eq!(pg[1].params, rows.concat());
+
+        let my = WriteSqlBuilder::<MysqlBackend>::build_scd2(
+            "users",
+            &keys,
+            &history,
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            my[0].sql,
+            "UPDATE users AS t JOIN (SELECT ? AS id, ? AS name UNION ALL SELECT ?, ?) AS v \
+             ON t.id = v.id SET t.valid_to = CURRENT_TIMESTAMP, t.is_current = FALSE \
+             WHERE t.is_current = TRUE AND NOT (t.name <=> v.name)"
+        );
+
+        let close = WriteSqlBuilder::<MysqlBackend>::build_scd2_close(
+            "users",
+            &keys,
+            &history,
+            &keys,
+            &ColumnTypes::default(),
+            &[vec![UnifiedValue::Int(1)]],
+        )
+        .unwrap();
+        assert_eq!(
+            close.sql,
+            "UPDATE users AS t JOIN (SELECT ? AS id) AS v ON t.id = v.id \
+             SET t.valid_to = CURRENT_TIMESTAMP, t.is_current = FALSE WHERE t.is_current = TRUE"
+        );
+    }
+
+    #[test]
+    fn scd2_without_tracked_changes_only_inserts() {
+        let (columns, rows) = two_users();
+        let keys = vec!["id".to_string(), "name".to_string()];
+        let queries = WriteSqlBuilder::<MysqlBackend>::build_scd2(
+            "users",
+            &keys,
+            &HistoryColumns::default(),
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(queries.len(), 1);
+        assert!(queries[0].sql.starts_with("INSERT INTO users"));
+
+        let history = HistoryColumns {
+            tracked_columns: vec!["email".to_string()],
+            ..Default::default()
+        };
+        let err = WriteSqlBuilder::<MysqlBackend>::build_scd2(
+            "users",
+            &["id".to_string()],
+            &history,
+            &columns,
+            &ColumnTypes::default(),
+            &rows,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("email"));
+    }
+
+    #[test]
+    fn scd2_chunks_split_on_repeated_keys() {
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let row = |id: i64, name: &str| {
+            vec![
+                UnifiedValue::Int(id),
+                UnifiedValue::String(name.to_string()),
+            ]
+        };
+        let rows = vec![row(1, "a"), row(2, "b"), row(1, "c"), row(3, "d")];
+        let keys = vec!["id".to_string()];
+
+        let chunks = write_chunks(&columns, &keys, &rows, 10, true).unwrap();
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+            vec![2, 2]
+        );
+
+        let chunks = write_chunks(&columns, &keys, &rows, 3, false).unwrap();
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+    }
+
+    #[test]
+    fn rows_per_statement_respects_bind_param_limit() {
+        let columns: Vec<String> = (0..1000).map(|i| format!("c{}", i)).collect();
+        let keys = vec!["c0".to_string()];
+
+        assert_eq!(
+            WriteSqlBuilder::<PostgresBackend>::rows_per_statement(
+                WriteMode::Insert,
+                &keys,
+                &columns,
+                1000
+            ),
+            65
+        );
+        assert_eq!(
+            WriteSqlBuilder::<MysqlBackend>::rows_per_statement(
+                WriteMode::Delete,
+                &keys,
+                &columns,
+                1000
+            ),
+            1000
+        );
+        assert_eq!(
+            WriteSqlBuilder::<MysqlBackend>::rows_per_statement(
+                WriteMode::Update,
+                &keys,
+                &columns[..2],
+                0
+            ),
+            1
+        );
+    }
+
+    /// 连接 `RELUS_TEST_PG_URL` 指向的 PostgreSQL，未配置时跳过需要真实数据库的测试
+    async fn test_pg_pool() -> Result<Option<sqlx::PgPool>> {
+        let Ok(url) = std::env::var("RELUS_TEST_PG_URL") else {
+            return Ok(None);
+        };
+        Ok(Some(
+            sqlx::postgres::PgPoolOptions::new()
+                .max_connections(1)
+                .connect(&url)
+                .await?,
+        ))
+    }
+
+    #[tokio::test]
+    async fn postgres_batched_update_mixes_null_with_typed_values() -> Result<()> {
+        let Some(pg) = test_pg_pool().await? else {
+            return Ok(());
+        };
+        let pool = Arc::new(RdbmsPool::Postgres(pg.clone()));
+        let executor = pool.executor();
+        let table = "relus_test_batch_update_nulls";
+        executor
+            .execute(&format!("DROP TABLE IF EXISTS {table}"))
+            .await?;
+        executor
+            .execute(&format!(
+                "CREATE TABLE {table} (id INT PRIMARY KEY, amount INT, \
+                 price NUMERIC(10, 2), updated_at TIMESTAMP, note INT)"
+            ))
+            .await?;
+        executor
+            .execute(&format!(
+                "INSERT INTO {table} VALUES \
+                 (1, 1, 1.00, '2024-01-01 00:00:00', 1), \
+                 (2, 2, 2.00, '2024-01-01 00:00:00', 2), \
+                 (3, 3, 3.00, '2024-01-01 00:00:00', 3)"
+            ))
+            .await?;
+
+        let updated_at = chrono::NaiveDate::from_ymd_opt(2024, 6, 1)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap();
+        // amount / price / updated_at 同一列混有 NULL 和有值的行，note 整列为 NULL
+        let columns: Vec<String> = ["id", "amount", "price", "updated_at", "note"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        let rows = vec![
+            vec![
+                UnifiedValue::Int(1),
+                UnifiedValue::Null,
+                UnifiedValue::Decimal(rust_decimal::Decimal::new(950, 2)),
+                UnifiedValue::Null,
+                UnifiedValue::Null,
+            ],
+            vec![
+                UnifiedValue::Int(2),
+                UnifiedValue::Int(20),
+                UnifiedValue::Null,
+                UnifiedValue::DateTime(updated_at),
+                UnifiedValue::Null,
+            ],
+        ];
+        let result = execute_rdbms_write(
+            &pool,
+            executor.as_ref(),
+            table,
+            WriteMode::Update,
+            &["id".to_string()],
+            &HistoryColumns::default(),
+            WriteRows::new(columns, rows),
+            100,
+        )
+        .await;
+
+        let stored: Vec<(
+            i32,
+            Option<i32>,
+            Option<rust_decimal::Decimal>,
+            Option<chrono::NaiveDateTime>,
+            Option<i32>,
+        )> = sqlx::query_as(&format!(
+            "SELECT id, amount, price, updated_at, note FROM {table} ORDER BY id"
+        ))
+        .fetch_all(&pg)
+        .await?;
+        executor.execute(&format!("DROP TABLE {table}")).await?;
+
+        assert_eq!(result?, 2);
+        let initial = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .unwrap();
+        assert_eq!(
+            stored,
+            vec![
+                (
+                    1,
+                    None,
+                    Some(rust_decimal::Decimal::new(950, 2)),
+                    None,
+                    None
+                ),
+                (2, Some(20), None, Some(updated_at), None),
+                (
+                    3,
+                    Some(3),
+                    Some(rust_decimal::Decimal::new(300, 2)),
+                    Some(initial),
+                    Some(3)
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn postgres_scd2_compares_and_closes_null_values() -> Result<()> {
+        let Some(pg) = test_pg_pool().await? else {
+            return Ok(());
+        };
+        let pool = Arc::new(RdbmsPool::Postgres(pg.clone()));
+        let executor = pool.executor();
+        let table = "relus_test_scd2_nulls";
+        executor
+            .execute(&format!("DROP TABLE IF EXISTS {table}"))
+            .await?;
+        executor
+            .execute(&format!(
+                "CREATE TABLE {table} (id INT, amount INT, note INT, \
+                 valid_from TIMESTAMP, valid_to TIMESTAMP, is_current BOOLEAN)"
+            ))
+            .await?;
+        executor
+            .execute(&format!(
+                "INSERT INTO {table} (id, amount, note, valid_from, is_current) VALUES \
+                 (1, 1, NULL, CURRENT_TIMESTAMP, TRUE), \
+                 (2, 2, 2, CURRENT_TIMESTAMP, TRUE), \
+                 (4, NULL, NULL, CURRENT_TIMESTAMP, TRUE)"
+            ))
+            .await?;
+
+        // amount 混有 NULL 和整数，note 整列为 NULL；4 号与当前版本相同，不产生新版本
+        let columns: Vec<String> = ["id", "amount", "note"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        let row = |id: i64, amount: Option<i64>| {
+            vec![
+                UnifiedValue::Int(id),
+                amount.map_or(UnifiedValue::Null, UnifiedValue::Int),
+                UnifiedValue::Null,
+            ]
+        };
+        let keys = vec!["id".to_string()];
+        let history = HistoryColumns::default();
+        let written = execute_rdbms_write(
+            &pool,
+            executor.as_ref(),
+            table,
+            WriteMode::Scd2,
+            &keys,
+            &history,
+            WriteRows::new(
+                columns.clone(),
+                vec![
+                    row(1, None),
+                    row(2, Some(2)),
+                    row(3, Some(30)),
+                    row(4, None),
+                ],
+            ),
+            100,
+        )
+        .await;
+        let closed = execute_scd2_close(
+            &pool,
+            executor.as_ref(),
+            table,
+            &keys,
+            &history,
+            WriteRows::new(columns, vec![row(3, Some(30))]),
+            100,
+        )
+        .await;
+
+        let stored: Vec<(i32, Option<i32>, Option<i32>, bool, bool)> = sqlx::query_as(&format!(
+            "SELECT id, amount, note, is_current, valid_to IS NULL FROM {table} \
+             ORDER BY id, is_current"
+        ))
+        .fetch_all(&pg)
+        .await?;
+        executor.execute(&format!("DROP TABLE {table}")).await?;
+
+        assert_eq!(written?, 4);
+        assert_eq!(closed?, 1);
+        assert_eq!(
+            stored,
+            vec![
+                (1, Some(1), None, false, false),
+                (1, None, None, true, true),
+                (2, Some(2), Some(2), false, false),
+                (2, Some(2), None, true, true),
+                (3, Some(30), None, false, false),
+                (4, None, None, true, true),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn dialect_dispatch_stays_at_wrapper_boundary() {
         let source = include_str!("sql_builder.rs");
@@ -10,6 +10,7 @@ use super::sql_builder::{MysqlBackend, PostgresBackend, RdbmsSqlBuilder, SqlBuil
 use relus_common::data_source_config::DataSourceConfig;
 use relus_common::job_config::JobConfig;
 use relus_common::resp::BaseDbQuery;
+use tracing::info;
 
 /// Database parameters trait for resolving connection info
 pub trait DbParams {
@@ -86,6 +87,30 @@ pub async fn get_pool_from_output(cfg: &JobConfig) -> Result<Arc<RdbmsPool>> {
     get_pool_for(&cfg.target).await
 }
 
+/// 依次执行数据源配置的 pre_sql / post_sql，任一条失败即返回错误
+///
+/// 语句以文本协议执行，不受预编译语句可用语法的限制。
+/// `stage` 用于日志和错误信息，例如 `target pre_sql`。
+pub async fn execute_hook_sql(
+    ds: &DataSourceConfig,
+    stage: &str,
+    statements: &[String],
+) -> Result<()> {
+    if statements.is_empty() {
+        return Ok(());
+    }
+    let pool = get_pool_for(ds).await?;
+    for sql in statements {
+        info!("[{}] {} 执行: {}", ds.name, stage, sql);
+        let result = match pool.as_ref() {
+            RdbmsPool::Postgres(p) => sqlx::Executor::execute(p, sql.as_str()).await.map(|_| ()),
+            RdbmsPool::Mysql(p) => sqlx::Executor::execute(p, sql.as_str()).await.map(|_| ()),
+        };
+        result.with_context(|| format!("{} 执行失败: {}", stage, sql))?;
+    }
+    Ok(())
+}
+
 /// Build query SQL with LIMIT and OFFSET
 pub fn build_query_sql(
     columns: &str,
@@ -187,6 +187,8 @@ async fn main() -> Result<()> {
                     "table": table_name,
                 }
             }),
+            pre_sql: Vec::new(),
+            post_sql: Vec::new(),
         },
         target: relus_common::data_source_config::DataSourceConfig {
             name: "dummy_target".to_string(),
@@ -195,6 +197,8 @@ async fn main() -> Result<()> {
             query_sql: None,
             writer_mode: None,
             config: json!({}),
+            pre_sql: Vec::new(),
+            post_sql: Vec::new(),
         },
         column_mapping: BTreeMap::new(),
         column_types: None,
@@ -203,6 +207,13 @@ async fn main() -> Result<()> {
         channel_buffer_size: None,
         job_id: None,
         schedule: None,
+        tables: Vec::new(),
+        migrate: None,
+        schema_discovery: None,
+        error_limit: None,
+        retry: None,
+        writer_threads: None,
+        speed: None,
     };
 
     // 创建连接池
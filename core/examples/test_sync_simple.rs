@@ -206,6 +206,8 @@ async fn main() {
                 "key_columns": ["id"]
             }
         }),
+        pre_sql: Vec::new(),
+        post_sql: Vec::new(),
     };
 
     let output = DataSourceConfig {
@@ -226,6 +228,8 @@ async fn main() {
                 "key_columns": ["id"]
             }
         }),
+        pre_sql: Vec::new(),
+        post_sql: Vec::new(),
     };
 
     // 扩展的列映射配置
@@ -256,6 +260,13 @@ async fn main() {
         channel_buffer_size: None,
         job_id: None,
         schedule: None,
+        tables: Vec::new(),
+        migrate: None,
+        schema_discovery: None,
+        error_limit: None,
+        retry: None,
+        writer_threads: None,
+        speed: None,
     };
 
     println!("配置信息:");
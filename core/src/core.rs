@@ -1,7 +1,10 @@
 pub mod cli;
+pub mod dispatch;
+pub mod migrate;
 pub mod pipeline_executor;
 pub mod progress;
 pub mod registry;
 pub mod runner;
+pub mod schema_evolution;
 pub mod scheduler;
 pub mod serve;
@@ -1,6 +1,9 @@
 // cli 命令行参数解析
 
-use crate::core::runner::RunStatus;
+use crate::checkpoint_store_path;
+use crate::core::runner::{start_task_with_checkpoint, RunResult, RunStatus};
+use crate::core::scheduler::checkpoint::{CheckpointStore, RunRecord, SplitLog};
+use crate::core::schema_evolution::SchemaChangeLog;
 use crate::core::serve::start_job;
 use crate::init_and_watch_config;
 use crate::run_scheduler;
@@ -20,6 +23,8 @@ use sqlx::{MySqlPool, PgPool, Row};
 use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Parser)]
 #[command(name = "Relus CLI")]
@@ -40,6 +45,11 @@ pub enum Commands {
         #[arg(short, long)]
         config: PathBuf,
     },
+    /// 续跑一次批量同步：只执行该次运行中失败或未执行的分片
+    Resume {
+        #[arg(short, long)]
+        run_id: String,
+    },
     ListTables {
         #[arg(short = 'u', long)]
         db_url: String,
@@ -111,9 +121,24 @@ pub async fn run_cli(cmd: Commands) -> Result<()> {
         }
         Commands::Sync { config } => {
             init_and_watch_config();
-            let cfg = read_job_config(&config)?;
-            validate_job_identifiers(&cfg)?;
-            let result = start_job(cfg).await?;
+            let (job_name, cfg) = load_job_config(&config)?;
+            let result = sync_recorded(job_name, cfg).await?;
+            print_run_result(&result);
+        }
+        Commands::Resume { run_id } => {
+            init_and_watch_config();
+            let store = Arc::new(open_checkpoint_store()?);
+            let Some(record) = store.load_run(&run_id) else {
+                bail!("run '{}' 不存在", run_id);
+            };
+            let pending = store
+                .load_splits(&run_id, None)?
+                .iter()
+                .filter(|s| !s.done)
+                .count();
+            println!("续跑 run '{}'（{} 个未完成分片）", run_id, pending);
+            let splits = SplitLog::resume(store, &run_id);
+            let result = run_with_splits(record.config, splits).await?;
             print_run_result(&result);
         }
         Commands::ListTables { db_url, db_type } => {
@@ -281,7 +306,50 @@ fn validate_database_identifiers(db_config: &relus_common::DbConfig) -> Result<(
     Ok(())
 }
 
-fn print_run_result(result: &crate::core::runner::RunResult) {
+fn open_checkpoint_store() -> Result<CheckpointStore> {
+    let path = checkpoint_store_path();
+    CheckpointStore::open(&path)
+        .with_context(|| format!("打开 checkpoint 存储失败: {}", path.display()))
+}
+
+/// 同步并记录分片；存储不可用（如被运行中的 scheduler 占用）时不记录，直接同步
+async fn sync_recorded(job_name: String, cfg: JobConfig) -> Result<RunResult> {
+    if cfg.is_cdc() {
+        return start_job(cfg).await;
+    }
+    let store = match open_checkpoint_store() {
+        Ok(store) => Arc::new(store),
+        Err(e) => {
+            eprintln!("{:#}，本次运行不记录分片", e);
+            return start_job(cfg).await;
+        }
+    };
+    let run_id = format!("{}-{}", job_name, chrono::Utc::now().format("%Y%m%d%H%M%S"));
+    let record = RunRecord {
+        job_name,
+        config: cfg.clone(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let splits = SplitLog::start(store, &run_id, &record)?;
+    println!(
+        "run id: {}（失败后可执行 resume -r {} 续跑）",
+        run_id, run_id
+    );
+    run_with_splits(cfg, splits).await
+}
+
+async fn run_with_splits(cfg: JobConfig, splits: SplitLog) -> Result<RunResult> {
+    start_task_with_checkpoint(
+        Arc::new(cfg),
+        CancellationToken::new(),
+        None,
+        Some(splits),
+        SchemaChangeLog::default(),
+    )
+    .await
+}
+
+fn print_run_result(result: &RunResult) {
     match result.status {
         RunStatus::Shutdown => {
             println!(
@@ -299,6 +367,27 @@ fn print_run_result(result: &crate::core::runner::RunResult) {
             );
         }
     }
+    if result.stats.retries > 0 {
+        println!(" 重试 {} 次", result.stats.retries);
+    }
+    if let Some(speed) = &result.stats.speed {
+        println!(
+            " 限速 {} rec/s, {} B/s，读取 {:.0} rec/s, {:.0} B/s，写入 {:.0} rec/s, {:.0} B/s",
+            speed
+                .record_limit
+                .map_or_else(|| "-".to_string(), |v| v.to_string()),
+            speed
+                .byte_limit
+                .map_or_else(|| "-".to_string(), |v| v.to_string()),
+            speed.read_record_rate,
+            speed.read_byte_rate,
+            speed.record_rate,
+            speed.byte_rate
+        );
+    }
+    for change in &result.schema_changes {
+        println!(" 结构变更: {}", change);
+    }
 }
 
 async fn list_tables_postgres(pool: &PgPool) -> Result<Vec<String>> {
@@ -0,0 +1,287 @@
+//! Reader → Writer 分发（N:M）
+//!
+//! 配置 `writer_threads` 后，一张表的 N 个 Reader 把批次交给同一个分发器，
+//! 分发器按 `key_columns` 的哈希把行路由到 M 个 Writer：
+//! - 同一 key 的行总是落在同一个 Writer 上，CDC 事件的先后顺序得以保持
+//! - 没有 key_columns 时整批轮询分发
+//!
+//! 一个批次被拆到多个 Writer 后，只有所有分片都确认写入，该批次的位点才能保存；
+//! [`AckTracker`] 按分发顺序跟踪各批次的确认情况，保证 checkpoint 不超前。
+
+use relus_common::types::UnifiedValue;
+use relus_common::MappingRow;
+use serde_json::Value as JsonValue;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// 按 key 列把行路由到 Writer
+pub struct KeyRouter {
+    key_columns: Vec<String>,
+    writers: usize,
+    /// 没有 key_columns 时下一个批次的 Writer
+    next: usize,
+}
+
+impl KeyRouter {
+    pub fn new(key_columns: Vec<String>, writers: usize) -> Self {
+        Self {
+            key_columns,
+            writers: writers.max(1),
+            next: 0,
+        }
+    }
+
+    /// 把一批行按 Writer 分组，返回 `writers` 个分组（可能为空），组内保持原有顺序
+    pub fn route(&mut self, rows: Vec<MappingRow>) -> Vec<Vec<MappingRow>> {
+        let mut parts: Vec<Vec<MappingRow>> = (0..self.writers).map(|_| Vec::new()).collect();
+        if self.key_columns.is_empty() {
+            parts[self.next] = rows;
+            self.next = (self.next + 1) % self.writers;
+            return parts;
+        }
+        for row in rows {
+            let writer = self.writer_of(&row);
+            parts[writer].push(row);
+        }
+        parts
+    }
+
+    fn writer_of(&self, row: &MappingRow) -> usize {
+        if self.writers == 1 {
+            return 0;
+        }
+        let mut hasher = DefaultHasher::new();
+        for column in &self.key_columns {
+            match row.get_value(column) {
+                Some(value) => hash_value(value, &mut hasher),
+                None => u8::MAX.hash(&mut hasher),
+            }
+        }
+        (hasher.finish() % self.writers as u64) as usize
+    }
+}
+
+/// 按 `to_canonical` 后的值哈希，不复制值也不格式化字符串：
+/// Option 变体与标准变体（OptI64(Some(1)) / Int(1)）按同一个值路由
+fn hash_value<H: Hasher>(value: &UnifiedValue, hasher: &mut H) {
+    match value {
+        UnifiedValue::Null
+        | UnifiedValue::OptI64(None)
+        | UnifiedValue::OptF64(None)
+        | UnifiedValue::OptBool(None)
+        | UnifiedValue::OptDecimal(None)
+        | UnifiedValue::OptDateTime(None) => 0u8.hash(hasher),
+        UnifiedValue::Bool(b) | UnifiedValue::OptBool(Some(b)) => (1u8, b).hash(hasher),
+        UnifiedValue::Int(n) | UnifiedValue::OptI64(Some(n)) => (2u8, n).hash(hasher),
+        UnifiedValue::Float(n) | UnifiedValue::OptF64(Some(n)) => (3u8, n.to_bits()).hash(hasher),
+        UnifiedValue::Decimal(d) | UnifiedValue::OptDecimal(Some(d)) => (4u8, d).hash(hasher),
+        UnifiedValue::String(s) => (5u8, s).hash(hasher),
+        UnifiedValue::Bytes(b) => (6u8, b).hash(hasher),
+        UnifiedValue::Date(d) => (7u8, d).hash(hasher),
+        UnifiedValue::Time(t) => (8u8, t).hash(hasher),
+        UnifiedValue::DateTime(dt) | UnifiedValue::OptDateTime(Some(dt)) => (9u8, dt).hash(hasher),
+        UnifiedValue::Json(j) => {
+            10u8.hash(hasher);
+            hash_json(j, hasher);
+        }
+        UnifiedValue::Array(items) => {
+            (11u8, items.len()).hash(hasher);
+            for item in items {
+                hash_value(item, hasher);
+            }
+        }
+    }
+}
+
+fn hash_json<H: Hasher>(value: &JsonValue, hasher: &mut H) {
+    match value {
+        JsonValue::Null => 0u8.hash(hasher),
+        JsonValue::Bool(b) => (1u8, b).hash(hasher),
+        JsonValue::Number(n) => {
+            2u8.hash(hasher);
+            match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => i.hash(hasher),
+                (None, Some(u)) => u.hash(hasher),
+                _ => n.as_f64().map(f64::to_bits).hash(hasher),
+            }
+        }
+        JsonValue::String(s) => (3u8, s).hash(hasher),
+        JsonValue::Array(items) => {
+            (4u8, items.len()).hash(hasher);
+            for item in items {
+                hash_json(item, hasher);
+            }
+        }
+        JsonValue::Object(map) => {
+            (5u8, map.len()).hash(hasher);
+            for (key, item) in map {
+                key.hash(hasher);
+                hash_json(item, hasher);
+            }
+        }
+    }
+}
+
+/// 按分发顺序跟踪批次确认，返回可以保存的最新位点
+pub struct AckTracker {
+    /// 尚未推进的批次：(未确认的分片数, 位点)，队首序号为 `base`
+    batches: VecDeque<(usize, Option<Vec<u8>>)>,
+    base: u64,
+    /// 每个 Writer 已分发、未确认的批次序号；Writer 按接收顺序确认
+    queues: Vec<VecDeque<u64>>,
+}
+
+impl AckTracker {
+    pub fn new(writers: usize) -> Self {
+        Self {
+            batches: VecDeque::new(),
+            base: 0,
+            queues: (0..writers).map(|_| VecDeque::new()).collect(),
+        }
+    }
+
+    /// 登记一个已分发的批次，`writers` 为收到分片的 Writer；
+    /// 必须在分片发出之前调用。没有分片的批次可以立即推进。
+    pub fn dispatched(&mut self, writers: &[usize], position: Option<Vec<u8>>) -> Option<Vec<u8>> {
+        let seq = self.base + self.batches.len() as u64;
+        for &writer in writers {
+            if let Some(queue) = self.queues.get_mut(writer) {
+                queue.push_back(seq);
+            }
+        }
+        self.batches.push_back((writers.len(), position));
+        self.advance()
+    }
+
+    /// Writer 确认了一个分片
+    pub fn ack(&mut self, writer: usize) -> Option<Vec<u8>> {
+        let seq = self.queues.get_mut(writer)?.pop_front()?;
+        let index = seq.checked_sub(self.base)? as usize;
+        if let Some((remaining, _)) = self.batches.get_mut(index) {
+            *remaining = remaining.saturating_sub(1);
+        }
+        self.advance()
+    }
+
+    /// 弹出队首所有已全部确认的批次，返回其中最新的位点
+    fn advance(&mut self) -> Option<Vec<u8>> {
+        let mut latest = None;
+        while self
+            .batches
+            .front()
+            .is_some_and(|(remaining, _)| *remaining == 0)
+        {
+            if let Some((_, position)) = self.batches.pop_front() {
+                if position.is_some() {
+                    latest = position;
+                }
+            }
+            self.base += 1;
+        }
+        latest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use relus_common::types::UnifiedValue;
+
+    fn row(id: UnifiedValue, name: &str) -> MappingRow {
+        let mut row = MappingRow::simple();
+        row.insert_simple("id", id, "int");
+        row.insert_simple("name", UnifiedValue::String(name.to_string()), "string");
+        row
+    }
+
+    #[test]
+    fn same_key_always_routes_to_same_writer() {
+        let mut router = KeyRouter::new(vec!["id".to_string()], 4);
+        let rows: Vec<MappingRow> = (0..100)
+            .map(|i| row(UnifiedValue::Int(i % 10), "a"))
+            .collect();
+        let parts = router.route(rows);
+        assert_eq!(parts.iter().map(Vec::len).sum::<usize>(), 100);
+
+        let mut again = router.route(vec![row(UnifiedValue::OptI64(Some(3)), "b")]);
+        let writer = again
+            .iter()
+            .position(|p| !p.is_empty())
+            .unwrap_or(usize::MAX);
+        assert!(parts[writer]
+            .iter()
+            .any(|r| r.get_value("id") == Some(&UnifiedValue::Int(3))));
+        assert!(parts
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != writer)
+            .all(|(_, p)| p
+                .iter()
+                .all(|r| r.get_value("id") != Some(&UnifiedValue::Int(3)))));
+        assert_eq!(
+            again[writer]
+                .pop()
+                .and_then(|r| r.get_value("name").cloned()),
+            Some(UnifiedValue::String("b".to_string()))
+        );
+    }
+
+    #[test]
+    fn canonical_variants_hash_alike() {
+        let hash = |value: &UnifiedValue| {
+            let mut hasher = DefaultHasher::new();
+            hash_value(value, &mut hasher);
+            hasher.finish()
+        };
+        let decimal = rust_decimal::Decimal::new(1205, 2);
+        assert_eq!(
+            hash(&UnifiedValue::OptI64(Some(7))),
+            hash(&UnifiedValue::Int(7))
+        );
+        assert_eq!(
+            hash(&UnifiedValue::OptDecimal(Some(decimal))),
+            hash(&UnifiedValue::Decimal(decimal))
+        );
+        assert_eq!(hash(&UnifiedValue::OptF64(None)), hash(&UnifiedValue::Null));
+        assert_ne!(
+            hash(&UnifiedValue::Int(7)),
+            hash(&UnifiedValue::String("7".to_string()))
+        );
+        assert_eq!(
+            hash(&UnifiedValue::Json(serde_json::json!({"a": [1, "x"]}))),
+            hash(&UnifiedValue::Json(serde_json::json!({"a": [1, "x"]})))
+        );
+    }
+
+    #[test]
+    fn batches_round_robin_without_keys() {
+        let mut router = KeyRouter::new(Vec::new(), 2);
+        let first = router.route(vec![
+            row(UnifiedValue::Int(1), "a"),
+            row(UnifiedValue::Int(2), "b"),
+        ]);
+        let second = router.route(vec![row(UnifiedValue::Int(3), "c")]);
+        assert_eq!((first[0].len(), first[1].len()), (2, 0));
+        assert_eq!((second[0].len(), second[1].len()), (0, 1));
+    }
+
+    #[test]
+    fn checkpoint_advances_only_when_all_parts_acked_in_order() {
+        let mut tracker = AckTracker::new(2);
+        assert_eq!(tracker.dispatched(&[0, 1], Some(b"p1".to_vec())), None);
+        assert_eq!(tracker.dispatched(&[1], Some(b"p2".to_vec())), None);
+
+        // 批次 2 的唯一分片先确认，批次 1 还差 Writer-0
+        assert_eq!(tracker.ack(1), None);
+        assert_eq!(tracker.ack(1), None);
+        assert_eq!(tracker.ack(0), Some(b"p2".to_vec()));
+
+        // 没有分片的批次（全部为脏数据）立即推进
+        assert_eq!(
+            tracker.dispatched(&[], Some(b"p3".to_vec())),
+            Some(b"p3".to_vec())
+        );
+        assert_eq!(tracker.ack(0), None);
+    }
+}
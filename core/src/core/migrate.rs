@@ -0,0 +1,178 @@
+//! 整库迁移
+//!
+//! 运行前列出 source 库中匹配 include/exclude 的表，逐表发现结构并生成
+//! column_mapping / column_types / 主键，展开为多表任务；
+//! `create_tables` 开启时按源表结构在目标库创建缺少的表。
+
+use anyhow::{bail, Result};
+use relus_common::job_config::{JobConfig, TableJobConfig};
+use relus_connector_rdbms::connector::RdbmsConnector;
+use relus_connector_rdbms::pool::RdbmsPool;
+use relus_connector_rdbms::schema::{
+    create_table_sql, MetadataDiscoverer, RdbmsDiscoverer, TableSchema,
+};
+use relus_connector_rdbms::util::get_pool_for;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tracing::info;
+
+/// 将整库迁移任务展开为多表任务，非迁移任务原样返回
+///
+/// `tables` 中显式配置的表总会被迁移，其中已配置的字段覆盖发现的结果。
+pub async fn expand_migration(config: &JobConfig) -> Result<JobConfig> {
+    let Some(migrate) = &config.migrate else {
+        return Ok(config.clone());
+    };
+    if config.source.source_type != "database" || config.target.source_type != "database" {
+        bail!("整库迁移只支持 database → database");
+    }
+
+    let source_pool = get_pool_for(&config.source).await?;
+    let source = RdbmsConnector::new(source_pool.as_ref().clone());
+    let mut names: BTreeSet<String> = source
+        .list_tables()
+        .await?
+        .into_iter()
+        .filter(|table| migrate.matches(table))
+        .collect();
+    names.extend(config.tables.iter().map(|t| t.source_table.clone()));
+    if names.is_empty() {
+        bail!("source 库中没有匹配 include/exclude 的表");
+    }
+
+    let discoverer = RdbmsDiscoverer::new(Arc::clone(&source_pool), String::new());
+    let mut tables = Vec::with_capacity(names.len());
+    for name in &names {
+        let schema = discoverer.discover_table(name).await?;
+        let discovered = table_job_of(&schema);
+        let table = match config.tables.iter().find(|t| &t.source_table == name) {
+            Some(explicit) => overlay(discovered, explicit),
+            None => discovered,
+        };
+        tables.push((schema, table));
+    }
+
+    if migrate.create_tables {
+        let target_pool = get_pool_for(&config.target).await?;
+        create_missing_tables(&target_pool, &tables).await?;
+    }
+
+    info!("[migrate] 共 {} 张表: {:?}", names.len(), names);
+
+    let mut expanded = config.clone();
+    expanded.migrate = None;
+    expanded.tables = tables.into_iter().map(|(_, table)| table).collect();
+    Ok(expanded)
+}
+
+/// 由源表结构生成单表配置：同名映射、逻辑类型、主键作为 key_columns 与切分键
+fn table_job_of(schema: &TableSchema) -> TableJobConfig {
+    let keys = &schema.primary_keys;
+    TableJobConfig {
+        source_table: schema.table_name.clone(),
+        target_table: None,
+        column_mapping: schema.to_column_mapping(),
+        column_types: Some(schema.to_column_types()),
+        key_columns: (!keys.is_empty()).then(|| keys.clone()),
+        split_pk: (!keys.is_empty()).then(|| keys.join(",")),
+        writer_mode: None,
+    }
+}
+
+/// 显式配置的字段覆盖发现的结果
+fn overlay(discovered: TableJobConfig, explicit: &TableJobConfig) -> TableJobConfig {
+    TableJobConfig {
+        source_table: discovered.source_table,
+        target_table: explicit.target_table.clone().or(discovered.target_table),
+        column_mapping: if explicit.column_mapping.is_empty() {
+            discovered.column_mapping
+        } else {
+            explicit.column_mapping.clone()
+        },
+        column_types: explicit.column_types.clone().or(discovered.column_types),
+        key_columns: explicit.key_columns.clone().or(discovered.key_columns),
+        split_pk: explicit.split_pk.clone().or(discovered.split_pk),
+        writer_mode: explicit.writer_mode.clone().or(discovered.writer_mode),
+    }
+}
+
+async fn create_missing_tables(
+    target_pool: &RdbmsPool,
+    tables: &[(TableSchema, TableJobConfig)],
+) -> Result<()> {
+    let target_kind = target_pool.kind();
+    let existing: BTreeSet<String> = RdbmsConnector::new(target_pool.clone())
+        .list_tables()
+        .await?
+        .into_iter()
+        .map(|t| t.to_lowercase())
+        .collect();
+
+    let executor = target_pool.executor();
+    for (schema, table) in tables {
+        let target_table = table.target_table();
+        if existing.contains(&target_table.to_lowercase()) {
+            continue;
+        }
+        let ddl = create_table_sql(schema, target_kind, target_table);
+        info!("[migrate] 创建目标表 {}: {}", target_table, ddl);
+        executor.execute(&ddl).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use relus_connector_rdbms::schema::ColumnInfo;
+
+    fn orders_schema() -> TableSchema {
+        TableSchema::new("orders".to_string(), "mysql".to_string())
+            .with_columns(vec![
+                ColumnInfo::new("id".to_string(), "bigint".to_string(), "int".to_string()),
+                ColumnInfo::new(
+                    "created_at".to_string(),
+                    "datetime".to_string(),
+                    "timestamp".to_string(),
+                ),
+            ])
+            .with_primary_keys(vec!["id".to_string()])
+    }
+
+    #[test]
+    fn discovered_table_maps_columns_and_keys() {
+        let table = table_job_of(&orders_schema());
+        assert_eq!(
+            table.column_mapping.get("created_at").map(String::as_str),
+            Some("created_at")
+        );
+        assert_eq!(
+            table
+                .column_types
+                .as_ref()
+                .and_then(|t| t.get("created_at"))
+                .map(String::as_str),
+            Some("timestamp")
+        );
+        assert_eq!(table.key_columns, Some(vec!["id".to_string()]));
+        assert_eq!(table.split_pk.as_deref(), Some("id"));
+    }
+
+    #[test]
+    fn explicit_table_fields_win() {
+        let explicit = TableJobConfig {
+            source_table: "orders".to_string(),
+            target_table: Some("ods_orders".to_string()),
+            column_mapping: Default::default(),
+            column_types: None,
+            key_columns: None,
+            split_pk: None,
+            writer_mode: Some("upsert".to_string()),
+        };
+        let table = overlay(table_job_of(&orders_schema()), &explicit);
+        assert_eq!(table.target_table(), "ods_orders");
+        assert_eq!(table.writer_mode.as_deref(), Some("upsert"));
+        assert_eq!(table.column_mapping.len(), 2);
+        assert_eq!(table.split_pk.as_deref(), Some("id"));
+    }
+}
@@ -1,10 +1,14 @@
 //! Pipeline Executor 模块
 //!
-//! Reader → Channel → Writer 1:1
+//! Reader → Channel → Writer
 //!
 //! - 一个 Job 被 Reader split 为 N 个 ReadTask
-//! - Writer 以相同数量 N split，形成 N 个 1:1 Pair
+//! - 默认 Writer 以相同数量 N split，形成 N 个 1:1 Pair
+//! - 配置 `writer_threads` = M 时 Writer split 为 M 个任务，N 个 Reader 经分发器
+//!   按 key 哈希路由到 M 个 Writer（N:M），整张表作为一个调度单元，见 [`super::dispatch`]
+//! - 多表任务中每张表各自 split，所有表的 Pair 共享同一组 TaskGroup 与并发上限
 //! - 通过 TaskGroup + TaskExecutor 控制并发
+//! - 批量任务记录各表的分片，Pair 成功后标记完成，续跑时只执行未完成的分片
 //!
 //! Core 层负责 stream 消费、buffer 切分、RecordBuilder mapping 和 channel 发送
 
@@ -12,23 +16,31 @@ use anyhow::Result;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use indicatif::ProgressBar;
+use parking_lot::Mutex;
+use relus_common::app_config::manager::ConfigManager;
 use relus_common::constant::pipeline::{
     DEFAULT_BATCH_SIZE, DEFAULT_BUFFER_SIZE, DEFAULT_CHANNEL_NUMBER, DEFAULT_PER_GROUP_CHANNEL,
     DEFAULT_READER_THREADS,
 };
-use relus_common::pipeline::PipelineMessage;
+use relus_common::job_config::{RetryConfig, SpeedConfig};
+use relus_common::pipeline::{row_bytes, PipelineMessage, SpeedLimiter, SpeedStats};
 use relus_common::types::SourceType;
+use relus_common::{JobConfig, MappingRow};
+use relus_connector_rdbms::retry::RetryPolicy;
 use relus_reader::{DataReader, ReadTask};
-use relus_writer::{DataWriter, WriteTask};
+use relus_writer::{DataWriter, DirtyRecordCollector, WriteTask};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
+use super::dispatch::{AckTracker, KeyRouter};
 use super::progress::create_progress_bars;
+use super::scheduler::checkpoint::{CheckpointHandle, SplitLog};
 use crate::pipeline::RecordBuilder;
 
 // ==========================================
@@ -37,13 +49,49 @@ use crate::pipeline::RecordBuilder;
 
 #[derive(Clone)]
 struct PipelineRunContext {
-    reader: Arc<dyn DataReader>,
-    writer: Arc<dyn DataWriter>,
     buffer_size: usize,
     batch_size: usize,
-    record_builder: Arc<RecordBuilder>,
     cancel_token: CancellationToken,
     progress: PipelineProgress,
+    /// N:M 调度单元内同时运行的 Reader 数上限
+    channel_number: usize,
+    /// 读取侧全局限速器，所有 Pair 共享
+    read_speed: Option<SpeedLimiter>,
+    /// 已完成的表数 / 总表数，用于逐表进度日志
+    tables_done: Arc<AtomicUsize>,
+    table_count: usize,
+}
+
+/// 参与同步的一张表（单表任务即只有一张）
+pub struct TablePipeline {
+    /// 结果中使用的表名
+    pub name: String,
+    pub reader: Arc<dyn DataReader>,
+    pub writer: Arc<dyn DataWriter>,
+    pub job_config: Arc<JobConfig>,
+    /// 位点持久化句柄，批次写入确认后保存 reader 提供的位点
+    pub checkpoint: Option<CheckpointHandle>,
+    /// 分片记录句柄，Pair 成功后标记其分片已完成；续跑时只执行未完成的分片
+    pub splits: Option<SplitLog>,
+}
+
+/// 运行中的表：某张表失败只取消该表的 Pair，不影响其他表
+struct TableRuntime {
+    index: usize,
+    name: String,
+    reader: Arc<dyn DataReader>,
+    writer: Arc<dyn DataWriter>,
+    record_builder: Arc<RecordBuilder>,
+    checkpoint: Option<CheckpointHandle>,
+    splits: Option<SplitLog>,
+    /// 配置 error_limit 时的脏数据收集器，转换失败与写入失败的行都记入其中
+    dirty: Option<Arc<DirtyRecordCollector>>,
+    /// 该表读写共享的重试策略与重试计数
+    retry: RetryPolicy,
+    cancel_token: CancellationToken,
+    /// 尚未完成的 Pair 数（N:M 分发时整张表只算一个）与已写入条数
+    pending_pairs: AtomicUsize,
+    written: AtomicUsize,
 }
 
 #[derive(Clone)]
@@ -54,29 +102,63 @@ struct PipelineProgress {
 
 struct PairWork {
     pair_id: usize,
-    read_task: ReadTask,
-    write_task: WriteTask,
+    table: Arc<TableRuntime>,
+    tasks: PairTasks,
+}
+
+enum PairTasks {
+    /// Reader 与 Writer 1:1
+    Paired {
+        read_task: ReadTask,
+        write_task: WriteTask,
+    },
+    /// 表的全部 Reader 经分发器按 key 路由到 `writer_threads` 个 Writer
+    Dispatched {
+        read_tasks: Vec<ReadTask>,
+        write_tasks: Vec<WriteTask>,
+        key_columns: Vec<String>,
+    },
 }
 
 struct GroupWork {
     group_id: usize,
     tasks: Vec<PairWork>,
     concurrency: usize,
+    table_count: usize,
 }
 
 struct PairResult {
     pair_id: usize,
+    table: Arc<TableRuntime>,
+    /// 该 Pair 负责的读取分片
+    task_ids: Vec<usize>,
     read_count: usize,
     write_count: usize,
     error: Option<anyhow::Error>,
     shutdown: bool,
 }
 
+/// 单张表的累计结果，只保留第一个错误
+#[derive(Default)]
+struct TableTally {
+    read: usize,
+    written: usize,
+    error: Option<anyhow::Error>,
+}
+
+impl TableTally {
+    fn merge(&mut self, other: TableTally) {
+        self.read += other.read;
+        self.written += other.written;
+        if self.error.is_none() {
+            self.error = other.error;
+        }
+    }
+}
+
 struct GroupResult {
     group_id: usize,
-    total_read: usize,
-    total_written: usize,
-    error: Option<anyhow::Error>,
+    tables: Vec<TableTally>,
     shutdown: bool,
 }
 
@@ -95,6 +177,12 @@ pub struct PipelineConfig {
     pub batch_size: usize,
     /// 是否使用事务
     pub use_transaction: bool,
+    /// 系统配置 `retry` 中的重试策略，任务未配置 `retry` 时使用
+    pub retry: Option<RetryConfig>,
+    /// Writer 并发数，None 时 Writer 与 Reader 分片 1:1 配对
+    pub writer_threads: Option<usize>,
+    /// 全局限速，读取与写入两侧各按此上限限速
+    pub speed: SpeedConfig,
 }
 
 impl Default for PipelineConfig {
@@ -106,6 +194,9 @@ impl Default for PipelineConfig {
             per_group_channel: DEFAULT_PER_GROUP_CHANNEL,
             batch_size: DEFAULT_BATCH_SIZE,
             use_transaction: true,
+            retry: None,
+            writer_threads: None,
+            speed: SpeedConfig::default(),
         }
     }
 }
@@ -113,9 +204,10 @@ impl Default for PipelineConfig {
 impl PipelineConfig {
     /// 从系统配置读取 pipeline 参数
     ///
-    /// 优先级：系统配置 (default.config.json) > 常量默认值
+    /// 优先级：系统配置 (default.config.json) > 常量默认值；
+    /// batch_size、writer_threads 与 speed 以任务配置为先
     pub fn from_system_config(job_config: &relus_common::JobConfig) -> Self {
-        let (sys_reader, sys_buffer, sys_channel, sys_per_group, sys_batch, sys_tx) =
+        let (sys_reader, sys_buffer, sys_channel, sys_per_group, sys_batch, sys_tx, sys_writer) =
             crate::get_config_manager()
                 .map(|mgr| {
                     let m = mgr.read();
@@ -136,9 +228,12 @@ impl PipelineConfig {
                             .and_then(|v| v.as_i64())
                             .map(|v| v as usize),
                         m.get("pipeline.use_transaction").and_then(|v| v.as_bool()),
+                        m.get("pipeline.writer_threads")
+                            .and_then(|v| v.as_i64())
+                            .map(|v| v as usize),
                     )
                 })
-                .unwrap_or((None, None, None, None, None, None));
+                .unwrap_or((None, None, None, None, None, None, None));
 
         Self {
             reader_threads: sys_reader.unwrap_or(DEFAULT_READER_THREADS),
@@ -150,10 +245,59 @@ impl PipelineConfig {
                 .or(sys_batch)
                 .unwrap_or(DEFAULT_BATCH_SIZE),
             use_transaction: sys_tx.unwrap_or(true),
+            retry: crate::get_config_manager().and_then(|mgr| system_retry_config(&mgr.read())),
+            writer_threads: job_config.writer_threads.or(sys_writer).filter(|&n| n > 0),
+            speed: job_config
+                .speed
+                .clone()
+                .unwrap_or_default()
+                .or(&system_speed_config()),
         }
     }
 }
 
+/// 读取系统配置中的 `pipeline.speed.*`
+fn system_speed_config() -> SpeedConfig {
+    crate::get_config_manager()
+        .map(|mgr| {
+            let m = mgr.read();
+            let get = |key: &str| {
+                m.get(&format!("pipeline.speed.{}", key))
+                    .and_then(|v| v.as_i64())
+                    .filter(|&v| v > 0)
+                    .map(|v| v as u64)
+            };
+            SpeedConfig {
+                record: get("record"),
+                byte: get("byte"),
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// 读取系统配置中的 `retry.*`，三项都未配置时返回 None，缺少的项使用默认值
+fn system_retry_config(mgr: &ConfigManager) -> Option<RetryConfig> {
+    let get = |key: &str| {
+        mgr.get(&format!("retry.{}", key))
+            .and_then(|v| v.as_i64())
+            .map(|v| v.max(0) as u64)
+    };
+    let (max_retries, initial, max) = (
+        get("max_retries"),
+        get("initial_backoff_ms"),
+        get("max_backoff_ms"),
+    );
+    if max_retries.is_none() && initial.is_none() && max.is_none() {
+        return None;
+    }
+    let default = RetryConfig::default();
+    Some(RetryConfig {
+        max_retries: max_retries.map_or(default.max_retries, |v| v as usize),
+        initial_backoff_ms: initial.unwrap_or(default.initial_backoff_ms),
+        max_backoff_ms: max.unwrap_or(default.max_backoff_ms),
+    })
+}
+
 /// 管道执行统计
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PipelineStats {
@@ -163,6 +307,27 @@ pub struct PipelineStats {
     pub elapsed_secs: f64,
     pub throughput: f64,
     pub shutdown: bool,
+    /// 瞬时错误的重试次数（读取与写入合计）
+    #[serde(default)]
+    pub retries: usize,
+    /// 各表的执行结果
+    #[serde(default)]
+    pub tables: Vec<TableStats>,
+    /// 配置全局限速时写入侧的限速与实际速率
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed: Option<SpeedStats>,
+}
+
+/// 单张表的执行结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableStats {
+    pub table: String,
+    pub records_read: usize,
+    pub records_written: usize,
+    #[serde(default)]
+    pub retries: usize,
+    #[serde(default)]
+    pub error: Option<String>,
 }
 
 impl PipelineStats {
@@ -177,54 +342,85 @@ impl PipelineStats {
     }
 }
 
-/// Pipeline 的执行逻辑：Reader → Writer 1:1
+/// Pipeline 的执行逻辑：Reader → Writer 1:1，或配置 writer_threads 时 N:M 分发
 pub async fn start_run(
     config: PipelineConfig,
-    reader: Arc<dyn DataReader>,
-    writer: Arc<dyn DataWriter>,
-    job_config: Arc<relus_common::JobConfig>,
+    tables: Vec<TablePipeline>,
     cancel_token: CancellationToken,
 ) -> Result<PipelineStats> {
-    // 从 JobConfig 构建 RecordBuilder
+    run_paired_pipeline(&config, tables, cancel_token).await
+}
+
+/// 从 JobConfig 构建 RecordBuilder
+fn build_record_builder(job_config: &JobConfig) -> Result<RecordBuilder> {
     let source_type = job_config
         .source
         .source_type
         .parse::<SourceType>()
         .unwrap_or_else(|err| match err {});
-    let record_builder = Arc::new(
-        RecordBuilder::new(
-            job_config.column_mapping.clone(),
-            job_config.column_types.clone(),
-        )?
-        .with_source_type(source_type),
-    );
-
-    run_paired_pipeline(&config, reader, writer, record_builder, cancel_token).await
+    Ok(RecordBuilder::new(
+        job_config.column_mapping.clone(),
+        job_config.column_types.clone(),
+    )?
+    .with_source_type(source_type))
 }
 
+/// 每个已发送批次对应的位点（按发送顺序排列）
+type PendingCheckpoints = mpsc::UnboundedSender<Option<Vec<u8>>>;
+
 /// 从 Reader 获取 JsonStream，消费并通过 RecordBuilder mapping 后发送到 channel
 /// consume_stream_and_send
+#[allow(clippy::too_many_arguments)]
 async fn csas(
     pair_id: usize,
     reader: Arc<dyn DataReader>,
     task: &ReadTask,
     batch_size: usize,
     builder: &RecordBuilder,
+    dirty: Option<&DirtyRecordCollector>,
+    retry: &RetryPolicy,
     tx: &mpsc::Sender<PipelineMessage>,
     reader_bar: &ProgressBar,
+    pending: Option<&PendingCheckpoints>,
+    speed: Option<&SpeedLimiter>,
 ) -> Result<usize> {
-    let stream = reader.read_data(task).await?;
+    let mut stream = reader.read_data(task).await?;
     let mut sent = 0;
     let mut buffer = Vec::with_capacity(batch_size);
+    // 已从流中取出的行数；重试时重新打开分片并跳过这些行
+    let mut consumed = 0usize;
+    let mut skip = 0usize;
+    let mut attempt = 0;
 
-    futures::pin_mut!(stream);
     while let Some(result) = stream.next().await {
-        let json_val = result?;
+        let json_val = match result {
+            Ok(value) => value,
+            Err(e) if retry.should_retry(attempt, &e) => {
+                attempt += 1;
+                retry
+                    .wait(attempt, &e, &format!("Reader-{}", pair_id))
+                    .await;
+                stream = reader.read_data(task).await?;
+                skip = consumed;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        consumed += 1;
+        attempt = 0;
         buffer.push(json_val);
 
         if buffer.len() >= batch_size {
             let count = buffer.len();
-            let message = builder.build_message(&buffer)?;
+            let message = build_message(builder, &buffer, dirty).await?;
+            throttle(speed, &message).await;
+            if let Some(pending) = pending {
+                let _ = pending.send(buffer.last().and_then(|r| reader.checkpoint_of(r)));
+            }
             tx.send(message)
                 .await
                 .map_err(|e| anyhow::anyhow!("发送失败: {}", e))?;
@@ -237,7 +433,11 @@ async fn csas(
     // 发送残余数据
     if !buffer.is_empty() {
         let count = buffer.len();
-        let message = builder.build_message(&buffer)?;
+        let message = build_message(builder, &buffer, dirty).await?;
+        throttle(speed, &message).await;
+        if let Some(pending) = pending {
+            let _ = pending.send(buffer.last().and_then(|r| reader.checkpoint_of(r)));
+        }
         tx.send(message)
             .await
             .map_err(|e| anyhow::anyhow!("发送失败: {}", e))?;
@@ -249,25 +449,115 @@ async fn csas(
     Ok(sent)
 }
 
-async fn run_task_pair(pair: PairWork, ctx: PipelineRunContext) -> PairResult {
+/// 配置全局限速时按映射后批次的记录数与字节数取令牌，与写入侧使用同一口径
+async fn throttle(speed: Option<&SpeedLimiter>, message: &PipelineMessage) {
+    if let (Some(speed), PipelineMessage::DataBatch(rows)) = (speed, message) {
+        speed
+            .acquire(rows.len(), rows.iter().map(row_bytes).sum())
+            .await;
+    }
+}
+
+/// 把一批 JSON 行转换为 DataBatch
+///
+/// 有脏数据收集器时逐行转换，转换失败的行记为脏数据；批次即使为空也照常发送，
+/// 保证 writer 确认与位点一一对应。
+async fn build_message(
+    builder: &RecordBuilder,
+    items: &[serde_json::Value],
+    dirty: Option<&DirtyRecordCollector>,
+) -> Result<PipelineMessage> {
+    let Some(dirty) = dirty else {
+        return builder.build_message(items);
+    };
+    dirty.add_total(items.len());
+    let mut rows = Vec::with_capacity(items.len());
+    for item in items {
+        match builder.build(item) {
+            Ok(row) => rows.push(row),
+            Err(e) => dirty.record(item, &e).await?,
+        }
+    }
+    Ok(PipelineMessage::DataBatch(rows))
+}
+
+/// 按批次顺序把 writer 已确认批次的位点写入 checkpoint
+async fn persist_checkpoints(
+    pair_id: usize,
+    checkpoint: CheckpointHandle,
+    mut pending: mpsc::UnboundedReceiver<Option<Vec<u8>>>,
+    mut acks: mpsc::UnboundedReceiver<usize>,
+) {
+    while acks.recv().await.is_some() {
+        let Some(position) = pending.recv().await else {
+            break;
+        };
+        if let Some(position) = position {
+            if let Err(e) = checkpoint.save_async(position).await {
+                warn!("Pair-{} checkpoint 保存失败: {}", pair_id, e);
+            }
+        }
+    }
+}
+
+async fn run_work(pair: PairWork, ctx: PipelineRunContext) -> PairResult {
     let PairWork {
         pair_id,
-        read_task,
-        write_task,
+        table,
+        tasks,
     } = pair;
+    let task_ids = match &tasks {
+        PairTasks::Paired { read_task, .. } => vec![read_task.task_id],
+        PairTasks::Dispatched { read_tasks, .. } => read_tasks.iter().map(|t| t.task_id).collect(),
+    };
+    let result = match tasks {
+        PairTasks::Paired {
+            read_task,
+            write_task,
+        } => run_task_pair(pair_id, table, read_task, write_task, ctx).await,
+        PairTasks::Dispatched {
+            read_tasks,
+            write_tasks,
+            key_columns,
+        } => run_dispatched(pair_id, table, read_tasks, write_tasks, key_columns, ctx).await,
+    };
+    PairResult { task_ids, ..result }
+}
 
+async fn run_task_pair(
+    pair_id: usize,
+    table: Arc<TableRuntime>,
+    read_task: ReadTask,
+    mut write_task: WriteTask,
+    ctx: PipelineRunContext,
+) -> PairResult {
     let (tx, rx) = mpsc::channel(ctx.buffer_size);
     let was_cancelled = ctx.cancel_token.is_cancelled();
 
-    let r = Arc::clone(&ctx.reader);
-    let reader_for_cancel = Arc::clone(&ctx.reader);
-    let builder = Arc::clone(&ctx.record_builder);
-    let reader_cancel = ctx.cancel_token.clone();
+    // checkpoint：reader 记录每批位点，writer 每确认一批就保存对应位点
+    let (pending_tx, checkpoint_handle) = match table.checkpoint.clone() {
+        Some(checkpoint) => {
+            let (ack_tx, ack_rx) = mpsc::unbounded_channel();
+            let (pending_tx, pending_rx) = mpsc::unbounded_channel();
+            write_task.batch_ack = Some(ack_tx);
+            let handle = tokio::spawn(persist_checkpoints(pair_id, checkpoint, pending_rx, ack_rx));
+            (Some(pending_tx), Some(handle))
+        }
+        None => (None, None),
+    };
+
+    let r = Arc::clone(&table.reader);
+    let reader_for_cancel = Arc::clone(&table.reader);
+    let builder = Arc::clone(&table.record_builder);
+    let dirty = table.dirty.clone();
+    let retry = table.retry.clone();
+    let reader_cancel = table.cancel_token.clone();
     let r_bar = ctx.progress.reader_bar.clone();
     let batch_size = ctx.batch_size;
+    let read_speed = ctx.read_speed.clone();
     let r_handle = tokio::spawn(async move {
         tokio::select! {
-            result = csas(pair_id, r, &read_task, batch_size, &builder, &tx, &r_bar) => {
+            result = csas(pair_id, r, &read_task, batch_size, &builder, dirty.as_deref(), &retry, &tx, &r_bar, pending_tx.as_ref(), read_speed.as_ref()) => {
                 match result {
                     Ok(count) => {
                         let _ = tx.send(PipelineMessage::ReaderFinished).await;
@@ -303,8 +593,8 @@ async fn run_task_pair(pair: PairWork, ctx: PipelineRunContext) -> PairResult {
         }
     });
 
-    let w = Arc::clone(&ctx.writer);
-    let writer_cancel = ctx.cancel_token.clone();
+    let w = Arc::clone(&table.writer);
+    let writer_cancel = table.cancel_token.clone();
     let w_handle = tokio::spawn(async move {
         tokio::select! {
             result = w.write_data(write_task, rx2) => {
@@ -329,6 +619,9 @@ async fn run_task_pair(pair: PairWork, ctx: PipelineRunContext) -> PairResult {
     let writer_result = w_handle
         .await
         .unwrap_or_else(|e| Err(anyhow::anyhow!("Writer-{} 任务崩溃: {}", pair_id, e)));
+    if let Some(handle) = checkpoint_handle {
+        let _ = handle.await;
+    }
 
     let (read_count, read_err) = match reader_result {
         Ok(n) => (n, None),
@@ -342,11 +635,16 @@ async fn run_task_pair(pair: PairWork, ctx: PipelineRunContext) -> PairResult {
     let error = match (read_err, write_err) {
         (Some(r), Some(w)) => Some(anyhow::anyhow!("R/W FULL FAIL: {}; {}", r, w)),
         (Some(e), None) | (None, Some(e)) => Some(e),
-        _ => None,
+        _ => table
+            .dirty
+            .as_ref()
+            .and_then(|d| d.check_percentage().err()),
     };
 
     PairResult {
         pair_id,
+        table,
+        task_ids: Vec::new(),
         read_count,
         write_count,
         error,
@@ -354,23 +652,349 @@ async fn run_task_pair(pair: PairWork, ctx: PipelineRunContext) -> PairResult {
     }
 }
 
+/// 一批待分发的行及其位点
+type DispatchItem = (Vec<MappingRow>, Option<Vec<u8>>);
+
+/// N:M 调度单元：表的 N 个 Reader → 分发器 → M 个 Writer
+///
+/// 分发器按 key 哈希路由，同一 key 的行由同一个 Writer 按读取顺序写入；
+/// 任一 Reader / Writer 失败都会取消整张表。位点在批次的所有分片都确认写入后按序保存。
+async fn run_dispatched(
+    pair_id: usize,
+    table: Arc<TableRuntime>,
+    read_tasks: Vec<ReadTask>,
+    write_tasks: Vec<WriteTask>,
+    key_columns: Vec<String>,
+    ctx: PipelineRunContext,
+) -> PairResult {
+    let was_cancelled = ctx.cancel_token.is_cancelled();
+    let writer_count = write_tasks.len();
+    info!(
+        "Pair-{} ({}) 启动分发: readers={}, writers={}, key_columns={:?}",
+        pair_id,
+        table.name,
+        read_tasks.len(),
+        writer_count,
+        key_columns
+    );
+
+    let tracker = table
+        .checkpoint
+        .as_ref()
+        .map(|_| Arc::new(Mutex::new(AckTracker::new(writer_count))));
+    // 推进出的位点经 channel 按序交给单独的任务落盘，不在持锁时提交 redb 事务
+    let (positions, saver) = match table.checkpoint.clone() {
+        Some(checkpoint) => {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let saver = tokio::spawn(save_positions(pair_id, checkpoint, rx));
+            (Some(tx), Some(saver))
+        }
+        None => (None, None),
+    };
+
+    let mut writer_txs = Vec::with_capacity(writer_count);
+    let mut writer_handles = Vec::with_capacity(writer_count);
+    let mut ack_handles = Vec::new();
+    for (index, mut write_task) in write_tasks.into_iter().enumerate() {
+        let (tx, rx) = mpsc::channel(ctx.buffer_size);
+        writer_txs.push(tx);
+        let (ack_tx, ack_rx) = mpsc::unbounded_channel();
+        write_task.batch_ack = Some(ack_tx);
+        ack_handles.push(tokio::spawn(handle_dispatched_acks(
+            index,
+            ack_rx,
+            ctx.progress.writer_bar.clone(),
+            tracker.clone(),
+            positions.clone(),
+        )));
+
+        let w = Arc::clone(&table.writer);
+        let writer_cancel = table.cancel_token.clone();
+        writer_handles.push(tokio::spawn(async move {
+            tokio::select! {
+                result = w.write_data(write_task, rx) => {
+                    if let Err(ref e) = result {
+                        error!("Pair-{} Writer-{} 失败: {}", pair_id, index, e);
+                        writer_cancel.cancel();
+                    }
+                    result
+                }
+                () = writer_cancel.cancelled() => {
+                    warn!("Pair-{} Writer-{} 正常关闭", pair_id, index);
+                    Ok(0)
+                }
+            }
+        }));
+    }
+
+    let (dispatch_tx, dispatch_rx) = mpsc::channel(ctx.buffer_size);
+    let dispatcher = tokio::spawn(dispatch_batches(
+        KeyRouter::new(key_columns, writer_count),
+        dispatch_rx,
+        writer_txs,
+        tracker,
+        positions,
+    ));
+
+    // Reader 共享全局并发上限，同时运行的 Reader 不超过 channel_number
+    let permits = Arc::new(Semaphore::new(ctx.channel_number.max(1)));
+    let mut reader_handles = Vec::with_capacity(read_tasks.len());
+    for (index, read_task) in read_tasks.into_iter().enumerate() {
+        let table = Arc::clone(&table);
+        let dispatch_tx = dispatch_tx.clone();
+        let permits = Arc::clone(&permits);
+        let r_bar = ctx.progress.reader_bar.clone();
+        let batch_size = ctx.batch_size;
+        let read_speed = ctx.read_speed.clone();
+        reader_handles.push(tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            tokio::select! {
+                result = read_to_dispatcher(index, &table, &read_task, batch_size, &dispatch_tx, &r_bar, read_speed.as_ref()) => {
+                    if let Err(ref e) = result {
+                        error!("Pair-{} Reader-{} 失败: {}", pair_id, index, e);
+                        table.cancel_token.cancel();
+                    }
+                    (result, false)
+                }
+                () = table.cancel_token.cancelled() => {
+                    warn!("Pair-{} Reader-{} being eliminated.【outside】", pair_id, index);
+                    table.reader.shutdown();
+                    (Err(anyhow::anyhow!("Reader-{} process terminates unexpectedly.", index)), true)
+                }
+            }
+        }));
+    }
+    drop(dispatch_tx);
+
+    let mut read_count = 0;
+    let mut read_err = None;
+    let mut interrupted = None;
+    for (index, handle) in reader_handles.into_iter().enumerate() {
+        match handle.await {
+            Ok((Ok(n), _)) => read_count += n,
+            Ok((Err(e), true)) => {
+                interrupted.get_or_insert(e);
+            }
+            Ok((Err(e), false)) => {
+                read_err.get_or_insert(e);
+            }
+            Err(e) => {
+                read_err.get_or_insert(anyhow::anyhow!("Reader-{} 任务崩溃: {}", index, e));
+            }
+        }
+    }
+    let read_err = read_err.or(interrupted);
+
+    let _ = dispatcher.await;
+
+    let mut write_count = 0;
+    let mut write_err = None;
+    for (index, handle) in writer_handles.into_iter().enumerate() {
+        match handle.await {
+            Ok(Ok(n)) => write_count += n,
+            Ok(Err(e)) => {
+                write_err.get_or_insert(e);
+            }
+            Err(e) => {
+                write_err.get_or_insert(anyhow::anyhow!("Writer-{} 任务崩溃: {}", index, e));
+            }
+        }
+    }
+    for handle in ack_handles {
+        let _ = handle.await;
+    }
+    if let Some(saver) = saver {
+        let _ = saver.await;
+    }
+
+    let error = match (read_err, write_err) {
+        (Some(r), Some(w)) => Some(anyhow::anyhow!("R/W FULL FAIL: {}; {}", r, w)),
+        (Some(e), None) | (None, Some(e)) => Some(e),
+        _ => table
+            .dirty
+            .as_ref()
+            .and_then(|d| d.check_percentage().err()),
+    };
+
+    PairResult {
+        pair_id,
+        table,
+        task_ids: Vec::new(),
+        read_count,
+        write_count,
+        error,
+        shutdown: was_cancelled,
+    }
+}
+
+/// 运行一个 Reader 分片，把每个批次连同其位点交给分发器
+async fn read_to_dispatcher(
+    reader_id: usize,
+    table: &TableRuntime,
+    task: &ReadTask,
+    batch_size: usize,
+    dispatch_tx: &mpsc::Sender<DispatchItem>,
+    reader_bar: &ProgressBar,
+    speed: Option<&SpeedLimiter>,
+) -> Result<usize> {
+    let (tx, mut rx) = mpsc::channel(1);
+    let (pending_tx, mut pending_rx) = mpsc::unbounded_channel();
+
+    let read = async move {
+        csas(
+            reader_id,
+            Arc::clone(&table.reader),
+            task,
+            batch_size,
+            &table.record_builder,
+            table.dirty.as_deref(),
+            &table.retry,
+            &tx,
+            reader_bar,
+            Some(&pending_tx),
+            speed,
+        )
+        .await
+    };
+    let forward = async {
+        while let Some(message) = rx.recv().await {
+            if let PipelineMessage::DataBatch(rows) = message {
+                let position = pending_rx.recv().await.flatten();
+                if dispatch_tx.send((rows, position)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    };
+
+    let (result, ()) = tokio::join!(read, forward);
+    result
+}
+
+/// 按 key 把批次拆分到各 Writer；Reader 全部结束后通知每个 Writer
+async fn dispatch_batches(
+    mut router: KeyRouter,
+    mut rx: mpsc::Receiver<DispatchItem>,
+    writers: Vec<mpsc::Sender<PipelineMessage>>,
+    tracker: Option<Arc<Mutex<AckTracker>>>,
+    positions: Option<mpsc::UnboundedSender<Vec<u8>>>,
+) {
+    'batches: while let Some((rows, position)) = rx.recv().await {
+        let parts = router.route(rows);
+        if let (Some(tracker), Some(positions)) = (&tracker, &positions) {
+            let targets: Vec<usize> = parts
+                .iter()
+                .enumerate()
+                .filter(|(_, part)| !part.is_empty())
+                .map(|(index, _)| index)
+                .collect();
+            // 持锁发送，保证与 Writer 确认路径推进的位点按序落盘
+            let mut tracker = tracker.lock();
+            if let Some(position) = tracker.dispatched(&targets, position) {
+                let _ = positions.send(position);
+            }
+        }
+        for (index, part) in parts.into_iter().enumerate() {
+            if part.is_empty() {
+                continue;
+            }
+            if writers[index]
+                .send(PipelineMessage::DataBatch(part))
+                .await
+                .is_err()
+            {
+                break 'batches;
+            }
+        }
+    }
+
+    for writer in &writers {
+        let _ = writer.send(PipelineMessage::ReaderFinished).await;
+    }
+}
+
+/// Writer 每确认（提交）一个分片，按写入条数推进进度条；
+/// 开启 checkpoint 时推进已全部写入的批次位点并交给 [`save_positions`]
+async fn handle_dispatched_acks(
+    writer: usize,
+    mut acks: mpsc::UnboundedReceiver<usize>,
+    writer_bar: ProgressBar,
+    tracker: Option<Arc<Mutex<AckTracker>>>,
+    positions: Option<mpsc::UnboundedSender<Vec<u8>>>,
+) {
+    while let Some(count) = acks.recv().await {
+        writer_bar.inc(count as u64);
+        if let (Some(tracker), Some(positions)) = (&tracker, &positions) {
+            let mut tracker = tracker.lock();
+            if let Some(position) = tracker.ack(writer) {
+                let _ = positions.send(position);
+            }
+        }
+    }
+}
+
+/// 按接收顺序保存位点；积压时只保存最新的一个
+async fn save_positions(
+    pair_id: usize,
+    checkpoint: CheckpointHandle,
+    mut positions: mpsc::UnboundedReceiver<Vec<u8>>,
+) {
+    while let Some(mut position) = positions.recv().await {
+        while let Ok(newer) = positions.try_recv() {
+            position = newer;
+        }
+        if let Err(e) = checkpoint.save_async(position).await {
+            warn!("Pair-{} checkpoint 保存失败: {}", pair_id, e);
+        }
+    }
+}
+
+/// 表的所有 Pair 成功完成后执行 source / target 的 post_sql
+async fn run_post_sync(table: &TableRuntime) -> Result<()> {
+    table.reader.post_sync().await?;
+    table.writer.post_sync().await
+}
+
+/// 清理失败或被取消的表在目标端留下的临时表（如 staging 表）
+///
+/// `all` 为 true 时（任务取消或崩溃）清理所有表；已完成替换的表清理是空操作。
+async fn abort_unfinished(writers: &[Arc<dyn DataWriter>], tallies: &[TableTally], all: bool) {
+    for (writer, tally) in writers.iter().zip(tallies) {
+        if !all && tally.error.is_none() {
+            continue;
+        }
+        if let Err(e) = writer.abort().await {
+            warn!("[{}] 清理临时表失败: {}", writer.description(), e);
+        }
+    }
+}
+
+/// 取出下一个所属表仍在运行的 Pair，已失败（或已取消）表的 Pair 直接丢弃
+fn next_pair(queue: &mut VecDeque<PairWork>) -> Option<PairWork> {
+    while let Some(pair) = queue.pop_front() {
+        if !pair.table.cancel_token.is_cancelled() {
+            return Some(pair);
+        }
+    }
+    None
+}
+
 async fn run_task_group(group: GroupWork, ctx: PipelineRunContext) -> GroupResult {
     let GroupWork {
         group_id,
         tasks,
         concurrency,
+        table_count,
     } = group;
     let mut queue: VecDeque<PairWork> = VecDeque::from(tasks);
     let mut running = FuturesUnordered::new();
 
-    let mut total_read = 0usize;
-    let mut total_written = 0usize;
-    let mut first_error: Option<anyhow::Error> = None;
+    let mut tables: Vec<TableTally> = (0..table_count).map(|_| TableTally::default()).collect();
     let mut group_shutdown = false;
 
     while running.len() < concurrency {
-        if let Some(pair) = queue.pop_front() {
-            running.push(run_task_pair(pair, ctx.clone()));
+        if let Some(pair) = next_pair(&mut queue) {
+            running.push(run_work(pair, ctx.clone()));
         } else {
             break;
         }
@@ -381,55 +1005,122 @@ async fn run_task_group(group: GroupWork, ctx: PipelineRunContext) -> GroupResul
             group_shutdown = true;
         }
 
+        let table = &pair_result.table;
+        let tally = &mut tables[table.index];
         if let Some(e) = pair_result.error {
             error!(
-                "TaskGroup-{} 的 Pair-{} 失败: {}",
-                group_id, pair_result.pair_id, e
+                "TaskGroup-{} 的 Pair-{} ({}) 失败: {}",
+                group_id, pair_result.pair_id, table.name, e
             );
-            if first_error.is_none() && !pair_result.shutdown {
-                first_error = Some(e);
+            if tally.error.is_none() && !pair_result.shutdown {
+                tally.error = Some(e);
             }
-            ctx.cancel_token.cancel();
+            table.cancel_token.cancel();
         } else {
-            total_read += pair_result.read_count;
-            total_written += pair_result.write_count;
+            tally.read += pair_result.read_count;
+            tally.written += pair_result.write_count;
             info!(
-                "TaskGroup-{} 的 Pair-{} 完成，读取 {} 条，写入 {} 条",
-                group_id, pair_result.pair_id, pair_result.read_count, pair_result.write_count
+                "TaskGroup-{} 的 Pair-{} ({}) 完成，读取 {} 条，写入 {} 条",
+                group_id,
+                pair_result.pair_id,
+                table.name,
+                pair_result.read_count,
+                pair_result.write_count
             );
-        }
 
-        if first_error.is_none() {
-            while running.len() < concurrency {
-                if let Some(pair) = queue.pop_front() {
-                    running.push(run_task_pair(pair, ctx.clone()));
-                } else {
-                    break;
+            // 表已取消时 Writer 可能提前退出，分片不算完成，续跑时重新执行
+            if let Some(splits) = table
+                .splits
+                .as_ref()
+                .filter(|_| !table.cancel_token.is_cancelled())
+            {
+                if let Err(e) = splits.complete(&pair_result.task_ids) {
+                    warn!("Pair-{} 分片完成记录保存失败: {}", pair_result.pair_id, e);
                 }
             }
+
+            let written = table
+                .written
+                .fetch_add(pair_result.write_count, Ordering::Relaxed)
+                + pair_result.write_count;
+            if table.pending_pairs.fetch_sub(1, Ordering::AcqRel) == 1 {
+                if !table.cancel_token.is_cancelled() {
+                    if let Err(e) = run_post_sync(table).await {
+                        error!("表 {} post_sql 失败: {}", table.name, e);
+                        tally.error = Some(e);
+                    }
+                }
+                let done = ctx.tables_done.fetch_add(1, Ordering::AcqRel) + 1;
+                info!(
+                    "表 {} 同步完成，写入 {} 条（{}/{} 张表）",
+                    table.name, written, done, ctx.table_count
+                );
+            }
+        }
+
+        while running.len() < concurrency {
+            if let Some(pair) = next_pair(&mut queue) {
+                running.push(run_work(pair, ctx.clone()));
+            } else {
+                break;
+            }
         }
     }
 
     GroupResult {
         group_id,
-        total_read,
-        total_written,
-        error: first_error,
-        shutdown: group_shutdown,
+        tables,
+        shutdown: group_shutdown || ctx.cancel_token.is_cancelled(),
     }
 }
 
-/// 1:1 Pair
-async fn run_paired_pipeline(
+/// 对一张表完成 reader/writer split，生成该表的 Pair
+async fn plan_table(
     config: &PipelineConfig,
-    reader: Arc<dyn DataReader>,
-    writer: Arc<dyn DataWriter>,
-    record_builder: Arc<RecordBuilder>,
-    cancel_token: CancellationToken,
-) -> Result<PipelineStats> {
-    let start_time = Instant::now();
+    index: usize,
+    table: TablePipeline,
+    retry: RetryPolicy,
+    write_speed: Option<&SpeedLimiter>,
+    cancel_token: &CancellationToken,
+    pairs: &mut Vec<PairWork>,
+) -> Result<usize> {
+    let TablePipeline {
+        name,
+        reader,
+        writer,
+        job_config,
+        checkpoint,
+        splits,
+    } = table;
 
-    let reader_split = reader.split(config.reader_threads).await?;
+    let mut reader_split = reader.split(config.reader_threads).await?;
+    // 续跑时沿用上次记录的分片（pk 范围不随源表变化），只执行未完成的部分
+    if let Some(log) = &splits {
+        match log.pending()? {
+            Some(pending) if pending.is_empty() => {
+                info!(
+                    "[{}] run '{}' 的分片均已完成，跳过",
+                    reader.description(),
+                    log.run_id()
+                );
+                return Ok(0);
+            }
+            Some(pending) => {
+                info!(
+                    "[{}] 续跑 run '{}'，剩余 {} 个未完成分片",
+                    reader.description(),
+                    log.run_id(),
+                    pending.len()
+                );
+                reader_split.tasks = pending;
+            }
+            None => {
+                if let Err(e) = log.plan(&reader_split.tasks) {
+                    warn!("[{}] 分片记录保存失败: {}", reader.description(), e);
+                }
+            }
+        }
+    }
     let task_count = reader_split.tasks.len();
     info!(
         "[{}] 总记录数 {}, 切分为 {} 个任务",
@@ -438,60 +1129,192 @@ async fn run_paired_pipeline(
         task_count
     );
     if task_count == 0 {
-        info!("[{}] 无读取任务，Pipeline 关闭", reader.description());
-        return Ok(PipelineStats::default());
+        info!("[{}] 无读取任务", reader.description());
+        return Ok(0);
     }
 
-    let progress_ctx = create_progress_bars(reader_split.total_records)?;
-    let writer_split = writer.split(task_count).await?;
+    let writer_count = config.writer_threads.unwrap_or(task_count);
+    let mut writer_split = writer.split(writer_count).await?;
     info!(
-        "[{}] 切分为 {} 个任务（R1:W1）",
+        "[{}] 切分为 {} 个任务（{}）",
         writer.description(),
-        writer_split.tasks.len()
+        writer_split.tasks.len(),
+        if config.writer_threads.is_some() {
+            "N:M，按 key 分发"
+        } else {
+            "R1:W1"
+        }
     );
 
-    if writer_split.tasks.len() < task_count {
-        return Err(anyhow::anyhow!(
-            "Writer 只产出 {} 个任务，但有 {} 个 Reader 任务.",
-            writer_split.tasks.len(),
+    if writer_split.tasks.len() < writer_count {
+        return Err(match config.writer_threads {
+            Some(threads) => anyhow::anyhow!(
+                "Writer 只产出 {} 个任务，但配置了 writer_threads={}.",
+                writer_split.tasks.len(),
+                threads
+            ),
+            None => anyhow::anyhow!(
+                "Writer 只产出 {} 个任务，但有 {} 个 Reader 任务.",
+                writer_split.tasks.len(),
+                task_count
+            ),
+        });
+    }
+    writer_split.tasks.truncate(writer_count);
+
+    // 目标连接显式配置的 use_transaction 优先，否则沿用 pipeline.use_transaction
+    let target_db = job_config.target.parse_database_config().ok();
+    let use_transaction = target_db
+        .as_ref()
+        .and_then(|db| db.use_transaction)
+        .unwrap_or(config.use_transaction);
+    let dirty = match &job_config.error_limit {
+        Some(limits) => Some(DirtyRecordCollector::open(limits, &job_config, &name).await?),
+        None => None,
+    };
+    for write_task in &mut writer_split.tasks {
+        write_task.use_transaction = use_transaction;
+        write_task.dirty = dirty.clone();
+        write_task.retry = retry.clone();
+        write_task.speed = write_speed.cloned();
+    }
+
+    let record_builder = build_record_builder(&job_config)?
+        .with_source_column_types(reader_split.column_types.clone());
+    let runtime = Arc::new(TableRuntime {
+        index,
+        name,
+        reader,
+        writer,
+        record_builder: Arc::new(record_builder),
+        checkpoint,
+        splits,
+        dirty,
+        retry,
+        cancel_token: cancel_token.child_token(),
+        pending_pairs: AtomicUsize::new(if config.writer_threads.is_some() {
+            1
+        } else {
             task_count
-        ));
+        }),
+        written: AtomicUsize::new(0),
+    });
+
+    if config.writer_threads.is_some() {
+        pairs.push(PairWork {
+            pair_id: pairs.len(),
+            table: runtime,
+            tasks: PairTasks::Dispatched {
+                read_tasks: reader_split.tasks,
+                write_tasks: writer_split.tasks,
+                key_columns: target_db.and_then(|db| db.key_columns).unwrap_or_default(),
+            },
+        });
+        return Ok(reader_split.total_records);
     }
 
+    for (read_task, write_task) in reader_split.tasks.into_iter().zip(writer_split.tasks) {
+        pairs.push(PairWork {
+            pair_id: pairs.len(),
+            table: Arc::clone(&runtime),
+            tasks: PairTasks::Paired {
+                read_task,
+                write_task,
+            },
+        });
+    }
+
+    Ok(reader_split.total_records)
+}
+
+/// 1:1 Pair，配置 writer_threads 时每张表为一个 N:M 调度单元
+async fn run_paired_pipeline(
+    config: &PipelineConfig,
+    tables: Vec<TablePipeline>,
+    cancel_token: CancellationToken,
+) -> Result<PipelineStats> {
+    let start_time = Instant::now();
+
+    let table_count = tables.len();
+    let names: Vec<String> = tables.iter().map(|t| t.name.clone()).collect();
+    let writers: Vec<Arc<dyn DataWriter>> = tables.iter().map(|t| Arc::clone(&t.writer)).collect();
+    // 任务配置的 retry 优先，否则使用系统配置，都未配置时不重试
+    let retries: Vec<RetryPolicy> = tables
+        .iter()
+        .map(
+            |t| match t.job_config.retry.as_ref().or(config.retry.as_ref()) {
+                Some(retry) => RetryPolicy::new(retry.clone()),
+                None => RetryPolicy::disabled(),
+            },
+        )
+        .collect();
+    // 读取与写入两侧各一个全局限速器，所有表、所有 Pair 共享
+    let read_speed = SpeedLimiter::new(&config.speed);
+    let write_speed = SpeedLimiter::new(&config.speed);
+    let mut tallies: Vec<TableTally> = (0..table_count).map(|_| TableTally::default()).collect();
+    let mut pairs = Vec::new();
+    let mut total_records = 0usize;
+
+    for (index, table) in tables.into_iter().enumerate() {
+        match plan_table(
+            config,
+            index,
+            table,
+            retries[index].clone(),
+            write_speed.as_ref(),
+            &cancel_token,
+            &mut pairs,
+        )
+        .await
+        {
+            Ok(records) => total_records += records,
+            Err(e) => {
+                error!("表 {} 准备失败: {}", names[index], e);
+                tallies[index].error = Some(e);
+            }
+        }
+    }
+
+    let task_count = pairs.len();
+    if task_count == 0 {
+        info!("无读取任务，Pipeline 关闭");
+        abort_unfinished(&writers, &tallies, false).await;
+        return finish_stats(names, tallies, &retries, None, start_time, false);
+    }
+
+    let progress_ctx = create_progress_bars(total_records)?;
+
     let need_channel = config.channel_number.max(1).min(task_count);
     let per_group_channel = config.per_group_channel.max(1);
     let group_count = need_channel.div_ceil(per_group_channel);
 
     let mut grouped_tasks: Vec<Vec<PairWork>> = (0..group_count).map(|_| Vec::new()).collect();
-    for i in 0..task_count {
-        let group_id = i % group_count;
-        grouped_tasks[group_id].push(PairWork {
-            pair_id: i,
-            read_task: reader_split.tasks[i].clone(),
-            write_task: writer_split.tasks[i].clone(),
-        });
+    for pair in pairs {
+        let group_id = pair.pair_id % group_count;
+        grouped_tasks[group_id].push(pair);
     }
 
     let base_group_concurrency = need_channel / group_count;
     let extra_group_concurrency = need_channel % group_count;
 
     info!(
-        "Pipeline 准备配置: task_count={}, need_channel={}, group_count={}, per_group_channel={}",
-        task_count, need_channel, group_count, per_group_channel
+        "Pipeline 准备配置: table_count={}, task_count={}, need_channel={}, group_count={}, per_group_channel={}",
+        table_count, task_count, need_channel, group_count, per_group_channel
     );
 
     let mut group_handles = FuturesUnordered::new();
     let run_ctx = PipelineRunContext {
-        reader: Arc::clone(&reader),
-        writer: Arc::clone(&writer),
         buffer_size: config.buffer_size,
         batch_size: config.batch_size,
-        record_builder: Arc::clone(&record_builder),
         cancel_token: cancel_token.clone(),
         progress: PipelineProgress {
             reader_bar: progress_ctx.reader_bar.clone(),
             writer_bar: progress_ctx.writer_bar.clone(),
         },
+        channel_number: config.channel_number,
+        read_speed: read_speed.clone(),
+        tables_done: Arc::new(AtomicUsize::new(0)),
+        table_count,
     };
 
     for (group_id, tasks) in grouped_tasks.into_iter().enumerate() {
@@ -514,6 +1337,7 @@ async fn run_paired_pipeline(
                 group_id,
                 tasks,
                 concurrency: group_concurrency,
+                table_count,
             },
             run_ctx.clone(),
         );
@@ -521,54 +1345,123 @@ async fn run_paired_pipeline(
         group_handles.push(tokio::spawn(group_future));
     }
 
-    let mut total_read = 0usize;
-    let mut total_written = 0usize;
-    let mut first_error: Option<anyhow::Error> = None;
+    let speed_display = (read_speed.is_some() || write_speed.is_some()).then(|| {
+        tokio::spawn(show_speed(
+            read_speed.clone(),
+            write_speed.clone(),
+            run_ctx.progress.clone(),
+        ))
+    });
+
+    let mut crashed: Option<anyhow::Error> = None;
     let mut pipeline_shutdown = false;
 
     while let Some(group_result) = group_handles.next().await {
         match group_result {
             Ok(result) => {
-                total_read += result.total_read;
-                total_written += result.total_written;
                 if result.shutdown {
                     pipeline_shutdown = true;
                 }
+                let (read, written) = result
+                    .tables
+                    .iter()
+                    .fold((0, 0), |(r, w), t| (r + t.read, w + t.written));
                 info!(
                     "TaskGroup-{} 结束: read={}, write={}",
-                    result.group_id, result.total_read, result.total_written
+                    result.group_id, read, written
                 );
-                if let Some(e) = result.error {
-                    if first_error.is_none() && !result.shutdown {
-                        first_error = Some(e);
-                    }
+                for (total, tally) in tallies.iter_mut().zip(result.tables) {
+                    total.merge(tally);
                 }
             }
             Err(e) => {
                 error!("TaskGroup 任务崩溃: {}", e);
-                if first_error.is_none() {
-                    first_error = Some(anyhow::anyhow!("TaskGroup 任务崩溃: {}", e));
+                if crashed.is_none() {
+                    crashed = Some(anyhow::anyhow!("TaskGroup 任务崩溃: {}", e));
                 }
             }
         }
     }
 
+    if let Some(handle) = speed_display {
+        handle.abort();
+    }
+    progress_ctx.finish()?;
+    abort_unfinished(&writers, &tallies, pipeline_shutdown || crashed.is_some()).await;
+
+    if let Some(e) = crashed {
+        return Err(e);
+    }
+
+    finish_stats(
+        names,
+        tallies,
+        &retries,
+        write_speed.map(|s| s.stats().with_read(read_speed.as_ref())),
+        start_time,
+        pipeline_shutdown,
+    )
+}
+
+/// 每秒在进度条上刷新限速器的当前速率
+async fn show_speed(
+    read_speed: Option<SpeedLimiter>,
+    write_speed: Option<SpeedLimiter>,
+    progress: PipelineProgress,
+) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        if let Some(speed) = &read_speed {
+            progress.reader_bar.set_message(speed.rate_message());
+        }
+        if let Some(speed) = &write_speed {
+            progress.writer_bar.set_message(speed.rate_message());
+        }
+    }
+}
+
+/// 汇总各表结果；所有表都失败时返回第一个错误
+fn finish_stats(
+    names: Vec<String>,
+    mut tallies: Vec<TableTally>,
+    retries: &[RetryPolicy],
+    speed: Option<SpeedStats>,
+    start_time: Instant,
+    shutdown: bool,
+) -> Result<PipelineStats> {
+    if tallies.iter().all(|t| t.error.is_some()) {
+        if let Some(e) = tallies.first_mut().and_then(|t| t.error.take()) {
+            return Err(e);
+        }
+    }
+
+    let tables: Vec<TableStats> = names
+        .into_iter()
+        .zip(tallies)
+        .zip(retries)
+        .map(|((table, tally), retry)| TableStats {
+            table,
+            records_read: tally.read,
+            records_written: tally.written,
+            retries: retry.retries(),
+            error: tally.error.map(|e| e.to_string()),
+        })
+        .collect();
+
     let elapsed = start_time.elapsed();
     let mut stats = PipelineStats {
-        records_read: total_read,
-        records_written: total_written,
+        records_read: tables.iter().map(|t| t.records_read).sum(),
+        records_written: tables.iter().map(|t| t.records_written).sum(),
+        retries: tables.iter().map(|t| t.retries).sum(),
         elapsed_secs: elapsed.as_secs_f64(),
-        shutdown: pipeline_shutdown,
+        shutdown,
+        tables,
+        speed,
         ..Default::default()
     };
     stats.records_failed = stats.records_failed();
     stats.calculate_throughput();
 
-    progress_ctx.finish()?;
-
-    if let Some(e) = first_error {
-        return Err(e);
-    }
-
     Ok(stats)
 }
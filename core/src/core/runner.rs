@@ -12,8 +12,9 @@ use relus_common::constant::pipeline::{
     DEFAULT_BATCH_SIZE, DEFAULT_BUFFER_SIZE, DEFAULT_CHANNEL_NUMBER, DEFAULT_PER_GROUP_CHANNEL,
     DEFAULT_READER_THREADS,
 };
-use relus_common::job_config::JobConfig;
-use relus_reader::{DataReader, StreamMode};
+use relus_common::job_config::{JobConfig, RetryConfig, SpeedConfig, SyncMode};
+use relus_common::pipeline::SpeedStats;
+use relus_reader::{binlog_source_for_snapshot, capture_binlog_position, DataReader, StreamMode};
 use relus_writer::DataWriter;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -21,7 +22,11 @@ use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
-use super::pipeline_executor::{start_run, PipelineConfig, PipelineStats};
+use super::pipeline_executor::{
+    start_run, PipelineConfig, PipelineStats, TablePipeline, TableStats,
+};
+use super::scheduler::checkpoint::{CheckpointHandle, SplitLog};
+use super::schema_evolution::{SchemaChangeLog, SchemaEvolution};
 use relus_reader::ReaderRegistry;
 use relus_writer::WriterRegistry;
 
@@ -38,6 +43,12 @@ pub struct RunnerConfig {
     pub per_group_channel: usize,
     pub batch_size: usize,
     pub use_transaction: bool,
+    /// 系统配置中的重试策略
+    pub retry: Option<RetryConfig>,
+    /// Writer 并发数，None 时与 Reader 分片 1:1
+    pub writer_threads: Option<usize>,
+    /// 全局限速
+    pub speed: SpeedConfig,
 }
 
 impl Default for RunnerConfig {
@@ -49,6 +60,9 @@ impl Default for RunnerConfig {
             per_group_channel: DEFAULT_PER_GROUP_CHANNEL,
             batch_size: DEFAULT_BATCH_SIZE,
             use_transaction: true,
+            retry: None,
+            writer_threads: None,
+            speed: SpeedConfig::default(),
         }
     }
 }
@@ -63,6 +77,9 @@ impl RunnerConfig {
             per_group_channel: pipeline_config.per_group_channel,
             batch_size: pipeline_config.batch_size,
             use_transaction: pipeline_config.use_transaction,
+            retry: pipeline_config.retry,
+            writer_threads: pipeline_config.writer_threads,
+            speed: pipeline_config.speed,
         }
     }
 
@@ -74,6 +91,9 @@ impl RunnerConfig {
             per_group_channel: self.per_group_channel,
             batch_size: self.batch_size,
             use_transaction: self.use_transaction,
+            retry: self.retry.clone(),
+            writer_threads: self.writer_threads,
+            speed: self.speed.clone(),
         }
     }
 }
@@ -89,6 +109,12 @@ pub struct RunResult {
     pub status: RunStatus,
     pub duration: Duration,
     pub error: Option<String>,
+    /// 各表的执行结果（单表任务只有一项）
+    #[serde(default)]
+    pub tables: Vec<TableStats>,
+    /// 运行期间检测到的源表结构变更
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schema_changes: Vec<String>,
 }
 
 /// 执行状态
@@ -108,6 +134,12 @@ pub struct RunnerStats {
     pub records_failed: usize,
     pub elapsed_secs: f64,
     pub throughput: f64,
+    /// 瞬时错误的重试次数
+    #[serde(default)]
+    pub retries: usize,
+    /// 配置全局限速时写入侧的限速与实际速率
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed: Option<SpeedStats>,
 }
 
 impl RunnerStats {
@@ -124,10 +156,21 @@ impl RunnerStats {
             records_failed: stats.records_failed,
             elapsed_secs: stats.elapsed_secs,
             throughput: stats.throughput,
+            retries: stats.retries,
+            speed: stats.speed,
         }
     }
 }
 
+/// 汇总失败的表，没有失败时为 None
+fn failed_tables_message(tables: &[TableStats]) -> Option<String> {
+    let failed: Vec<String> = tables
+        .iter()
+        .filter_map(|t| t.error.as_ref().map(|e| format!("{}: {}", t.table, e)))
+        .collect();
+    (!failed.is_empty()).then(|| format!("{} 张表同步失败 [{}]", failed.len(), failed.join("; ")))
+}
+
 // ==========================================
 // TaskRunner trait
 // ==========================================
@@ -137,9 +180,7 @@ impl RunnerStats {
 pub trait TaskRunner: Send + Sync {
     async fn run(
         &self,
-        reader: Arc<dyn DataReader>,
-        writer: Arc<dyn DataWriter>,
-        job_config: Arc<JobConfig>,
+        tables: Vec<TablePipeline>,
         cancel_token: CancellationToken,
     ) -> Result<RunResult>;
 }
@@ -163,23 +204,22 @@ impl BatchRunner {
 impl TaskRunner for BatchRunner {
     async fn run(
         &self,
-        reader: Arc<dyn DataReader>,
-        writer: Arc<dyn DataWriter>,
-        job_config: Arc<JobConfig>,
+        tables: Vec<TablePipeline>,
         cancel_token: CancellationToken,
     ) -> Result<RunResult> {
         let start_time = Instant::now();
         let pipeline_config = self.config.to_pipeline_config();
 
-        let pipeline_stats =
-            start_run(pipeline_config, reader, writer, job_config, cancel_token).await?;
+        let mut pipeline_stats = start_run(pipeline_config, tables, cancel_token).await?;
+        let tables = std::mem::take(&mut pipeline_stats.tables);
 
         let elapsed = start_time.elapsed();
         let mut stats = RunnerStats::from_pipeline(pipeline_stats);
         stats.elapsed_secs = elapsed.as_secs_f64();
         stats.calculate_throughput();
 
-        let status = if stats.records_failed > 0 {
+        let error = failed_tables_message(&tables);
+        let status = if stats.records_failed > 0 || error.is_some() {
             RunStatus::Partial
         } else {
             RunStatus::Success
@@ -189,7 +229,9 @@ impl TaskRunner for BatchRunner {
             stats,
             status,
             duration: elapsed,
-            error: None,
+            error,
+            tables,
+            schema_changes: Vec::new(),
         })
     }
 }
@@ -214,17 +256,14 @@ impl StreamRunner {
 impl TaskRunner for StreamRunner {
     async fn run(
         &self,
-        reader: Arc<dyn DataReader>,
-        writer: Arc<dyn DataWriter>,
-        job_config: Arc<JobConfig>,
+        tables: Vec<TablePipeline>,
         cancel_token: CancellationToken,
     ) -> Result<RunResult> {
         let start_time = Instant::now();
         let pipeline_config = self.config.to_pipeline_config();
 
         let pipeline_token = cancel_token.clone();
-        let pipeline_future =
-            start_run(pipeline_config, reader, writer, job_config, pipeline_token);
+        let pipeline_future = start_run(pipeline_config, tables, pipeline_token);
         let cancelled = cancel_token.cancelled();
 
         tokio::pin!(pipeline_future);
@@ -232,7 +271,8 @@ impl TaskRunner for StreamRunner {
         tokio::select! {
             _ = cancelled => {
                 info!("[StreamRunner] 收到停止信号，开始关闭");
-                let result = (&mut pipeline_future).await?;
+                let mut result = (&mut pipeline_future).await?;
+                let tables = std::mem::take(&mut result.tables);
 
                 let elapsed = start_time.elapsed();
                 let mut stats = RunnerStats::from_pipeline(result);
@@ -243,10 +283,13 @@ impl TaskRunner for StreamRunner {
                     status: RunStatus::Shutdown,
                     duration: elapsed,
                     error: None,
+                    tables,
+                    schema_changes: Vec::new(),
                 })
             }
             result = &mut pipeline_future => {
-                let pipeline_result = result?;
+                let mut pipeline_result = result?;
+                let tables = std::mem::take(&mut pipeline_result.tables);
 
                 let elapsed = start_time.elapsed();
                 let mut stats = RunnerStats::from_pipeline(pipeline_result);
@@ -265,6 +308,8 @@ impl TaskRunner for StreamRunner {
                     status,
                     duration: elapsed,
                     error: Some("Stream pipeline 非预期退出".to_string()),
+                    tables,
+                    schema_changes: Vec::new(),
                 })
             }
         }
@@ -290,32 +335,290 @@ fn dispatch_runner(stream_mode: StreamMode, config: RunnerConfig) -> Box<dyn Tas
 pub async fn start_task(
     config: Arc<JobConfig>,
     cancel_token: CancellationToken,
+) -> Result<RunResult> {
+    start_task_with_checkpoint(config, cancel_token, None, None, SchemaChangeLog::default()).await
+}
+
+/// 同 `start_task`，并使用 checkpoint 恢复/持久化 reader 的消费位点
+///
+/// 存在已保存的位点时，先交给 reader 覆盖起始位置；运行中每个批次
+/// 被 writer 确认后再保存该批次的位点。检测到的源表结构变更实时写入 `schema_log`。
+///
+/// `splits` 记录批量任务各表的分片完成情况；续跑（`SplitLog::resume`）时
+/// 只执行上次未完成的分片，CDC / Mix 任务不记录分片。
+pub async fn start_task_with_checkpoint(
+    config: Arc<JobConfig>,
+    cancel_token: CancellationToken,
+    checkpoint: Option<CheckpointHandle>,
+    splits: Option<SplitLog>,
+    schema_log: SchemaChangeLog,
 ) -> Result<RunResult> {
     super::registry::ensure_initialized();
 
+    let config = if config.migrate.is_some() {
+        Arc::new(super::migrate::expand_migration(&config).await?)
+    } else {
+        config
+    };
+
+    if config.is_multi_table() && config.is_cdc() {
+        anyhow::bail!("多表任务暂不支持 CDC 同步（binlog 增量 / Mix）");
+    }
+
+    let config = match &splits {
+        Some(log) if log.is_resume() => {
+            if config.is_cdc() {
+                anyhow::bail!("CDC / Mix 任务不支持按分片续跑");
+            }
+            Arc::new(resume_config(&config)?)
+        }
+        _ => config,
+    };
+
+    if config.sync_mode == Some(SyncMode::Mix) {
+        return run_mix(config, cancel_token, checkpoint, schema_log).await;
+    }
+
+    let splits = splits.filter(|_| !config.is_cdc());
+    let resume = checkpoint.as_ref().and_then(|handle| handle.load());
+    run_pipeline(config, cancel_token, checkpoint, resume, splits, schema_log).await
+}
+
+/// 续跑时使用的任务配置
+///
+/// 已完成的分片数据仍在目标表中：pre_sql（如 TRUNCATE）不再执行；
+/// 影子表加载只在全部分片写完后整体替换目标表，无法按分片续跑。
+fn resume_config(config: &JobConfig) -> Result<JobConfig> {
+    let staging = config
+        .target
+        .parse_database_config()
+        .ok()
+        .and_then(|db| db.use_staging_table)
+        .unwrap_or(false);
+    if staging {
+        anyhow::bail!("use_staging_table 任务不支持按分片续跑，请重新完整运行");
+    }
+    let mut resume_config = config.clone();
+    resume_config.source.pre_sql.clear();
+    resume_config.target.pre_sql.clear();
+    Ok(resume_config)
+}
+
+/// Mix 模式：先全量快照，再从快照前的 binlog 位点切换到 CDC
+///
+/// 快照开始前记录 binlog 位点，快照期间产生的变更会在 CDC 阶段重放，
+/// 因此目标端建议使用 upsert。已有 checkpoint 说明快照已完成，直接进入 CDC。
+async fn run_mix(
+    config: Arc<JobConfig>,
+    cancel_token: CancellationToken,
+    checkpoint: Option<CheckpointHandle>,
+    schema_log: SchemaChangeLog,
+) -> Result<RunResult> {
+    let cdc_config = Arc::new(mix_cdc_config(&config));
+
+    if let Some(saved) = checkpoint.as_ref().and_then(|handle| handle.load()) {
+        info!("[start_task] Mix 任务已有 checkpoint，跳过快照直接进入 CDC");
+        return run_pipeline(
+            cdc_config,
+            cancel_token,
+            checkpoint,
+            Some(saved),
+            None,
+            schema_log,
+        )
+        .await;
+    }
+
+    let position = capture_binlog_position(&config.source).await?;
+    info!("[start_task] Mix 快照开始，binlog 位点: {}", position);
+
+    let snapshot = run_pipeline(
+        Arc::clone(&config),
+        cancel_token.clone(),
+        None,
+        None,
+        None,
+        Arc::clone(&schema_log),
+    )
+    .await?;
+    if snapshot.status != RunStatus::Success || cancel_token.is_cancelled() {
+        warn!(
+            "[start_task] Mix 快照未成功完成 ({:?})，不进入 CDC 阶段",
+            snapshot.status
+        );
+        return Ok(snapshot);
+    }
+
+    let resume = position.to_bytes()?;
+    if let Some(handle) = &checkpoint {
+        handle.save(&resume)?;
+    }
+    info!(
+        "[start_task] Mix 快照完成 ({} 条)，从 {} 切换到 CDC",
+        snapshot.stats.records_written, position
+    );
+
+    let cdc = run_pipeline(
+        cdc_config,
+        cancel_token,
+        checkpoint,
+        Some(resume),
+        None,
+        schema_log,
+    )
+    .await?;
+    Ok(merge_results(snapshot, cdc))
+}
+
+/// 由 Mix 任务派生 CDC 阶段的任务配置
+fn mix_cdc_config(config: &JobConfig) -> JobConfig {
+    let mut cdc_config = config.clone();
+    cdc_config.source = binlog_source_for_snapshot(&config.source);
+    cdc_config.sync_mode = Some(SyncMode::Incremental);
+    // pre_sql 已在快照阶段执行过，CDC 阶段再执行会清掉快照数据（如 TRUNCATE）
+    cdc_config.source.pre_sql.clear();
+    cdc_config.target.pre_sql.clear();
+    // 快照阶段已替换目标表，CDC 阶段直接写入目标表
+    cdc_config
+        .target
+        .set_connection_field("use_staging_table", serde_json::Value::Bool(false));
+    cdc_config
+}
+
+/// 合并快照与 CDC 两个阶段的执行结果，状态以 CDC 阶段为准
+fn merge_results(snapshot: RunResult, cdc: RunResult) -> RunResult {
+    let duration = snapshot.duration + cdc.duration;
+    let mut stats = RunnerStats {
+        records_read: snapshot.stats.records_read + cdc.stats.records_read,
+        records_written: snapshot.stats.records_written + cdc.stats.records_written,
+        records_failed: snapshot.stats.records_failed + cdc.stats.records_failed,
+        elapsed_secs: duration.as_secs_f64(),
+        throughput: 0.0,
+        retries: snapshot.stats.retries + cdc.stats.retries,
+        speed: cdc.stats.speed.or(snapshot.stats.speed),
+    };
+    stats.calculate_throughput();
+
+    RunResult {
+        stats,
+        status: cdc.status,
+        duration,
+        error: cdc.error,
+        tables: snapshot.tables.into_iter().chain(cdc.tables).collect(),
+        schema_changes: cdc.schema_changes,
+    }
+}
+
+/// 创建 Reader/Writer，按 resume 覆盖起始位点后执行单个 pipeline
+///
+/// 多表任务为每张表各建一组 Reader/Writer，checkpoint 按 `job/表名` 分别保存，
+/// 此时 resume 不使用，各表从自己的 checkpoint 恢复。分片记录同样按表名区分。
+///
+/// 开启结构演进时先检测一次源表结构，Streaming 任务运行中再定期检测。
+async fn run_pipeline(
+    config: Arc<JobConfig>,
+    cancel_token: CancellationToken,
+    checkpoint: Option<CheckpointHandle>,
+    resume: Option<Vec<u8>>,
+    splits: Option<SplitLog>,
+    schema_log: SchemaChangeLog,
+) -> Result<RunResult> {
     let reader_registry = ReaderRegistry::instance();
     let writer_registry = WriterRegistry::instance();
+    let multi_table = config.is_multi_table();
+
+    let mut tables = Vec::new();
+    for (name, table_config) in config.table_jobs()? {
+        let table_config = Arc::new(table_config);
+        let reader: Arc<dyn DataReader> = Arc::from(
+            reader_registry
+                .prepare_reader(&table_config.source.source_type, Arc::clone(&table_config))?,
+        );
+        let writer: Arc<dyn DataWriter> = Arc::from(
+            writer_registry
+                .prepare_writer(&table_config.target.source_type, Arc::clone(&table_config))?,
+        );
+
+        let (table_checkpoint, saved) = if multi_table {
+            let handle = checkpoint.as_ref().map(|h| h.child(&name));
+            let saved = handle.as_ref().and_then(|h| h.load());
+            (handle, saved)
+        } else {
+            (checkpoint.clone(), resume.clone())
+        };
+        if let Some(saved) = saved {
+            reader.restore_checkpoint(&saved)?;
+            if let Some(handle) = &table_checkpoint {
+                info!("[start_task] job '{}' 从 checkpoint 恢复", handle.key());
+            }
+        }
 
-    let reader: Arc<dyn DataReader> =
-        Arc::from(reader_registry.prepare_reader(&config.source.source_type, Arc::clone(&config))?);
-    let writer: Arc<dyn DataWriter> =
-        Arc::from(writer_registry.prepare_writer(&config.target.source_type, Arc::clone(&config))?);
+        let table_splits = splits.as_ref().map(|log| log.child(&name));
+        tables.push(TablePipeline {
+            name,
+            reader,
+            writer,
+            job_config: table_config,
+            checkpoint: table_checkpoint,
+            splits: table_splits,
+        });
+    }
 
-    // 先 split 获取 StreamMode，用于选择策略
+    let Some(first) = tables.first() else {
+        anyhow::bail!("job 中没有可同步的表");
+    };
+
+    let evolution = match config.schema_evolution() {
+        Some(schema_config) => {
+            let table_configs = tables
+                .iter()
+                .map(|t| (t.name.clone(), Arc::clone(&t.job_config)))
+                .collect();
+            let mut evolution =
+                SchemaEvolution::new(schema_config, table_configs, Arc::clone(&schema_log))?;
+            if let Some(reason) = evolution.check_all().await? {
+                anyhow::bail!("{}", reason);
+            }
+            Some(evolution)
+        }
+        None => None,
+    };
+
+    // 先 split 获取 StreamMode，用于选择策略（各表 source 类型相同）
     let runner_config = RunnerConfig::from_job_config(&config);
-    let split_result = reader.split(runner_config.reader_threads).await?;
+    let split_result = first.reader.split(runner_config.reader_threads).await?;
     let stream_mode = split_result.stream_mode;
 
     info!(
-        "[start_task] {} 模式, {} 个任务, 总记录数 {}",
+        "[start_task] {} 模式, {} 张表, 首表 {} 个任务, 总记录数 {}",
         match stream_mode {
             StreamMode::Batch => "Batch",
             StreamMode::Streaming => "Streaming",
         },
+        tables.len(),
         split_result.tasks.len(),
         split_result.total_records
     );
 
+    let run_token = cancel_token.child_token();
+    let watcher = match (evolution, stream_mode) {
+        (Some(evolution), StreamMode::Streaming) => {
+            Some(evolution.spawn_watcher(run_token.clone()))
+        }
+        _ => None,
+    };
+
     let runner = dispatch_runner(stream_mode, runner_config);
-    runner.run(reader, writer, config, cancel_token).await
+    let result = runner.run(tables, run_token.clone()).await;
+    run_token.cancel();
+
+    let mut result = result?;
+    if let Some(watcher) = watcher {
+        if let Ok(Some(reason)) = watcher.await {
+            result.status = RunStatus::Failed;
+            result.error = Some(reason);
+        }
+    }
+    result.schema_changes = schema_log.lock().clone();
+    Ok(result)
 }
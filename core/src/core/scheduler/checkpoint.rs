@@ -1,8 +1,39 @@
 use anyhow::Result;
-use redb::{Database, TableDefinition};
+use redb::{Database, ReadableTable, TableDefinition};
+use relus_common::JobConfig;
+use relus_reader::ReadTask;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::Arc;
 
 const TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("checkpoints");
+/// 批量任务的运行记录：run id → [`RunRecord`]
+const RUNS: TableDefinition<&str, &[u8]> = TableDefinition::new("runs");
+/// 批量任务的分片记录：`run/表名/task_id` → [`SplitRecord`]
+const SPLITS: TableDefinition<&str, &[u8]> = TableDefinition::new("splits");
+
+/// 一次批量运行，续跑时按其中的配置重新执行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub job_name: String,
+    pub config: JobConfig,
+    pub started_at: String,
+}
+
+/// 一个读取分片及其是否已完成（读取完毕且全部写入）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitRecord {
+    pub task: ReadTask,
+    pub done: bool,
+}
+
+/// 分片 key 的前缀，`table` 为 None 时匹配整个运行
+fn split_prefix(run_id: &str, table: Option<&str>) -> String {
+    match table {
+        Some(table) => format!("{}/{}/", run_id, table),
+        None => format!("{}/", run_id),
+    }
+}
 
 /// CDC Checkpoint 持久化存储
 pub struct CheckpointStore {
@@ -14,6 +45,8 @@ impl CheckpointStore {
         let db = Database::create(path)?;
         let write_tx = db.begin_write()?;
         write_tx.open_table(TABLE)?;
+        write_tx.open_table(RUNS)?;
+        write_tx.open_table(SPLITS)?;
         write_tx.commit()?;
         Ok(Self { db })
     }
@@ -44,4 +77,325 @@ impl CheckpointStore {
         write_tx.commit()?;
         Ok(())
     }
+
+    /// 删除 job 及其下各表（`job/表名`）的 checkpoint
+    pub fn remove_with_children(&self, job_id: &str) -> Result<()> {
+        let prefix = format!("{}/", job_id);
+        let write_tx = self.db.begin_write()?;
+        {
+            let mut table = write_tx.open_table(TABLE)?;
+            let _ = table.remove(job_id);
+            table.retain_in(prefix.as_str().., |key, _| !key.starts_with(&prefix))?;
+        }
+        write_tx.commit()?;
+        Ok(())
+    }
+
+    /// 记录一次新的运行，并清除该 run id 下已有的分片记录
+    pub fn save_run(&self, run_id: &str, record: &RunRecord) -> Result<()> {
+        let value = serde_json::to_vec(record)?;
+        let prefix = split_prefix(run_id, None);
+        let write_tx = self.db.begin_write()?;
+        {
+            let mut runs = write_tx.open_table(RUNS)?;
+            runs.insert(run_id, value.as_slice())?;
+            let mut splits = write_tx.open_table(SPLITS)?;
+            splits.retain_in(prefix.as_str().., |key, _| !key.starts_with(&prefix))?;
+        }
+        write_tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_run(&self, run_id: &str) -> Option<RunRecord> {
+        let read_tx = self.db.begin_read().ok()?;
+        let table = read_tx.open_table(RUNS).ok()?;
+        let access = table.get(run_id).ok()??;
+        serde_json::from_slice(access.value()).ok()
+    }
+
+    /// 记录一张表本次切分出的全部分片（均未完成），替换该表已有的记录
+    pub fn plan_splits(&self, run_id: &str, table: &str, tasks: &[ReadTask]) -> Result<()> {
+        let prefix = split_prefix(run_id, Some(table));
+        let write_tx = self.db.begin_write()?;
+        {
+            let mut splits = write_tx.open_table(SPLITS)?;
+            splits.retain_in(prefix.as_str().., |key, _| !key.starts_with(&prefix))?;
+            for task in tasks {
+                let record = SplitRecord {
+                    task: task.clone(),
+                    done: false,
+                };
+                let key = format!("{}{}", prefix, task.task_id);
+                splits.insert(key.as_str(), serde_json::to_vec(&record)?.as_slice())?;
+            }
+        }
+        write_tx.commit()?;
+        Ok(())
+    }
+
+    /// 把表中的若干分片标记为已完成
+    pub fn complete_splits(&self, run_id: &str, table: &str, task_ids: &[usize]) -> Result<()> {
+        let prefix = split_prefix(run_id, Some(table));
+        let write_tx = self.db.begin_write()?;
+        {
+            let mut splits = write_tx.open_table(SPLITS)?;
+            for task_id in task_ids {
+                let key = format!("{}{}", prefix, task_id);
+                let record = match splits.get(key.as_str())? {
+                    Some(access) => serde_json::from_slice::<SplitRecord>(access.value())?,
+                    None => continue,
+                };
+                let record = SplitRecord {
+                    done: true,
+                    ..record
+                };
+                splits.insert(key.as_str(), serde_json::to_vec(&record)?.as_slice())?;
+            }
+        }
+        write_tx.commit()?;
+        Ok(())
+    }
+
+    /// 读取分片记录，`table` 为 None 时返回整个运行的所有表
+    pub fn load_splits(&self, run_id: &str, table: Option<&str>) -> Result<Vec<SplitRecord>> {
+        let prefix = split_prefix(run_id, table);
+        let read_tx = self.db.begin_read()?;
+        let splits = read_tx.open_table(SPLITS)?;
+        let mut records = Vec::new();
+        for entry in splits.range(prefix.as_str()..)? {
+            let (key, value) = entry?;
+            if !key.value().starts_with(&prefix) {
+                break;
+            }
+            records.push(serde_json::from_slice(value.value())?);
+        }
+        Ok(records)
+    }
+}
+
+/// 单个 job 的 checkpoint 访问句柄
+///
+/// 以 job 名称（配置中的 job_id 或文件名）作为 key，而不是每次运行生成的实例 id，
+/// 这样重启或重新提交后仍能找到上次的位点。
+#[derive(Clone)]
+pub struct CheckpointHandle {
+    store: Arc<CheckpointStore>,
+    key: String,
+}
+
+impl CheckpointHandle {
+    pub fn new(store: Arc<CheckpointStore>, key: impl Into<String>) -> Self {
+        Self {
+            store,
+            key: key.into(),
+        }
+    }
+
+    /// 多表任务中某张表的句柄，key 为 `job/表名`
+    pub fn child(&self, name: &str) -> Self {
+        Self {
+            store: Arc::clone(&self.store),
+            key: format!("{}/{}", self.key, name),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn load(&self) -> Option<Vec<u8>> {
+        self.store.load(&self.key)
+    }
+
+    pub fn save(&self, position: &[u8]) -> Result<()> {
+        self.store.save(&self.key, position)
+    }
+
+    /// 在阻塞线程池中保存，redb 提交（fsync）不占用 async worker
+    pub async fn save_async(&self, position: Vec<u8>) -> Result<()> {
+        let handle = self.clone();
+        tokio::task::spawn_blocking(move || handle.save(&position))
+            .await
+            .map_err(|e| anyhow::anyhow!("checkpoint 保存任务崩溃: {}", e))?
+    }
+}
+
+/// 批量任务分片记录的访问句柄
+///
+/// 以运行实例 id 作为 key：每次运行先记录各表切分出的分片，Pair 成功后标记完成；
+/// 续跑（`resume`）时沿用原 run id，只重新执行未完成的分片。
+#[derive(Clone)]
+pub struct SplitLog {
+    store: Arc<CheckpointStore>,
+    run_id: String,
+    table: String,
+    resume: bool,
+}
+
+impl SplitLog {
+    /// 开始一次新的运行
+    pub fn start(store: Arc<CheckpointStore>, run_id: &str, record: &RunRecord) -> Result<Self> {
+        store.save_run(run_id, record)?;
+        Ok(Self {
+            store,
+            run_id: run_id.to_string(),
+            table: String::new(),
+            resume: false,
+        })
+    }
+
+    /// 续跑已记录的运行
+    pub fn resume(store: Arc<CheckpointStore>, run_id: &str) -> Self {
+        Self {
+            store,
+            run_id: run_id.to_string(),
+            table: String::new(),
+            resume: true,
+        }
+    }
+
+    /// 某张表的句柄
+    pub fn child(&self, table: &str) -> Self {
+        Self {
+            store: Arc::clone(&self.store),
+            run_id: self.run_id.clone(),
+            table: table.to_string(),
+            resume: self.resume,
+        }
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn is_resume(&self) -> bool {
+        self.resume
+    }
+
+    /// 续跑时该表上次记录的未完成分片；上次没有走到该表（没有记录）时返回 None
+    pub fn pending(&self) -> Result<Option<Vec<ReadTask>>> {
+        if !self.resume {
+            return Ok(None);
+        }
+        let records = self.store.load_splits(&self.run_id, Some(&self.table))?;
+        if records.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(
+            records
+                .into_iter()
+                .filter(|r| !r.done)
+                .map(|r| r.task)
+                .collect(),
+        ))
+    }
+
+    pub fn plan(&self, tasks: &[ReadTask]) -> Result<()> {
+        self.store.plan_splits(&self.run_id, &self.table, tasks)
+    }
+
+    pub fn complete(&self, task_ids: &[usize]) -> Result<()> {
+        self.store
+            .complete_splits(&self.run_id, &self.table, task_ids)
+    }
+}
+
+impl std::fmt::Debug for CheckpointHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckpointHandle")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handle_saves_and_removes_by_job_name() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let store = Arc::new(CheckpointStore::open(&dir.path().join("cp.redb")).expect("store"));
+        let handle = CheckpointHandle::new(Arc::clone(&store), "cdc-job");
+
+        assert_eq!(handle.load(), None);
+        handle.save(b"mysql-bin.000001:4").expect("save");
+        assert_eq!(handle.load(), Some(b"mysql-bin.000001:4".to_vec()));
+        assert_eq!(store.load("cdc-job"), handle.load());
+
+        store.remove("cdc-job").expect("remove");
+        assert_eq!(handle.load(), None);
+    }
+
+    #[test]
+    fn remove_with_children_clears_table_checkpoints_only() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let store = Arc::new(CheckpointStore::open(&dir.path().join("cp.redb")).expect("store"));
+        let job = CheckpointHandle::new(Arc::clone(&store), "orders");
+        let table = job.child("items");
+        assert_eq!(table.key(), "orders/items");
+
+        job.save(b"job").expect("save");
+        table.save(b"table").expect("save");
+        store.save("orders-archive", b"other").expect("save");
+
+        store.remove_with_children("orders").expect("remove");
+        assert_eq!(job.load(), None);
+        assert_eq!(table.load(), None);
+        assert_eq!(store.load("orders-archive"), Some(b"other".to_vec()));
+    }
+
+    fn read_task(task_id: usize) -> ReadTask {
+        ReadTask {
+            task_id,
+            conn: serde_json::json!({"table": "orders"}),
+            query_sql: Some(format!(
+                "SELECT * FROM orders WHERE id >= {}",
+                task_id * 100
+            )),
+            offset: 0,
+            limit: 100,
+        }
+    }
+
+    #[test]
+    fn resume_returns_only_unfinished_splits() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let store = Arc::new(CheckpointStore::open(&dir.path().join("cp.redb")).expect("store"));
+        let record = RunRecord {
+            job_name: "orders".to_string(),
+            config: serde_json::from_value(serde_json::json!({
+                "source": {"name": "s", "type": "database", "config": {}},
+                "target": {"name": "t", "type": "database", "config": {}}
+            }))
+            .expect("config"),
+            started_at: "2026-10-17T00:00:00Z".to_string(),
+        };
+
+        let log = SplitLog::start(Arc::clone(&store), "orders-1", &record).expect("start");
+        let table = log.child("orders");
+        assert_eq!(table.pending().expect("pending").map(|t| t.len()), None);
+        table
+            .plan(&[read_task(0), read_task(1), read_task(2)])
+            .expect("plan");
+        table.complete(&[0, 2]).expect("complete");
+
+        let resumed = SplitLog::resume(Arc::clone(&store), "orders-1");
+        let pending = resumed.child("orders").pending().expect("pending");
+        let ids: Option<Vec<usize>> = pending.map(|t| t.iter().map(|t| t.task_id).collect());
+        assert_eq!(ids, Some(vec![1]));
+        // 原运行没有走到的表没有记录，续跑时重新切分
+        assert!(resumed.child("items").pending().expect("pending").is_none());
+        assert_eq!(
+            store.load_run("orders-1").map(|r| r.job_name),
+            Some("orders".to_string())
+        );
+
+        // 重新开始同一个 run id 时清空分片记录
+        SplitLog::start(Arc::clone(&store), "orders-1", &record).expect("restart");
+        assert!(store
+            .load_splits("orders-1", None)
+            .expect("splits")
+            .is_empty());
+    }
 }
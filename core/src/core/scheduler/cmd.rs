@@ -86,6 +86,9 @@ pub struct TaskInfo {
     pub schedule: Option<String>,
     pub stats: Option<TaskStats>,
     pub error: Option<String>,
+    /// 运行中检测到的源表结构变更
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schema_changes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,6 +112,7 @@ pub enum TaskDoneResult {
     Success {
         records_read: usize,
         records_written: usize,
+        records_failed: usize,
         elapsed_secs: f64,
     },
     Failed(String),
@@ -1,5 +1,7 @@
 use super::cmd::{Schedule, TaskInfo};
 use relus_common::job_config::JobConfig;
+use relus_reader::rdbms_reader_util::util::watermark::WatermarkMark;
+use relus_reader::BinlogPosition;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -22,6 +24,24 @@ pub enum SchedulerCommand {
         job_id: String,
         reply: oneshot::Sender<SchedulerResult>,
     },
+    QueryCheckpoint {
+        job_id: String,
+        reply: oneshot::Sender<SchedulerResult>,
+    },
+    ResetCheckpoint {
+        job_id: String,
+        reply: oneshot::Sender<SchedulerResult>,
+    },
+    SetCheckpoint {
+        job_id: String,
+        position: BinlogPosition,
+        reply: oneshot::Sender<SchedulerResult>,
+    },
+    /// 续跑一次批量运行中失败或未执行的分片
+    ResumeRun {
+        run_id: String,
+        reply: oneshot::Sender<SchedulerResult>,
+    },
     Shutdown {
         reply: oneshot::Sender<SchedulerResult>,
     },
@@ -76,6 +96,56 @@ impl SchedulerControlHandle {
         rx.await.map_err(|_| SchedulerError::SchedulerUnavailable)?
     }
 
+    pub async fn query_checkpoint(
+        &self,
+        job_id: String,
+    ) -> Result<SchedulerResponse, SchedulerError> {
+        let (reply, rx) = oneshot::channel();
+        self.tx
+            .send(SchedulerCommand::QueryCheckpoint { job_id, reply })
+            .await
+            .map_err(|_| SchedulerError::SchedulerUnavailable)?;
+        rx.await.map_err(|_| SchedulerError::SchedulerUnavailable)?
+    }
+
+    pub async fn reset_checkpoint(
+        &self,
+        job_id: String,
+    ) -> Result<SchedulerResponse, SchedulerError> {
+        let (reply, rx) = oneshot::channel();
+        self.tx
+            .send(SchedulerCommand::ResetCheckpoint { job_id, reply })
+            .await
+            .map_err(|_| SchedulerError::SchedulerUnavailable)?;
+        rx.await.map_err(|_| SchedulerError::SchedulerUnavailable)?
+    }
+
+    pub async fn set_checkpoint(
+        &self,
+        job_id: String,
+        position: BinlogPosition,
+    ) -> Result<SchedulerResponse, SchedulerError> {
+        let (reply, rx) = oneshot::channel();
+        self.tx
+            .send(SchedulerCommand::SetCheckpoint {
+                job_id,
+                position,
+                reply,
+            })
+            .await
+            .map_err(|_| SchedulerError::SchedulerUnavailable)?;
+        rx.await.map_err(|_| SchedulerError::SchedulerUnavailable)?
+    }
+
+    pub async fn resume_run(&self, run_id: String) -> Result<SchedulerResponse, SchedulerError> {
+        let (reply, rx) = oneshot::channel();
+        self.tx
+            .send(SchedulerCommand::ResumeRun { run_id, reply })
+            .await
+            .map_err(|_| SchedulerError::SchedulerUnavailable)?;
+        rx.await.map_err(|_| SchedulerError::SchedulerUnavailable)?
+    }
+
     pub async fn shutdown(&self) -> Result<SchedulerResponse, SchedulerError> {
         let (reply, rx) = oneshot::channel();
         self.tx
@@ -98,6 +168,26 @@ pub enum SchedulerResponse {
     TaskCancelled {
         job_id: String,
     },
+    Checkpoint {
+        job_name: String,
+        position: Option<BinlogPosition>,
+        /// 水位增量 job 的 checkpoint 是水位而非 binlog 位点
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        watermark: Option<WatermarkMark>,
+    },
+    CheckpointReset {
+        job_name: String,
+    },
+    CheckpointUpdated {
+        job_name: String,
+        position: BinlogPosition,
+    },
+    /// `pending` 为本次续跑的分片数，上次没有走到的表不计入
+    RunResumed {
+        job_id: String,
+        pending: usize,
+        total: usize,
+    },
     ShutdownRequested,
 }
 
@@ -105,6 +195,7 @@ pub enum SchedulerResponse {
 pub enum SchedulerError {
     JobNotFound { job_id: String },
     JobAlreadyExists { job_id: String },
+    JobRunning { job_id: String },
     InvalidConfig { message: String },
     MaxConcurrencyReached { running: usize, limit: usize },
     SchedulerUnavailable,
@@ -121,6 +212,12 @@ impl SchedulerError {
                     job_id
                 )
             }
+            SchedulerError::JobRunning { job_id } => {
+                format!(
+                    "Job '{}' is running. Cancel it before changing its checkpoint.",
+                    job_id
+                )
+            }
             SchedulerError::InvalidConfig { message } => message.clone(),
             SchedulerError::MaxConcurrencyReached { running, limit } => {
                 format!("Max concurrency reached ({}/{}).", running, limit)
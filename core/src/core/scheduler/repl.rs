@@ -1,4 +1,5 @@
 use super::control::{SchedulerControlHandle, SchedulerError, SchedulerResponse};
+use relus_reader::BinlogPosition;
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use tracing::warn;
@@ -8,13 +9,40 @@ const PROMPT: &str = "relus_cli> ";
 /// REPL 命令
 #[derive(Debug)]
 pub enum ReplCommand {
-    Status { job_id: Option<String> },
-    Submit { path: String },
-    Cancel { job_id: String },
+    Status {
+        job_id: Option<String>,
+    },
+    Submit {
+        path: String,
+    },
+    Cancel {
+        job_id: String,
+    },
+    Checkpoint {
+        job_id: String,
+        action: CheckpointAction,
+    },
+    Resume {
+        run_id: String,
+    },
     Exit,
-    Invalid { raw: String, hint: String },
+    Invalid {
+        raw: String,
+        hint: String,
+    },
 }
 
+/// `checkpoint` 子命令
+#[derive(Debug, PartialEq, Eq)]
+pub enum CheckpointAction {
+    Show,
+    Reset,
+    Set(BinlogPosition),
+}
+
+const CHECKPOINT_USAGE: &str =
+    "Usage: checkpoint <job> [reset | set <binlog_file> <binlog_position> | gtid <gtid_set>]";
+
 /// 简易引号感知 tokenizer
 ///
 /// 支持双引号包裹含空格的参数，如 `submit "C:\my path\job.json"`
@@ -79,16 +107,50 @@ impl ReplCommand {
                     hint: "Usage: cancel <job_id>".to_string(),
                 },
             },
+            "checkpoint" => Self::parse_checkpoint(&tokens[1..]),
+            "resume" => match tokens.get(1) {
+                Some(id) => Self::Resume {
+                    run_id: id.clone(),
+                },
+                None => Self::Invalid {
+                    raw: "resume".to_string(),
+                    hint: "Usage: resume <run_id>".to_string(),
+                },
+            },
             "exit" | "quit" => Self::Exit,
             _ => Self::Invalid {
                 raw: cmd.to_string(),
                 hint: format!(
-                    "(error) Unknown command '{}'. Available commands: status [job_id], submit <path>, cancel <job_id>, exit",
+                    "(error) Unknown command '{}'. Available commands: status [job_id], submit <path>, cancel <job_id>, checkpoint <job>, resume <run_id>, exit",
                     cmd
                 ),
             },
         }
     }
+
+    fn parse_checkpoint(args: &[String]) -> Self {
+        let invalid = || Self::Invalid {
+            raw: "checkpoint".to_string(),
+            hint: CHECKPOINT_USAGE.to_string(),
+        };
+        let Some(job_id) = args.first().cloned() else {
+            return invalid();
+        };
+
+        let action = match &args[1..] {
+            [] => CheckpointAction::Show,
+            [cmd] if cmd == "reset" => CheckpointAction::Reset,
+            [cmd, file, pos] if cmd == "set" => match pos.parse::<u32>() {
+                Ok(pos) => CheckpointAction::Set(BinlogPosition::from_file(file.clone(), pos)),
+                Err(_) => return invalid(),
+            },
+            [cmd, gtid] if cmd == "gtid" => {
+                CheckpointAction::Set(BinlogPosition::from_gtid(gtid.clone()))
+            }
+            _ => return invalid(),
+        };
+        Self::Checkpoint { job_id, action }
+    }
 }
 
 /// 交互式 REPL 循环
@@ -182,6 +244,24 @@ impl ReplLoop {
                 .await
                 .map(format_repl_response)
                 .unwrap_or_else(format_repl_error),
+            ReplCommand::Checkpoint { job_id, action } => {
+                let result = match action {
+                    CheckpointAction::Show => self.control.query_checkpoint(job_id).await,
+                    CheckpointAction::Reset => self.control.reset_checkpoint(job_id).await,
+                    CheckpointAction::Set(position) => {
+                        self.control.set_checkpoint(job_id, position).await
+                    }
+                };
+                result
+                    .map(format_repl_response)
+                    .unwrap_or_else(format_repl_error)
+            }
+            ReplCommand::Resume { run_id } => self
+                .control
+                .resume_run(run_id)
+                .await
+                .map(format_repl_response)
+                .unwrap_or_else(format_repl_error),
             ReplCommand::Exit => self
                 .control
                 .shutdown()
@@ -223,6 +303,9 @@ fn format_repl_response(response: SchedulerResponse) -> String {
                     "{:<20} {:<12} {:<6} {:>12} {:>12} {:>10}",
                     task.job_id, task.phase, task.is_cdc, read, written, elapsed
                 ));
+                for change in &task.schema_changes {
+                    lines.push(format!("  schema: {}", change));
+                }
             }
 
             lines.join("\n")
@@ -231,6 +314,32 @@ fn format_repl_response(response: SchedulerResponse) -> String {
         SchedulerResponse::TaskCancelled { job_id } => {
             format!("Job '{}' cancel signal sent.", job_id)
         }
+        SchedulerResponse::Checkpoint {
+            job_name,
+            position,
+            watermark,
+        } => match (position, watermark) {
+            (Some(position), _) => format!("Job '{}' checkpoint: {}", job_name, position),
+            (None, Some(mark)) => format!(
+                "Job '{}' watermark: {} = {}",
+                job_name, mark.column, mark.value
+            ),
+            (None, None) => format!("Job '{}' has no checkpoint.", job_name),
+        },
+        SchedulerResponse::CheckpointReset { job_name } => {
+            format!("Job '{}' checkpoint reset.", job_name)
+        }
+        SchedulerResponse::CheckpointUpdated { job_name, position } => {
+            format!("Job '{}' checkpoint set to {}.", job_name, position)
+        }
+        SchedulerResponse::RunResumed {
+            job_id,
+            pending,
+            total,
+        } => format!(
+            "Run '{}' resumed ({}/{} splits pending).",
+            job_id, pending, total
+        ),
         SchedulerResponse::ShutdownRequested => "Scheduler shutdown requested.".to_string(),
     }
 }
@@ -279,5 +388,45 @@ mod tests {
             ReplCommand::parse("foo bar"),
             ReplCommand::Invalid { .. }
         ));
+        assert!(matches!(
+            ReplCommand::parse("resume orders-20261017"),
+            ReplCommand::Resume { ref run_id } if run_id == "orders-20261017"
+        ));
+        assert!(matches!(
+            ReplCommand::parse("resume"),
+            ReplCommand::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn parse_checkpoint_commands() {
+        assert!(matches!(
+            ReplCommand::parse("checkpoint cdc-job"),
+            ReplCommand::Checkpoint { ref job_id, action: CheckpointAction::Show } if job_id == "cdc-job"
+        ));
+        assert!(matches!(
+            ReplCommand::parse("checkpoint cdc-job reset"),
+            ReplCommand::Checkpoint {
+                action: CheckpointAction::Reset,
+                ..
+            }
+        ));
+
+        match ReplCommand::parse("checkpoint cdc-job set mysql-bin.000003 194") {
+            ReplCommand::Checkpoint { action, .. } => assert_eq!(
+                action,
+                CheckpointAction::Set(BinlogPosition::from_file("mysql-bin.000003", 194))
+            ),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        assert!(matches!(
+            ReplCommand::parse("checkpoint cdc-job set mysql-bin.000003 abc"),
+            ReplCommand::Invalid { .. }
+        ));
+        assert!(matches!(
+            ReplCommand::parse("checkpoint"),
+            ReplCommand::Invalid { .. }
+        ));
     }
 }
@@ -1,4 +1,4 @@
-use super::checkpoint::CheckpointStore;
+use super::checkpoint::{CheckpointHandle, CheckpointStore, RunRecord, SplitLog};
 use super::cmd::{Schedule, TaskDoneEvent, TaskDoneResult, TaskInfo};
 use super::control::{
     load_job_config_from_path, SchedulerCommand, SchedulerControlHandle, SchedulerError,
@@ -7,9 +7,11 @@ use super::control::{
 use super::cron::CronTracker;
 use super::repl::ReplLoop;
 use super::task_slot::{TaskPhase, TaskSlot};
-use crate::core::runner::{start_task, RunStatus};
+use crate::core::runner::{start_task_with_checkpoint, RunStatus};
 use anyhow::Result;
-use relus_common::job_config::{JobConfig, SyncMode};
+use relus_common::job_config::JobConfig;
+use relus_reader::rdbms_reader_util::util::watermark::WatermarkMark;
+use relus_reader::BinlogPosition;
 use std::any::Any;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -29,8 +31,7 @@ pub struct TaskScheduler {
     cmd_rx: mpsc::Receiver<SchedulerCommand>,
     cmd_tx: mpsc::Sender<SchedulerCommand>,
     cron_tracker: CronTracker,
-    #[allow(dead_code)]
-    checkpoint: CheckpointStore,
+    checkpoint: Arc<CheckpointStore>,
     shutdown_token: CancellationToken,
     repl_cancel: CancellationToken,
     repl_alive: Arc<AtomicBool>,
@@ -40,7 +41,7 @@ impl TaskScheduler {
     pub fn new(checkpoint_path: PathBuf) -> Result<Self> {
         let (done_tx, done_rx) = mpsc::channel(256);
         let (cmd_tx, cmd_rx) = mpsc::channel(256);
-        let checkpoint = CheckpointStore::open(&checkpoint_path)?;
+        let checkpoint = Arc::new(CheckpointStore::open(&checkpoint_path)?);
         Ok(Self {
             slots: HashMap::new(),
             configs: HashMap::new(),
@@ -163,6 +164,26 @@ impl TaskScheduler {
                 let _ = reply.send(result);
                 false
             }
+            SchedulerCommand::QueryCheckpoint { job_id, reply } => {
+                let _ = reply.send(self.query_checkpoint(&job_id));
+                false
+            }
+            SchedulerCommand::ResetCheckpoint { job_id, reply } => {
+                let _ = reply.send(self.reset_checkpoint(&job_id));
+                false
+            }
+            SchedulerCommand::SetCheckpoint {
+                job_id,
+                position,
+                reply,
+            } => {
+                let _ = reply.send(self.set_checkpoint(&job_id, position));
+                false
+            }
+            SchedulerCommand::ResumeRun { run_id, reply } => {
+                let _ = reply.send(self.resume_run(&run_id));
+                false
+            }
             SchedulerCommand::Shutdown { reply } => {
                 self.repl_cancel.cancel();
                 info!("[TaskScheduler] exit command received, shutting down");
@@ -193,6 +214,158 @@ impl TaskScheduler {
         }
     }
 
+    /// 运行实例 id 映射回 job 名称；checkpoint 按 job 名称保存，两者都可作为参数
+    fn resolve_job_name(&self, job_id: &str) -> String {
+        self.configs
+            .get(job_id)
+            .map(|(_, _, name)| name.clone())
+            .unwrap_or_else(|| job_id.to_string())
+    }
+
+    /// 修改 checkpoint 前确认该 job 没有在运行，否则会被运行中的任务覆盖
+    fn ensure_job_idle(&self, job_name: &str) -> Result<(), SchedulerError> {
+        match self.active_job_for_name(job_name) {
+            Some(job_id) => Err(SchedulerError::JobRunning { job_id }),
+            None => Ok(()),
+        }
+    }
+
+    fn query_checkpoint(&self, job_id: &str) -> Result<SchedulerResponse, SchedulerError> {
+        let job_name = self.resolve_job_name(job_id);
+        let Some(bytes) = self.checkpoint.load(&job_name) else {
+            return Ok(SchedulerResponse::Checkpoint {
+                job_name,
+                position: None,
+                watermark: None,
+            });
+        };
+
+        match BinlogPosition::from_bytes(&bytes) {
+            Ok(position) => Ok(SchedulerResponse::Checkpoint {
+                job_name,
+                position: Some(position),
+                watermark: None,
+            }),
+            Err(e) => {
+                let watermark =
+                    WatermarkMark::from_bytes(&bytes).map_err(|_| SchedulerError::Internal {
+                        message: e.to_string(),
+                    })?;
+                Ok(SchedulerResponse::Checkpoint {
+                    job_name,
+                    position: None,
+                    watermark: Some(watermark),
+                })
+            }
+        }
+    }
+
+    fn reset_checkpoint(&self, job_id: &str) -> Result<SchedulerResponse, SchedulerError> {
+        let job_name = self.resolve_job_name(job_id);
+        self.ensure_job_idle(&job_name)?;
+        self.checkpoint
+            .remove_with_children(&job_name)
+            .map_err(|e| SchedulerError::Internal {
+                message: e.to_string(),
+            })?;
+        info!("[TaskScheduler] checkpoint of '{}' reset", job_name);
+        Ok(SchedulerResponse::CheckpointReset { job_name })
+    }
+
+    fn set_checkpoint(
+        &self,
+        job_id: &str,
+        position: BinlogPosition,
+    ) -> Result<SchedulerResponse, SchedulerError> {
+        let job_name = self.resolve_job_name(job_id);
+        self.ensure_job_idle(&job_name)?;
+        position
+            .validate()
+            .map_err(|e| SchedulerError::InvalidConfig {
+                message: e.to_string(),
+            })?;
+        position
+            .to_bytes()
+            .and_then(|bytes| self.checkpoint.save(&job_name, &bytes))
+            .map_err(|e| SchedulerError::Internal {
+                message: e.to_string(),
+            })?;
+        info!(
+            "[TaskScheduler] checkpoint of '{}' set to {}",
+            job_name, position
+        );
+        Ok(SchedulerResponse::CheckpointUpdated { job_name, position })
+    }
+
+    /// 续跑一次批量运行：沿用原 run id 与配置，只执行失败或未执行的分片
+    fn resume_run(&mut self, run_id: &str) -> Result<SchedulerResponse, SchedulerError> {
+        let Some(record) = self.checkpoint.load_run(run_id) else {
+            return Err(SchedulerError::JobNotFound {
+                job_id: run_id.to_string(),
+            });
+        };
+        if let Some(job_id) = self.active_job_for_name(&record.job_name) {
+            return Err(SchedulerError::JobAlreadyExists { job_id });
+        }
+        if record.config.is_cdc() {
+            return Err(SchedulerError::InvalidConfig {
+                message: format!("Job '{}' is a CDC job and cannot be resumed.", run_id),
+            });
+        }
+        let splits =
+            self.checkpoint
+                .load_splits(run_id, None)
+                .map_err(|e| SchedulerError::Internal {
+                    message: e.to_string(),
+                })?;
+        let total = splits.len();
+        let pending = splits.iter().filter(|s| !s.done).count();
+
+        let config = Arc::new(record.config);
+        self.configs
+            .entry(run_id.to_string())
+            .or_insert_with(|| (Arc::clone(&config), false, record.job_name.clone()));
+        let log = SplitLog::resume(Arc::clone(&self.checkpoint), run_id);
+        self.spawn_task(
+            run_id.to_string(),
+            record.job_name,
+            config,
+            false,
+            Some(log),
+        );
+        info!(
+            "[TaskScheduler] run '{}' resumed, {}/{} splits pending",
+            run_id, pending, total
+        );
+        Ok(SchedulerResponse::RunResumed {
+            job_id: run_id.to_string(),
+            pending,
+            total,
+        })
+    }
+
+    /// 为批量任务的一次运行记录分片，run id 即运行实例 id（cron 任务每次触发覆盖上次的记录）
+    fn start_splits(&self, job_id: &str, job_name: &str, config: &JobConfig) -> Option<SplitLog> {
+        if config.is_cdc() {
+            return None;
+        }
+        let record = RunRecord {
+            job_name: job_name.to_string(),
+            config: config.clone(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+        };
+        match SplitLog::start(Arc::clone(&self.checkpoint), job_id, &record) {
+            Ok(log) => Some(log),
+            Err(e) => {
+                warn!(
+                    "[TaskScheduler] failed to record splits of job '{}': {}",
+                    job_id, e
+                );
+                None
+            }
+        }
+    }
+
     /// 提交任务（程序启动时调用）
     pub fn submit_task(
         &mut self,
@@ -218,7 +391,7 @@ impl TaskScheduler {
             }));
         }
 
-        let is_cdc = config.sync_mode == Some(SyncMode::Incremental);
+        let is_cdc = config.is_cdc();
         let job_id = self.next_run_job_id(&job_name);
         self.configs.insert(
             job_id.clone(),
@@ -227,11 +400,12 @@ impl TaskScheduler {
 
         match &schedule {
             Schedule::Immediate => {
-                self.spawn_task(job_id.clone(), job_name, config, is_cdc);
+                let splits = self.start_splits(&job_id, &job_name, &config);
+                self.spawn_task(job_id.clone(), job_name, config, is_cdc, splits);
             }
             Schedule::Once(_) | Schedule::Cron(_) => {
                 if is_cdc {
-                    self.spawn_task(job_id.clone(), job_name, config, true);
+                    self.spawn_task(job_id.clone(), job_name, config, true, None);
                 } else {
                     self.cron_tracker.register(job_id.clone(), schedule);
                     info!("[TaskScheduler] job '{}' scheduled", job_id);
@@ -261,7 +435,8 @@ impl TaskScheduler {
         };
 
         info!("[TaskScheduler] cron job '{}' fired", job_id);
-        self.spawn_task(job_id.to_string(), job_name, config, is_cdc);
+        let splits = self.start_splits(job_id, &job_name, &config);
+        self.spawn_task(job_id.to_string(), job_name, config, is_cdc, splits);
     }
 
     fn spawn_task(
@@ -270,14 +445,27 @@ impl TaskScheduler {
         job_name: String,
         config: Arc<JobConfig>,
         is_cdc: bool,
+        splits: Option<SplitLog>,
     ) {
         let cancel_token = CancellationToken::new();
         let done_tx = self.done_tx.clone();
         let id = job_id.clone();
         let token_clone = cancel_token.clone();
+        let checkpoint = CheckpointHandle::new(Arc::clone(&self.checkpoint), job_name.clone());
+        let slot = TaskSlot::new(job_id.clone(), job_name, is_cdc, None, cancel_token);
+        let schema_log = Arc::clone(&slot.schema_changes);
 
         tokio::spawn(async move {
-            let run_handle = tokio::spawn(async move { start_task(config, token_clone).await });
+            let run_handle = tokio::spawn(async move {
+                start_task_with_checkpoint(
+                    config,
+                    token_clone,
+                    Some(checkpoint),
+                    splits,
+                    schema_log,
+                )
+                .await
+            });
             let done_result = match run_handle.await {
                 Ok(result) => task_result_to_done(result),
                 Err(error) => TaskDoneResult::Failed(join_error_message(error)),
@@ -291,7 +479,6 @@ impl TaskScheduler {
                 .await;
         });
 
-        let slot = TaskSlot::new(job_id.clone(), job_name, is_cdc, None, cancel_token);
         self.slots.insert(job_id.clone(), slot);
         info!("[TaskScheduler] job '{}' spawned (cdc={})", job_id, is_cdc);
     }
@@ -303,11 +490,12 @@ impl TaskScheduler {
             TaskDoneResult::Success {
                 records_read,
                 records_written,
+                records_failed,
                 elapsed_secs,
             } => {
                 format!(
-                    "success (read={}, written={}, {:.2}s)",
-                    records_read, records_written, elapsed_secs
+                    "success (read={}, written={}, failed={}, {:.2}s)",
+                    records_read, records_written, records_failed, elapsed_secs
                 )
             }
             TaskDoneResult::Failed(msg) => format!("failed: {}", msg),
@@ -393,6 +581,7 @@ impl TaskScheduler {
                     }),
                     stats: None,
                     error: None,
+                    schema_changes: Vec::new(),
                 });
             }
         }
@@ -435,6 +624,7 @@ fn task_result_to_done(result: Result<crate::core::runner::RunResult>) -> TaskDo
             RunStatus::Success | RunStatus::Partial => TaskDoneResult::Success {
                 records_read: r.stats.records_read,
                 records_written: r.stats.records_written,
+                records_failed: r.stats.records_failed,
                 elapsed_secs: r.stats.elapsed_secs,
             },
             RunStatus::Shutdown => TaskDoneResult::Cancelled,
@@ -561,6 +751,7 @@ fn print_task_done(job_id: &str, is_cdc: bool, phase: &super::task_slot::TaskPha
 mod tests {
     use super::*;
     use relus_common::data_source_config::DataSourceConfig;
+    use relus_common::job_config::SyncMode;
     use std::collections::BTreeMap;
 
     fn scheduler() -> TaskScheduler {
@@ -576,6 +767,8 @@ mod tests {
             query_sql: None,
             writer_mode: None,
             config: serde_json::json!({}),
+            pre_sql: Vec::new(),
+            post_sql: Vec::new(),
         };
         let target = DataSourceConfig {
             name: "target".to_string(),
@@ -584,6 +777,8 @@ mod tests {
             query_sql: None,
             writer_mode: None,
             config: serde_json::json!({}),
+            pre_sql: Vec::new(),
+            post_sql: Vec::new(),
         };
 
         Arc::new(JobConfig {
@@ -596,6 +791,13 @@ mod tests {
             channel_buffer_size: None,
             job_id: None,
             schedule: None,
+            tables: Vec::new(),
+            migrate: None,
+            schema_discovery: None,
+            error_limit: None,
+            retry: None,
+            writer_threads: None,
+            speed: None,
         })
     }
 
@@ -677,6 +879,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn watermark_incremental_job_follows_its_cron_schedule() {
+        let mut scheduler = scheduler();
+        let mut config = (*job_config(Some(SyncMode::Incremental))).clone();
+        config.source.source_type = "database".to_string();
+        config.source.config = serde_json::json!({"watermark_column": "updated_at"});
+
+        let job_id = scheduler
+            .submit_task(
+                "orders-sync".to_string(),
+                Arc::new(config),
+                Schedule::Cron("*/5 * * * *".to_string()),
+            )
+            .expect("submit");
+
+        assert!(!scheduler.slots.contains_key(&job_id));
+        let tasks = scheduler.list_tasks();
+        let task = tasks
+            .iter()
+            .find(|task| task.job_id == job_id)
+            .expect("scheduled task");
+        assert_eq!(task.phase, "scheduled");
+        assert!(!task.is_cdc);
+    }
+
     #[test]
     fn running_job_name_cannot_be_submitted_again() {
         let mut scheduler = scheduler();
@@ -706,4 +933,129 @@ mod tests {
             &SchedulerError::JobAlreadyExists { job_id: active_id }
         );
     }
+
+    #[test]
+    fn checkpoint_can_be_set_queried_and_reset_by_job_name() {
+        let scheduler = scheduler();
+        let position = BinlogPosition::from_file("mysql-bin.000003", 194);
+
+        scheduler
+            .set_checkpoint("cdc-job", position.clone())
+            .expect("set checkpoint");
+        match scheduler.query_checkpoint("cdc-job").expect("query") {
+            SchedulerResponse::Checkpoint {
+                job_name,
+                position: saved,
+                ..
+            } => {
+                assert_eq!(job_name, "cdc-job");
+                assert_eq!(saved, Some(position));
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        scheduler.reset_checkpoint("cdc-job").expect("reset");
+        assert!(matches!(
+            scheduler.query_checkpoint("cdc-job"),
+            Ok(SchedulerResponse::Checkpoint { position: None, .. })
+        ));
+    }
+
+    #[test]
+    fn watermark_checkpoint_is_reported_as_watermark() {
+        let scheduler = scheduler();
+        let mark = WatermarkMark {
+            column: "updated_at".to_string(),
+            value: serde_json::json!("2024-03-01 00:00:00"),
+            keys: vec![serde_json::json!(42)],
+        };
+        scheduler
+            .checkpoint
+            .save("orders-sync", &mark.to_bytes().expect("encode"))
+            .expect("save");
+
+        match scheduler.query_checkpoint("orders-sync").expect("query") {
+            SchedulerResponse::Checkpoint {
+                position,
+                watermark,
+                ..
+            } => {
+                assert_eq!(position, None);
+                assert_eq!(watermark, Some(mark));
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn checkpoint_of_running_job_cannot_be_changed() {
+        let mut scheduler = scheduler();
+        let active_id = "cdc-job-active".to_string();
+        let slot = TaskSlot::new(
+            active_id.clone(),
+            "cdc-job".to_string(),
+            true,
+            None,
+            CancellationToken::new(),
+        );
+        scheduler.slots.insert(active_id.clone(), slot);
+
+        let err = scheduler
+            .reset_checkpoint("cdc-job")
+            .expect_err("running job should conflict");
+        assert_eq!(err, SchedulerError::JobRunning { job_id: active_id });
+
+        let err = scheduler
+            .set_checkpoint("other-job", BinlogPosition::default())
+            .expect_err("empty position is invalid");
+        assert!(matches!(err, SchedulerError::InvalidConfig { .. }));
+    }
+
+    #[test]
+    fn only_recorded_idle_batch_runs_can_be_resumed() {
+        let mut scheduler = scheduler();
+        let err = scheduler
+            .resume_run("missing-run")
+            .expect_err("unknown run");
+        assert_eq!(
+            err,
+            SchedulerError::JobNotFound {
+                job_id: "missing-run".to_string()
+            }
+        );
+
+        let config = job_config(Some(SyncMode::Fullsnapshot));
+        assert!(scheduler
+            .start_splits("daily-job-1", "daily-job", &config)
+            .is_some());
+        let active_id = "daily-job-2".to_string();
+        let slot = TaskSlot::new(
+            active_id.clone(),
+            "daily-job".to_string(),
+            false,
+            None,
+            CancellationToken::new(),
+        );
+        scheduler.slots.insert(active_id.clone(), slot);
+        let err = scheduler
+            .resume_run("daily-job-1")
+            .expect_err("same job is running");
+        assert_eq!(err, SchedulerError::JobAlreadyExists { job_id: active_id });
+
+        let cdc = job_config(Some(SyncMode::Mix));
+        assert!(scheduler
+            .start_splits("cdc-job-1", "cdc-job", &cdc)
+            .is_none());
+        let record = RunRecord {
+            job_name: "cdc-job".to_string(),
+            config: (*cdc).clone(),
+            started_at: String::new(),
+        };
+        scheduler
+            .checkpoint
+            .save_run("cdc-job-1", &record)
+            .expect("save run");
+        let err = scheduler.resume_run("cdc-job-1").expect_err("cdc job");
+        assert!(matches!(err, SchedulerError::InvalidConfig { .. }));
+    }
 }
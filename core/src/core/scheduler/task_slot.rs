@@ -1,4 +1,5 @@
 use crate::core::scheduler::cmd::{Schedule, TaskDoneResult, TaskInfo, TaskStats};
+use crate::core::schema_evolution::SchemaChangeLog;
 use std::time::{Instant, SystemTime};
 use tokio_util::sync::CancellationToken;
 
@@ -31,6 +32,8 @@ pub struct TaskSlot {
     pub started_at: Instant,
     pub is_cdc: bool,
     pub schedule: Option<Schedule>,
+    /// 运行中检测到的源表结构变更，与 runner 共享
+    pub schema_changes: SchemaChangeLog,
 }
 
 impl TaskSlot {
@@ -49,6 +52,7 @@ impl TaskSlot {
             started_at: Instant::now(),
             is_cdc,
             schedule,
+            schema_changes: SchemaChangeLog::default(),
         }
     }
 
@@ -76,6 +80,7 @@ impl TaskSlot {
                 TaskPhase::Failed(msg) => Some(msg.clone()),
                 _ => None,
             },
+            schema_changes: self.schema_changes.lock().clone(),
         }
     }
 
@@ -84,11 +89,12 @@ impl TaskSlot {
             TaskDoneResult::Success {
                 records_read,
                 records_written,
+                records_failed,
                 elapsed_secs,
             } => TaskPhase::Completed(TaskStats {
                 records_read,
                 records_written,
-                records_failed: 0,
+                records_failed,
                 elapsed_secs,
             }),
             TaskDoneResult::Failed(_) if self.cancel_token.is_cancelled() => TaskPhase::Cancelled,
@@ -0,0 +1,260 @@
+//! 源表结构演进
+//!
+//! 开启 `schema_discovery.enabled` 与 `check_evolution` 后，每次运行前、以及 CDC 任务运行中
+//! 每隔 `check_interval_secs`，发现源表结构并与 SchemaCache 中的最新版本比较：
+//! - 兼容变更（新增列、类型放宽、非空改可空）同步到目标表后登记为新版本
+//! - 破坏性变更在 `break_on_incompatible` 为 true 时让任务失败；否则暂停该表的结构同步，
+//!   不登记新版本，直到源表结构恢复兼容
+//!
+//! 检测到的变更写入 `SchemaChangeLog`，体现在运行结果和任务状态中。
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use relus_common::constant::cdc::BINLOG_SOURCE_TYPE;
+use relus_common::constant::schema::DEFAULT_SCHEMA_CACHE_DIR;
+use relus_common::data_source_config::DataSourceConfig;
+use relus_common::job_config::SchemaDiscoveryConfig;
+use relus_common::JobConfig;
+use relus_connector_rdbms::schema::{
+    evolution_ddl, CompatibilityResult, EvolutionChecker, MetadataDiscoverer, RdbmsDiscoverer,
+    SchemaCache, SchemaChange, TableSchema,
+};
+use relus_connector_rdbms::util::{get_pool_for, get_pool_from_output};
+use relus_reader::binlog_reader::BinlogConfig;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// 运行期间检测到的结构变更描述，按发生顺序排列
+pub type SchemaChangeLog = Arc<Mutex<Vec<String>>>;
+
+/// SchemaCache 数据库文件名（位于 cache_dir 下）
+const CACHE_FILE: &str = "schemas.redb";
+
+/// 同一进程内按目录共享 SchemaCache，redb 文件不能被重复打开
+static CACHES: OnceLock<Mutex<HashMap<PathBuf, Arc<SchemaCache>>>> = OnceLock::new();
+
+fn open_cache(config: &SchemaDiscoveryConfig) -> Result<Arc<SchemaCache>> {
+    let dir = PathBuf::from(
+        config
+            .cache_dir
+            .as_deref()
+            .unwrap_or(DEFAULT_SCHEMA_CACHE_DIR),
+    );
+    let mut caches = CACHES.get_or_init(|| Mutex::new(HashMap::new())).lock();
+    if let Some(cache) = caches.get(&dir) {
+        return Ok(Arc::clone(cache));
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    let cache = Arc::new(SchemaCache::new(dir.join(CACHE_FILE))?);
+    caches.insert(dir, Arc::clone(&cache));
+    Ok(cache)
+}
+
+/// 一个任务内各表的结构演进检测
+pub struct SchemaEvolution {
+    cache: Arc<SchemaCache>,
+    checker: EvolutionChecker,
+    break_on_incompatible: bool,
+    check_interval: Duration,
+    /// (表名, 单表任务配置)
+    tables: Vec<(String, Arc<JobConfig>)>,
+    log: SchemaChangeLog,
+    /// 已暂停结构同步的表及原因，避免定期检测重复记录
+    paused: HashMap<String, String>,
+}
+
+impl SchemaEvolution {
+    pub fn new(
+        config: &SchemaDiscoveryConfig,
+        tables: Vec<(String, Arc<JobConfig>)>,
+        log: SchemaChangeLog,
+    ) -> Result<Self> {
+        Ok(Self {
+            cache: open_cache(config)?,
+            checker: EvolutionChecker::new(config.break_on_incompatible),
+            break_on_incompatible: config.break_on_incompatible,
+            check_interval: Duration::from_secs(config.check_interval_secs.max(1)),
+            tables,
+            log,
+            paused: HashMap::new(),
+        })
+    }
+
+    /// 检测所有表，返回需要中断任务的破坏性变更描述
+    pub async fn check_all(&mut self) -> Result<Option<String>> {
+        for i in 0..self.tables.len() {
+            let (name, job) = self.tables[i].clone();
+            if let Some(reason) = self.check_table(&name, &job).await? {
+                return Ok(Some(reason));
+            }
+        }
+        Ok(None)
+    }
+
+    /// CDC 任务中定期检测，破坏性变更需要中断时取消 `run_token` 并返回原因
+    ///
+    /// 检测本身出错（如源库暂时不可用）只记录告警，下一个周期重试。
+    pub fn spawn_watcher(mut self, run_token: CancellationToken) -> JoinHandle<Option<String>> {
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = run_token.cancelled() => return None,
+                    _ = tokio::time::sleep(self.check_interval) => {}
+                }
+                match self.check_all().await {
+                    Ok(Some(reason)) => {
+                        warn!("[SchemaEvolution] {}，停止任务", reason);
+                        run_token.cancel();
+                        return Some(reason);
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("[SchemaEvolution] 源表结构检测失败: {:?}", e),
+                }
+            }
+        })
+    }
+
+    async fn check_table(&mut self, name: &str, job: &JobConfig) -> Result<Option<String>> {
+        let Some(mut schema) = discover_source(&job.source).await? else {
+            return Ok(None);
+        };
+        // 同一源表可能同步到多个目标，版本按 源表 -> 目标表 分别登记
+        let key = cache_key(&schema.table_name, job);
+        schema.table_name = key.clone();
+
+        let Some(old) = self.cache.get_latest(&key)? else {
+            let version = self.cache.register(&schema)?;
+            info!("[SchemaEvolution] 表 {} 登记初始结构 v{}", name, version);
+            return Ok(None);
+        };
+
+        let changes = self.checker.detect_changes(&old, &schema);
+        if changes.is_empty() {
+            self.paused.remove(name);
+            return Ok(None);
+        }
+
+        if let CompatibilityResult::Breaking { reason, .. } =
+            self.checker.check(Some(&old), &schema)
+        {
+            let message = format!("表 {} 源表结构发生不兼容变更: {}", name, reason);
+            if self.break_on_incompatible {
+                self.record(message.clone());
+                return Ok(Some(message));
+            }
+            if self.paused.get(name) != Some(&message) {
+                warn!("[SchemaEvolution] {}，暂停该表的结构同步", message);
+                self.record(format!("{}（已暂停结构同步）", message));
+                self.paused.insert(name.to_string(), message);
+            }
+            return Ok(None);
+        }
+        self.paused.remove(name);
+
+        let applied = apply_to_target(job, &old, &schema, &changes).await?;
+        let version = self.cache.register(&schema)?;
+        let summary: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        self.record(format!(
+            "表 {} 结构 v{}: {}（目标表执行 {} 条 DDL）",
+            name,
+            version,
+            summary.join(", "),
+            applied
+        ));
+        Ok(None)
+    }
+
+    fn record(&self, message: String) {
+        info!("[SchemaEvolution] {}", message);
+        self.log.lock().push(message);
+    }
+}
+
+/// 发现源表结构；source 不是数据库或无法确定单张源表时返回 None
+///
+/// binlog source 优先使用连接中的 table，否则使用不含通配符的 `database.table` 过滤条件。
+async fn discover_source(source: &DataSourceConfig) -> Result<Option<TableSchema>> {
+    let is_binlog = source.source_type == BINLOG_SOURCE_TYPE;
+    if source.source_type != "database" && !is_binlog {
+        return Ok(None);
+    }
+
+    if let Some(table) = source.table_name() {
+        let pool = get_pool_for(source).await?;
+        return Ok(Some(RdbmsDiscoverer::new(pool, table).discover().await?));
+    }
+
+    let filter = if is_binlog {
+        BinlogConfig::from_data_source_config(source)?.table_filter
+    } else {
+        None
+    };
+    let Some((database, table)) = filter
+        .as_deref()
+        .filter(|f| !f.contains('*') && !f.contains('?'))
+        .and_then(|f| f.split_once('.'))
+    else {
+        warn!(
+            "[SchemaEvolution] source {} 没有确定的源表，跳过结构检测",
+            source.name
+        );
+        return Ok(None);
+    };
+
+    let pool = get_pool_for(source).await?;
+    let discoverer = RdbmsDiscoverer::new(pool, String::new());
+    Ok(Some(
+        discoverer.discover_mysql_table(database, table).await?,
+    ))
+}
+
+fn cache_key(source_table: &str, job: &JobConfig) -> String {
+    match job.target.table_name() {
+        Some(target) => format!("{}->{}", source_table, target),
+        None => source_table.to_string(),
+    }
+}
+
+/// 把兼容变更同步到数据库目标表，返回执行的 DDL 条数
+///
+/// 目标表已经存在的列不再重复添加（上次同步中途失败时会出现）。
+async fn apply_to_target(
+    job: &JobConfig,
+    old: &TableSchema,
+    new: &TableSchema,
+    changes: &[SchemaChange],
+) -> Result<usize> {
+    if job.target.source_type != "database" {
+        return Ok(0);
+    }
+    let Some(table) = job.target.table_name() else {
+        return Ok(0);
+    };
+
+    let pool = get_pool_from_output(job).await?;
+    let existing = RdbmsDiscoverer::new(Arc::clone(&pool), table.clone())
+        .discover()
+        .await?;
+    let pending: Vec<SchemaChange> = changes
+        .iter()
+        .filter(|change| match change {
+            SchemaChange::ColumnAdded(col) => existing.get_column(&col.name).is_none(),
+            _ => true,
+        })
+        .cloned()
+        .collect();
+
+    let statements = evolution_ddl(old, new, &pending, &job.column_mapping, pool.kind(), &table);
+    let executor = pool.executor();
+    for sql in &statements {
+        info!("[SchemaEvolution] 目标表 {} 执行: {}", table, sql);
+        executor.execute(sql).await?;
+    }
+    Ok(statements.len())
+}
@@ -207,6 +207,37 @@ impl SchedulerControl for CoreSchedulerControl {
         let handle = self.handle.clone();
         Box::pin(async move { scheduler_result(handle.cancel_task(job_id).await) })
     }
+
+    fn query_checkpoint(&self, job_id: String) -> ApiFuture<ApiHandlerResult> {
+        let handle = self.handle.clone();
+        Box::pin(async move { scheduler_result(handle.query_checkpoint(job_id).await) })
+    }
+
+    fn reset_checkpoint(&self, job_id: String) -> ApiFuture<ApiHandlerResult> {
+        let handle = self.handle.clone();
+        Box::pin(async move { scheduler_result(handle.reset_checkpoint(job_id).await) })
+    }
+
+    fn set_checkpoint(
+        &self,
+        job_id: String,
+        position: serde_json::Value,
+    ) -> ApiFuture<ApiHandlerResult> {
+        let handle = self.handle.clone();
+        Box::pin(async move {
+            match serde_json::from_value::<relus_reader::BinlogPosition>(position) {
+                Ok(position) => scheduler_result(handle.set_checkpoint(job_id, position).await),
+                Err(e) => scheduler_failure(SchedulerError::InvalidConfig {
+                    message: format!("checkpoint 解析失败: {}", e),
+                }),
+            }
+        })
+    }
+
+    fn resume_run(&self, run_id: String) -> ApiFuture<ApiHandlerResult> {
+        let handle = self.handle.clone();
+        Box::pin(async move { scheduler_result(handle.resume_run(run_id).await) })
+    }
 }
 
 fn api_state() -> SharedState {
@@ -251,6 +282,7 @@ fn scheduler_failure(error: SchedulerError) -> ApiHandlerResult {
     let status = match error {
         SchedulerError::JobNotFound { .. } => StatusCode::NOT_FOUND,
         SchedulerError::JobAlreadyExists { .. }
+        | SchedulerError::JobRunning { .. }
         | SchedulerError::InvalidConfig { .. }
         | SchedulerError::MaxConcurrencyReached { .. } => StatusCode::BAD_REQUEST,
         SchedulerError::SchedulerUnavailable => StatusCode::SERVICE_UNAVAILABLE,
@@ -303,6 +335,14 @@ fn scheduler_server_addr(host: Option<String>, port: Option<u16>) -> (String, u1
     (host, port)
 }
 
+/// checkpoint 与分片记录的存储文件，与系统配置位于同一目录
+pub fn checkpoint_store_path() -> PathBuf {
+    relus_common::app_config::path::default_config_path("app_trans")
+        .parent()
+        .map(|p| p.join("checkpoints.redb"))
+        .unwrap_or_else(|| PathBuf::from("checkpoints.redb"))
+}
+
 /// start TaskScheduler
 pub async fn run_scheduler(
     configs: Vec<(String, relus_common::job_config::JobConfig)>,
@@ -310,12 +350,7 @@ pub async fn run_scheduler(
     host: Option<String>,
     port: Option<u16>,
 ) -> Result<()> {
-    let checkpoint_dir = relus_common::app_config::path::default_config_path("app_trans")
-        .parent()
-        .map(|p| p.join("checkpoints.redb"))
-        .unwrap_or_else(|| PathBuf::from("checkpoints.redb"));
-
-    let mut scheduler = TaskScheduler::new(checkpoint_dir)?;
+    let mut scheduler = TaskScheduler::new(checkpoint_store_path())?;
     let scheduler_handle = scheduler.control_handle();
 
     if configs.is_empty() {
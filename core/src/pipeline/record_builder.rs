@@ -75,6 +75,8 @@ fn extract_dsl_rule(value: &str) -> &str {
 pub struct RecordBuilder {
     column_mapping: BTreeMap<String, String>,
     column_types: Option<BTreeMap<String, String>>,
+    /// Reader 探测到的源列类型，未显式配置 column_types 时作为类型提示
+    source_column_types: BTreeMap<String, String>,
     registry: Arc<TypeConverterRegistry>,
     source_type: SourceType,
     table_name: Option<String>,
@@ -91,6 +93,7 @@ impl RecordBuilder {
         Ok(Self {
             column_mapping,
             column_types,
+            source_column_types: BTreeMap::new(),
             registry: Arc::new(TypeConverterRegistry::new()),
             source_type: SourceType::Other("unknown".to_string()),
             table_name: None,
@@ -108,6 +111,7 @@ impl RecordBuilder {
         Ok(Self {
             column_mapping,
             column_types,
+            source_column_types: BTreeMap::new(),
             registry,
             source_type: SourceType::Other("unknown".to_string()),
             table_name: None,
@@ -146,10 +150,27 @@ impl RecordBuilder {
         self
     }
 
+    /// 设置源列类型（源列名 → 逻辑类型）
+    pub fn with_source_column_types(
+        mut self,
+        source_column_types: BTreeMap<String, String>,
+    ) -> Self {
+        self.source_column_types = source_column_types;
+        self
+    }
+
+    /// 类型提示：优先使用显式配置，其次按纯路径映射的源列类型推断
     fn get_type_hint(&self, column: &str) -> Option<&str> {
-        self.column_types
-            .as_ref()
-            .and_then(|m| m.get(column))
+        if let Some(hint) = self.column_types.as_ref().and_then(|m| m.get(column)) {
+            return Some(hint.as_str());
+        }
+
+        let source = self.column_mapping.get(column)?;
+        if !matches!(classify_mapping(source), MappingStrategy::Path) {
+            return None;
+        }
+        self.source_column_types
+            .get(source.as_str())
             .map(|s| s.as_str())
     }
 
@@ -410,6 +431,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_source_column_types_as_hint() {
+        let mut mapping = BTreeMap::new();
+        mapping.insert("id".to_string(), "user_id".to_string());
+        mapping.insert("amount".to_string(), "amount".to_string());
+        mapping.insert("label".to_string(), "= upper(source.name)".to_string());
+
+        let mut explicit = BTreeMap::new();
+        explicit.insert("amount".to_string(), "float".to_string());
+
+        let mut source_types = BTreeMap::new();
+        source_types.insert("user_id".to_string(), "int".to_string());
+        source_types.insert("amount".to_string(), "decimal".to_string());
+        source_types.insert("name".to_string(), "int".to_string());
+
+        let builder = RecordBuilder::new(mapping, Some(explicit))
+            .unwrap_or_else(|e| panic!("record builder should be valid: {}", e))
+            .with_source_column_types(source_types);
+
+        assert_eq!(builder.get_type_hint("id"), Some("int"));
+        assert_eq!(builder.get_type_hint("amount"), Some("float"));
+        assert_eq!(builder.get_type_hint("label"), None);
+
+        let record = builder
+            .build(&json!({"user_id": 7, "amount": 1.5, "name": "bob"}))
+            .unwrap();
+        assert_eq!(record.get_value("id").and_then(|v| v.as_int()), Some(7));
+    }
+
     #[test]
     fn invalid_dsl_mapping_returns_error() {
         let mut mapping = BTreeMap::new();
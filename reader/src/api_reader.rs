@@ -42,6 +42,7 @@ impl DataReaderJob for ApiReader {
         Ok(SplitReaderResult {
             total_records: 0,
             stream_mode: StreamMode::Batch,
+            column_types: Default::default(),
             tasks: vec![ReadTask {
                 task_id: 0,
                 conn: JsonValue::Null,
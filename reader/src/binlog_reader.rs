@@ -6,21 +6,33 @@
 //!
 //! 使用 `mysql_cdc` crate 同步消费 Binlog 事件，
 //! 通过 `tokio::task::spawn_blocking` + `mpsc` 桥接到 async stream。
+//!
+//! 每条变更记录都会带上所属事务开始前的已提交位点（`_binlog_file`、
+//! `_binlog_pos`、`_gtid`），core 在批次写入成功后据此保存 checkpoint，
+//! 重启时通过 `restore_checkpoint` 从该位点继续消费（at-least-once）。
+//!
+//! 行事件只携带按序号排列的列值，列名、ENUM/SET 标签和符号位通过
+//! `RdbmsDiscoverer` 从 information_schema 查询并按表缓存，DDL 后失效重查。
 
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 use crate::{DataReaderJob, DataReaderTask, JsonStream, ReadTask, SplitReaderResult, StreamMode};
 use mysql_cdc::events::binlog_event::BinlogEvent;
 use mysql_cdc::events::row_events::mysql_value::MySqlValue;
 use mysql_cdc::events::row_events::row_data::RowData;
+use relus_common::constant::cdc;
 use relus_common::data_source_config::DataSourceConfig;
 use relus_common::job_config::JobConfig;
+use relus_connector_rdbms::pool::RdbmsPool;
+use relus_connector_rdbms::schema::{ColumnInfo, RdbmsDiscoverer, TableSchema};
+use relus_connector_rdbms::util::get_pool_for;
 
 // ==========================================
 // BinlogConfig
@@ -93,6 +105,188 @@ impl BinlogConfig {
     }
 }
 
+// ==========================================
+// BinlogPosition
+// ==========================================
+
+/// 记录中携带位点的元数据字段，writer 不会把它们写入目标表
+pub use relus_common::constant::cdc::{BINLOG_FILE_FIELD, BINLOG_GTID_FIELD, BINLOG_POS_FIELD};
+
+/// Binlog 消费位点
+///
+/// 表示一个事务边界：从该位点重新消费不会丢失之后的任何变更。
+/// 普通模式使用 file/position，GTID 模式使用已执行的 GTID 集合。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BinlogPosition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binlog_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binlog_position: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gtid: Option<String>,
+}
+
+impl BinlogPosition {
+    pub fn from_file(file: impl Into<String>, position: u32) -> Self {
+        Self {
+            binlog_file: Some(file.into()),
+            binlog_position: Some(position),
+            gtid: None,
+        }
+    }
+
+    pub fn from_gtid(gtid: impl Into<String>) -> Self {
+        Self {
+            binlog_file: None,
+            binlog_position: None,
+            gtid: Some(gtid.into()),
+        }
+    }
+
+    /// 校验位点可用于启动复制：需要 GTID 或完整的 file + position
+    pub fn validate(&self) -> Result<()> {
+        let has_gtid = self.gtid.as_deref().is_some_and(|g| !g.trim().is_empty());
+        let has_file = self
+            .binlog_file
+            .as_deref()
+            .is_some_and(|f| !f.trim().is_empty());
+        if has_gtid || (has_file && self.binlog_position.is_some()) {
+            Ok(())
+        } else {
+            bail!("binlog 位点需要指定 gtid 或 binlog_file + binlog_position")
+        }
+    }
+
+    /// 从变更记录的元数据字段中提取位点
+    pub fn from_record(record: &JsonValue) -> Option<Self> {
+        let position = Self {
+            binlog_file: record
+                .get(BINLOG_FILE_FIELD)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            binlog_position: record
+                .get(BINLOG_POS_FIELD)
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u32::try_from(v).ok()),
+            gtid: record
+                .get(BINLOG_GTID_FIELD)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        };
+        position.validate().ok().map(|()| position)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let position: Self = serde_json::from_slice(bytes)
+            .map_err(|e| anyhow::anyhow!("binlog checkpoint 解析失败: {}", e))?;
+        position.validate()?;
+        Ok(position)
+    }
+}
+
+impl std::fmt::Display for BinlogPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.binlog_file, self.binlog_position) {
+            (Some(file), Some(pos)) => write!(f, "{}:{}", file, pos)?,
+            _ => write!(f, "-")?,
+        }
+        if let Some(gtid) = &self.gtid {
+            write!(f, " (gtid={})", gtid)?;
+        }
+        Ok(())
+    }
+}
+
+/// 把位点元数据写入变更记录
+pub fn attach_binlog_position(record: &mut JsonValue, position: &BinlogPosition) {
+    let Some(obj) = record.as_object_mut() else {
+        return;
+    };
+    if let Some(file) = &position.binlog_file {
+        obj.insert(
+            BINLOG_FILE_FIELD.to_string(),
+            JsonValue::String(file.clone()),
+        );
+    }
+    if let Some(pos) = position.binlog_position {
+        obj.insert(BINLOG_POS_FIELD.to_string(), JsonValue::Number(pos.into()));
+    }
+    if let Some(gtid) = &position.gtid {
+        obj.insert(
+            BINLOG_GTID_FIELD.to_string(),
+            JsonValue::String(gtid.clone()),
+        );
+    }
+}
+
+// ==========================================
+// Mix 模式：快照 → Binlog 交接
+// ==========================================
+
+pub use relus_common::constant::cdc::BINLOG_SOURCE_TYPE;
+
+/// 读取 MySQL 当前的 binlog 位点（Mix 模式快照前调用）
+///
+/// MySQL 8.4 移除了 `SHOW MASTER STATUS`，失败时回退到 `SHOW BINARY LOG STATUS`。
+pub async fn capture_binlog_position(source: &DataSourceConfig) -> Result<BinlogPosition> {
+    use sqlx::Row;
+
+    let pool = get_pool_for(source).await?;
+    let RdbmsPool::Mysql(pool) = pool.as_ref() else {
+        bail!("binlog 位点只能从 MySQL 源读取");
+    };
+
+    let row = match sqlx::query("SHOW MASTER STATUS").fetch_optional(pool).await {
+        Ok(row) => row,
+        Err(_) => {
+            sqlx::query("SHOW BINARY LOG STATUS")
+                .fetch_optional(pool)
+                .await?
+        }
+    };
+    let row =
+        row.ok_or_else(|| anyhow::anyhow!("无法读取 binlog 位点，请确认 MySQL 已开启 log_bin"))?;
+
+    let file = row.try_get::<String, _>("File").or_else(|_| {
+        row.try_get::<Vec<u8>, _>("File")
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+    })?;
+    let position: u64 = row.try_get("Position")?;
+    let position = u32::try_from(position)
+        .map_err(|_| anyhow::anyhow!("binlog position 超出范围: {}", position))?;
+
+    Ok(BinlogPosition::from_file(file, position))
+}
+
+/// 基于快照 source 派生 binlog source
+///
+/// 两者共用同一个 connection；未配置 `table_filter` 时按 `database.table`
+/// 过滤，只消费快照对应表的变更。
+pub fn binlog_source_for_snapshot(source: &DataSourceConfig) -> DataSourceConfig {
+    let mut binlog_source = source.clone();
+    binlog_source.source_type = BINLOG_SOURCE_TYPE.to_string();
+
+    let conn = match binlog_source.config.get_mut("connections") {
+        Some(JsonValue::Array(conns)) => conns.first_mut(),
+        _ => binlog_source.config.get_mut("connection"),
+    };
+    if let Some(JsonValue::Object(conn)) = conn {
+        if !conn.contains_key("table_filter") {
+            let database = conn.get("database").and_then(|v| v.as_str());
+            let table = conn.get("table").and_then(|v| v.as_str());
+            if let (Some(database), Some(table)) = (database, table) {
+                let filter = format_table(database, table);
+                conn.insert("table_filter".to_string(), JsonValue::String(filter));
+            }
+        }
+    }
+    binlog_source
+}
+
 // ==========================================
 // CdcOp & 事件转换
 // ==========================================
@@ -118,7 +312,7 @@ impl CdcOp {
 /// 将变更事件转换为 JsonValue
 ///
 /// 输出格式包含元数据字段（`_op`, `_table`, `_timestamp`）和业务列值。
-/// 列名来自 TableMapEvent 的 column metadata（当可用时用 col_0, col_1...）。
+/// 列名来自表结构缓存（表结构不可用时用 col_0, col_1...）。
 pub fn cdc_event_to_json(
     op: CdcOp,
     table: &str,
@@ -127,12 +321,15 @@ pub fn cdc_event_to_json(
 ) -> JsonValue {
     let mut obj = serde_json::Map::new();
     obj.insert(
-        "_op".to_string(),
+        cdc::OP_FIELD.to_string(),
         JsonValue::String(op.as_str().to_string()),
     );
-    obj.insert("_table".to_string(), JsonValue::String(table.to_string()));
     obj.insert(
-        "_timestamp".to_string(),
+        cdc::TABLE_FIELD.to_string(),
+        JsonValue::String(table.to_string()),
+    );
+    obj.insert(
+        cdc::TIMESTAMP_FIELD.to_string(),
         JsonValue::Number(timestamp.into()),
     );
     for (col, val) in columns {
@@ -141,8 +338,21 @@ pub fn cdc_event_to_json(
     JsonValue::Object(obj)
 }
 
-fn mysql_value_to_json(val: &MySqlValue) -> JsonValue {
+/// 将 binlog 列值转换为 JsonValue
+///
+/// binlog 中整数按无符号存储，`column` 标记为有符号时还原负数；
+/// ENUM/SET 按定义的标签输出。没有表结构时保持原始数值。
+fn mysql_value_to_json(val: &MySqlValue, column: Option<&ColumnInfo>) -> JsonValue {
+    let signed = column.is_some_and(|c| !c.unsigned);
+    let labels = column.map(|c| c.enum_values.as_slice()).unwrap_or_default();
+
     match val {
+        MySqlValue::TinyInt(n) if signed => JsonValue::Number((*n as i8).into()),
+        MySqlValue::SmallInt(n) if signed => JsonValue::Number((*n as i16).into()),
+        // MEDIUMINT 为 24 位，左移后算术右移完成符号扩展
+        MySqlValue::MediumInt(n) if signed => JsonValue::Number((((*n << 8) as i32) >> 8).into()),
+        MySqlValue::Int(n) if signed => JsonValue::Number((*n as i32).into()),
+        MySqlValue::BigInt(n) if column.is_some() && !signed => JsonValue::Number((*n).into()),
         MySqlValue::TinyInt(n) => JsonValue::Number((*n).into()),
         MySqlValue::SmallInt(n) => JsonValue::Number((*n).into()),
         MySqlValue::MediumInt(n) => JsonValue::Number((*n).into()),
@@ -188,23 +398,42 @@ fn mysql_value_to_json(val: &MySqlValue) -> JsonValue {
                 );
             JsonValue::Number((val as i64).into())
         }
+        // ENUM 序号从 1 开始，0 表示非法值（MySQL 中显示为空串）
+        MySqlValue::Enum(n) if !labels.is_empty() => match *n {
+            0 => JsonValue::String(String::new()),
+            n => labels
+                .get(n as usize - 1)
+                .map(|label| JsonValue::String(label.clone()))
+                .unwrap_or(JsonValue::Number(n.into())),
+        },
+        MySqlValue::Set(bits) if !labels.is_empty() => {
+            let members: Vec<&str> = labels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i < 64 && bits & (1u64 << i) != 0)
+                .map(|(_, label)| label.as_str())
+                .collect();
+            JsonValue::String(members.join(","))
+        }
         MySqlValue::Enum(n) => JsonValue::Number((*n as i64).into()),
         MySqlValue::Set(n) => JsonValue::Number((*n as i64).into()),
     }
 }
 
-fn row_data_to_columns(row: &RowData, column_names: &[String]) -> Vec<(String, JsonValue)> {
+/// 按序号把列值对应到列名；列数与表结构不一致时不按位置套用列名，退化为 `col_N`
+fn row_data_to_columns(row: &RowData, schema: Option<&TableSchema>) -> Vec<(String, JsonValue)> {
+    let schema = schema.filter(|s| s.columns.len() == row.cells.len());
     row.cells
         .iter()
         .enumerate()
         .map(|(i, cell)| {
-            let name = column_names
-                .get(i)
-                .cloned()
+            let column = schema.and_then(|s| s.columns.get(i));
+            let name = column
+                .map(|c| c.name.clone())
                 .unwrap_or_else(|| format!("col_{}", i));
             let val = cell
                 .as_ref()
-                .map(mysql_value_to_json)
+                .map(|v| mysql_value_to_json(v, column))
                 .unwrap_or(JsonValue::Null);
             (name, val)
         })
@@ -216,6 +445,166 @@ fn row_data_to_columns(row: &RowData, column_names: &[String]) -> Vec<(String, J
 struct TableMapInfo {
     database_name: String,
     table_name: String,
+    /// 列结构，未匹配 table_filter 或查询失败时为 None
+    schema: Option<Arc<TableSchema>>,
+}
+
+/// Binlog 表结构缓存
+///
+/// key 为 `database.table`，未命中时通过 `RdbmsDiscoverer` 查询 information_schema。
+/// 查询失败的表也会缓存（值为 None），避免每个事务都重复查询；DDL 后整体失效。
+///
+/// 缓存的列数与 TableMapEvent 不一致（重放 DDL 之前的 binlog，或 ADD/DROP COLUMN 之后）时
+/// 重新查询一次，仍不一致则该表的行使用 `col_N`，不按位置套用错误的列名。
+struct TableMetadataCache {
+    source: DataSourceConfig,
+    runtime: tokio::runtime::Handle,
+    discoverer: Option<RdbmsDiscoverer>,
+    tables: HashMap<String, Option<Arc<TableSchema>>>,
+    /// 重新查询后仍与事件列数不一致的表及该列数，避免每个事务都重复查询
+    mismatched: HashMap<String, usize>,
+}
+
+impl TableMetadataCache {
+    fn new(source: DataSourceConfig, runtime: tokio::runtime::Handle) -> Self {
+        Self {
+            source,
+            runtime,
+            discoverer: None,
+            tables: HashMap::new(),
+            mismatched: HashMap::new(),
+        }
+    }
+
+    /// 获取与事件列数一致的表结构（在 binlog 阻塞线程中调用）
+    fn get(
+        &mut self,
+        database: &str,
+        table: &str,
+        column_count: usize,
+    ) -> Option<Arc<TableSchema>> {
+        let key = format_table(database, table);
+        let schema = match self.tables.get(&key) {
+            Some(cached) => cached.clone(),
+            None => self.load(&key, database, table),
+        };
+        match schema {
+            Some(schema) if schema.columns.len() != column_count => {
+                self.reload_mismatched(key, database, table, column_count)
+            }
+            schema => schema,
+        }
+    }
+
+    fn reload_mismatched(
+        &mut self,
+        key: String,
+        database: &str,
+        table: &str,
+        column_count: usize,
+    ) -> Option<Arc<TableSchema>> {
+        if self.mismatched.get(&key) == Some(&column_count) {
+            return None;
+        }
+        match self.load(&key, database, table) {
+            Some(schema) if schema.columns.len() == column_count => {
+                self.mismatched.remove(&key);
+                Some(schema)
+            }
+            schema => {
+                tracing::warn!(
+                    "[BinlogReader] {} 的 binlog 事件有 {} 列，当前表结构为 {} 列，列名将使用 col_N",
+                    key,
+                    column_count,
+                    schema.map_or(0, |s| s.columns.len())
+                );
+                self.mismatched.insert(key, column_count);
+                None
+            }
+        }
+    }
+
+    /// 查询表结构并写入缓存
+    fn load(&mut self, key: &str, database: &str, table: &str) -> Option<Arc<TableSchema>> {
+        let schema = match self.discover(database, table) {
+            Ok(schema) => {
+                tracing::info!(
+                    "[BinlogReader] loaded schema of {} ({} columns)",
+                    key,
+                    schema.columns.len()
+                );
+                Some(Arc::new(schema))
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "[BinlogReader] 查询 {} 表结构失败，列名将使用 col_N: {:?}",
+                    key,
+                    e
+                );
+                None
+            }
+        };
+        self.tables.insert(key.to_string(), schema.clone());
+        schema
+    }
+
+    fn discover(&mut self, database: &str, table: &str) -> Result<TableSchema> {
+        if self.discoverer.is_none() {
+            let pool = self.runtime.block_on(get_pool_for(&self.source))?;
+            self.discoverer = Some(RdbmsDiscoverer::new(pool, String::new()));
+        }
+        let Some(discoverer) = self.discoverer.as_ref() else {
+            bail!("RdbmsDiscoverer 未初始化");
+        };
+        self.runtime
+            .block_on(discoverer.discover_mysql_table(database, table))
+    }
+
+    fn invalidate(&mut self) {
+        if !self.tables.is_empty() {
+            tracing::info!("[BinlogReader] DDL detected, table schema cache cleared");
+            self.tables.clear();
+        }
+        self.mismatched.clear();
+    }
+}
+
+/// 判断 QueryEvent 是否为会改变表结构的 DDL
+///
+/// 跳过开头的空白与注释（mysqldump、迁移工具常带 `/* ... */` 前缀）；
+/// 版本注释 `/*!50001 CREATE ... */` 中的语句会被 MySQL 执行，按其内容判断。
+fn is_ddl_statement(sql: &str) -> bool {
+    let mut rest = sql;
+    loop {
+        rest = rest.trim_start();
+        if let Some(body) = rest.strip_prefix("/*") {
+            let end = body.find("*/").unwrap_or(body.len());
+            if let Some(versioned) = body[..end].strip_prefix('!') {
+                if starts_with_ddl_keyword(
+                    versioned.trim_start_matches(|c: char| c.is_ascii_digit()),
+                ) {
+                    return true;
+                }
+            }
+            rest = body.get(end + 2..).unwrap_or_default();
+        } else if rest.starts_with("--") || rest.starts_with('#') {
+            rest = rest.find('\n').map_or("", |i| &rest[i + 1..]);
+        } else {
+            return starts_with_ddl_keyword(rest);
+        }
+    }
+}
+
+fn starts_with_ddl_keyword(sql: &str) -> bool {
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    matches!(
+        keyword.as_str(),
+        "ALTER" | "CREATE" | "DROP" | "RENAME" | "TRUNCATE"
+    )
 }
 
 // ==========================================
@@ -231,19 +620,34 @@ pub struct BinlogReader {
 pub struct BinlogJob {
     _original_config: Arc<JobConfig>,
     binlog_config: BinlogConfig,
-    column_names: Vec<String>,
     shutdown: Arc<AtomicBool>,
+    /// checkpoint 恢复的起始位点，优先于配置中的 binlog_file/binlog_position/gtid
+    resume_from: RwLock<Option<BinlogPosition>>,
 }
 
 impl BinlogJob {
     pub fn new(_original_config: Arc<JobConfig>, binlog_config: BinlogConfig) -> Self {
-        let column_names: Vec<String> = _original_config.column_mapping.keys().cloned().collect();
         Self {
             _original_config,
             binlog_config,
-            column_names,
             shutdown: Arc::new(AtomicBool::new(false)),
+            resume_from: RwLock::new(None),
+        }
+    }
+
+    /// 合并 checkpoint 后的实际启动配置
+    fn effective_config(&self) -> Result<BinlogConfig> {
+        let mut config = self.binlog_config.clone();
+        let resume_from = self
+            .resume_from
+            .read()
+            .map_err(|e| anyhow::anyhow!("binlog checkpoint lock is poisoned: {}", e))?;
+        if let Some(position) = resume_from.as_ref() {
+            config.binlog_file = position.binlog_file.clone();
+            config.binlog_position = position.binlog_position;
+            config.gtid = position.gtid.clone();
         }
+        Ok(config)
     }
 }
 
@@ -262,6 +666,7 @@ impl DataReaderJob for BinlogReader {
         Ok(SplitReaderResult {
             total_records: 0, // CDC 流式消费，无法预知总量��触发 spinner 模式
             stream_mode: StreamMode::Streaming,
+            column_types: Default::default(),
             tasks: vec![ReadTask {
                 task_id: 0,
                 conn: JsonValue::Null,
@@ -287,14 +692,31 @@ impl DataReaderJob for BinlogReader {
 #[async_trait::async_trait]
 impl DataReaderTask for BinlogReader {
     async fn read_data(&self, _task: &ReadTask) -> Result<JsonStream> {
-        let config = self.job.binlog_config.clone();
-        let column_names = self.job.column_names.clone();
+        let mut config = self.job.effective_config()?;
+        // 未指定起始位点时先读取当前位点，首个事务的记录也能携带有效位点
+        if start_position(&config).validate().is_err() {
+            match capture_binlog_position(&self.job._original_config.source).await {
+                Ok(position) => {
+                    tracing::info!("[BinlogReader] start from current position {}", position);
+                    config.binlog_file = position.binlog_file;
+                    config.binlog_position = position.binlog_position;
+                }
+                Err(e) => tracing::warn!(
+                    "[BinlogReader] 读取当前 binlog 位点失败，首个事务前的记录不保存 checkpoint: {}",
+                    e
+                ),
+            }
+        }
+        let metadata = TableMetadataCache::new(
+            self.job._original_config.source.clone(),
+            tokio::runtime::Handle::current(),
+        );
         let shutdown = Arc::clone(&self.job.shutdown);
 
         let (tx, rx) = tokio::sync::mpsc::channel::<Result<JsonValue>>(1024);
 
         tokio::task::spawn_blocking(move || {
-            if let Err(e) = run_binlog_stream(config, column_names, tx, shutdown) {
+            if let Err(e) = run_binlog_stream(config, metadata, tx, shutdown) {
                 tracing::error!("Binlog stream ended with error: {:?}", e);
             }
         });
@@ -310,6 +732,22 @@ impl DataReaderTask for BinlogReader {
         self.job.shutdown.store(true, Ordering::Relaxed);
         tracing::info!("[BinlogReader] SHUTDOWN");
     }
+
+    fn checkpoint_of(&self, record: &JsonValue) -> Option<Vec<u8>> {
+        BinlogPosition::from_record(record).and_then(|position| position.to_bytes().ok())
+    }
+
+    fn restore_checkpoint(&self, checkpoint: &[u8]) -> Result<()> {
+        let position = BinlogPosition::from_bytes(checkpoint)?;
+        tracing::info!("[BinlogReader] restore checkpoint: {}", position);
+        let mut resume_from = self
+            .job
+            .resume_from
+            .write()
+            .map_err(|e| anyhow::anyhow!("binlog checkpoint lock is poisoned: {}", e))?;
+        *resume_from = Some(position);
+        Ok(())
+    }
 }
 
 // ==========================================
@@ -318,12 +756,13 @@ impl DataReaderTask for BinlogReader {
 
 fn run_binlog_stream(
     config: BinlogConfig,
-    column_names: Vec<String>,
+    mut metadata: TableMetadataCache,
     tx: tokio::sync::mpsc::Sender<Result<JsonValue>>,
     shutdown: Arc<AtomicBool>,
 ) -> Result<()> {
     use mysql_cdc::binlog_client::BinlogClient;
     use mysql_cdc::binlog_options::BinlogOptions;
+    use mysql_cdc::providers::mysql::gtid::gtid_set::GtidSet;
     use mysql_cdc::replica_options::ReplicaOptions;
     use mysql_cdc::ssl_mode::SslMode;
     use std::time::Duration;
@@ -339,8 +778,17 @@ fn run_binlog_stream(
         config.server_id
     );
 
-    let binlog_opts = match (&config.binlog_file, config.binlog_position) {
-        (Some(file), Some(pos)) => {
+    let binlog_opts = match (&config.gtid, &config.binlog_file, config.binlog_position) {
+        (Some(gtid), _, _) => {
+            tracing::info!("[BinlogReader] 从 GTID 恢复: {}", gtid);
+            let gtid_set = GtidSet::parse(gtid).map_err(|e| {
+                let msg = format!("Error: invalid gtid set '{}': {:?}", gtid, e);
+                let _ = tx.blocking_send(Err(anyhow::anyhow!("{}", msg)));
+                anyhow::anyhow!("{}", msg)
+            })?;
+            BinlogOptions::from_mysql_gtid(gtid_set)
+        }
+        (None, Some(file), Some(pos)) => {
             println!("[BinlogReader] 从指定位点恢复: {}:{}", file, pos);
             BinlogOptions::from_position(file.clone(), pos)
         }
@@ -381,6 +829,8 @@ fn run_binlog_stream(
 
     let mut table_map: HashMap<u64, TableMapInfo> = HashMap::new();
     let mut event_counter: usize = 0;
+    // 最近一个事务边界的位点，事务内的每条记录都携带该值
+    let mut committed = start_position(&config);
 
     for result in replicate_result {
         if shutdown.load(Ordering::Relaxed) {
@@ -394,30 +844,41 @@ fn run_binlog_stream(
             anyhow::anyhow!("{}", msg)
         })?;
         let timestamp = header.timestamp;
+        client.commit(&header, &event);
 
-        match event {
+        match &event {
             BinlogEvent::TableMapEvent(e) => {
+                let table_name = format_table(&e.database_name, &e.table_name);
+                let schema = if matches_table_filter(&config.table_filter, &table_name) {
+                    metadata.get(&e.database_name, &e.table_name, e.column_types.len())
+                } else {
+                    None
+                };
                 table_map.insert(
                     e.table_id,
                     TableMapInfo {
                         database_name: e.database_name.clone(),
                         table_name: e.table_name.clone(),
+                        schema,
                     },
                 );
             }
             BinlogEvent::WriteRowsEvent(e) => {
-                let table_name = table_map
-                    .get(&e.table_id)
+                let table_info = table_map.get(&e.table_id);
+                let table_name = table_info
                     .map(|t| format_table(&t.database_name, &t.table_name))
                     .unwrap_or_else(|| format!("unknown_{}", e.table_id));
+                let schema = table_info.and_then(|t| t.schema.as_deref());
 
                 if !matches_table_filter(&config.table_filter, &table_name) {
                     continue;
                 }
 
                 for row in &e.rows {
-                    let columns = row_data_to_columns(row, &column_names);
-                    let json = cdc_event_to_json(CdcOp::Insert, &table_name, timestamp, &columns);
+                    let columns = row_data_to_columns(row, schema);
+                    let mut json =
+                        cdc_event_to_json(CdcOp::Insert, &table_name, timestamp, &columns);
+                    attach_binlog_position(&mut json, &committed);
                     if tx.blocking_send(Ok(json)).is_err() {
                         return Ok(());
                     }
@@ -426,18 +887,21 @@ fn run_binlog_stream(
                 log_event_sample("INSERT", &table_name, e.rows.len(), &mut event_counter);
             }
             BinlogEvent::UpdateRowsEvent(e) => {
-                let table_name = table_map
-                    .get(&e.table_id)
+                let table_info = table_map.get(&e.table_id);
+                let table_name = table_info
                     .map(|t| format_table(&t.database_name, &t.table_name))
                     .unwrap_or_else(|| format!("unknown_{}", e.table_id));
+                let schema = table_info.and_then(|t| t.schema.as_deref());
 
                 if !matches_table_filter(&config.table_filter, &table_name) {
                     continue;
                 }
 
                 for row in &e.rows {
-                    let columns = row_data_to_columns(&row.after_update, &column_names);
-                    let json = cdc_event_to_json(CdcOp::Update, &table_name, timestamp, &columns);
+                    let columns = row_data_to_columns(&row.after_update, schema);
+                    let mut json =
+                        cdc_event_to_json(CdcOp::Update, &table_name, timestamp, &columns);
+                    attach_binlog_position(&mut json, &committed);
                     if tx.blocking_send(Ok(json)).is_err() {
                         return Ok(());
                     }
@@ -445,18 +909,21 @@ fn run_binlog_stream(
                 log_event_sample("UPDATE", &table_name, e.rows.len(), &mut event_counter);
             }
             BinlogEvent::DeleteRowsEvent(e) => {
-                let table_name = table_map
-                    .get(&e.table_id)
+                let table_info = table_map.get(&e.table_id);
+                let table_name = table_info
                     .map(|t| format_table(&t.database_name, &t.table_name))
                     .unwrap_or_else(|| format!("unknown_{}", e.table_id));
+                let schema = table_info.and_then(|t| t.schema.as_deref());
 
                 if !matches_table_filter(&config.table_filter, &table_name) {
                     continue;
                 }
 
                 for row in &e.rows {
-                    let columns = row_data_to_columns(row, &column_names);
-                    let json = cdc_event_to_json(CdcOp::Delete, &table_name, timestamp, &columns);
+                    let columns = row_data_to_columns(row, schema);
+                    let mut json =
+                        cdc_event_to_json(CdcOp::Delete, &table_name, timestamp, &columns);
+                    attach_binlog_position(&mut json, &committed);
                     if tx.blocking_send(Ok(json)).is_err() {
                         return Ok(());
                     }
@@ -466,7 +933,15 @@ fn run_binlog_stream(
             BinlogEvent::HeartbeatEvent(e) => {
                 tracing::info!("[BinlogReader] heartbeat at {}", e.binlog_filename);
             }
-            BinlogEvent::XidEvent(_) => {}
+            BinlogEvent::RotateEvent(_) | BinlogEvent::XidEvent(_) => {
+                committed = current_position(&client.options.binlog);
+            }
+            BinlogEvent::QueryEvent(e) if e.sql_statement != "BEGIN" => {
+                if is_ddl_statement(&e.sql_statement) {
+                    metadata.invalidate();
+                }
+                committed = current_position(&client.options.binlog);
+            }
             _ => {}
         }
     }
@@ -475,6 +950,24 @@ fn run_binlog_stream(
     Ok(())
 }
 
+/// 配置中的起始位点，作为第一个事务边界之前的位点
+fn start_position(config: &BinlogConfig) -> BinlogPosition {
+    match (&config.gtid, &config.binlog_file, config.binlog_position) {
+        (Some(gtid), _, _) => BinlogPosition::from_gtid(gtid.clone()),
+        (None, Some(file), Some(pos)) => BinlogPosition::from_file(file.clone(), pos),
+        _ => BinlogPosition::default(),
+    }
+}
+
+/// 读取 client 已提交的复制位点
+fn current_position(options: &mysql_cdc::binlog_options::BinlogOptions) -> BinlogPosition {
+    BinlogPosition {
+        binlog_file: Some(options.filename.clone()).filter(|f| !f.is_empty()),
+        binlog_position: Some(options.position),
+        gtid: options.gtid_set.as_ref().map(|set| set.to_string()),
+    }
+}
+
 /// 检查 table_name 是否匹配 table_filter
 fn matches_table_filter(filter: &Option<String>, table_name: &str) -> bool {
     match filter {
@@ -558,6 +1051,8 @@ mod tests {
             query_sql: None,
             writer_mode: None,
             config,
+            pre_sql: Vec::new(),
+            post_sql: Vec::new(),
         };
 
         let binlog_cfg = BinlogConfig::from_data_source_config(&ds)?;
@@ -568,6 +1063,18 @@ mod tests {
         assert_eq!(binlog_cfg.server_id, 2001);
         assert_eq!(binlog_cfg.binlog_file, Some("mysql-bin.000003".to_string()));
         assert_eq!(binlog_cfg.binlog_position, Some(194));
+        // 配置的起始位点即第一个事务前的已提交位点
+        assert_eq!(
+            start_position(&binlog_cfg),
+            BinlogPosition::from_file("mysql-bin.000003", 194)
+        );
+
+        let mut gtid_cfg = binlog_cfg.clone();
+        gtid_cfg.gtid = Some("3E11FA47-71CA-11E1-9E33-C80AA9429562:1-5".to_string());
+        assert!(start_position(&gtid_cfg).gtid.is_some());
+        gtid_cfg.gtid = None;
+        gtid_cfg.binlog_position = None;
+        assert!(start_position(&gtid_cfg).validate().is_err());
         Ok(())
     }
 
@@ -575,4 +1082,156 @@ mod tests {
     fn test_format_table() {
         assert_eq!(format_table("mydb", "users"), "mydb.users");
     }
+
+    #[test]
+    fn test_binlog_position_round_trip_through_record() -> Result<()> {
+        let position = BinlogPosition::from_file("mysql-bin.000003", 194);
+        let mut record = cdc_event_to_json(CdcOp::Insert, "test.users", 1710000000, &[]);
+        attach_binlog_position(&mut record, &position);
+
+        assert_eq!(record[BINLOG_FILE_FIELD], "mysql-bin.000003");
+        assert_eq!(record[BINLOG_POS_FIELD], 194);
+        assert_eq!(BinlogPosition::from_record(&record), Some(position.clone()));
+        assert_eq!(BinlogPosition::from_bytes(&position.to_bytes()?)?, position);
+        Ok(())
+    }
+
+    #[test]
+    fn test_binlog_position_requires_file_and_pos_or_gtid() {
+        assert!(BinlogPosition::default().validate().is_err());
+        assert!(
+            BinlogPosition::from_gtid("3E11FA47-71CA-11E1-9E33-C80AA9429562:1-5")
+                .validate()
+                .is_ok()
+        );
+
+        let record = cdc_event_to_json(CdcOp::Delete, "test.users", 1710000000, &[]);
+        assert_eq!(BinlogPosition::from_record(&record), None);
+    }
+
+    #[test]
+    fn test_binlog_source_for_snapshot_derives_table_filter() -> Result<()> {
+        let ds = relus_common::data_source_config::DataSourceConfig {
+            name: "mix_source".to_string(),
+            source_type: "database".to_string(),
+            is_table_mode: true,
+            query_sql: None,
+            writer_mode: None,
+            config: serde_json::json!({
+                "split_pk": "id",
+                "connections": [{
+                    "type": "mysql",
+                    "host": "127.0.0.1",
+                    "database": "my_db",
+                    "username": "root",
+                    "table": "user_x",
+                    "server_id": 2001
+                }]
+            }),
+            pre_sql: Vec::new(),
+            post_sql: Vec::new(),
+        };
+
+        let binlog_source = binlog_source_for_snapshot(&ds);
+        assert_eq!(binlog_source.source_type, BINLOG_SOURCE_TYPE);
+
+        let binlog_cfg = BinlogConfig::from_data_source_config(&binlog_source)?;
+        assert_eq!(binlog_cfg.table_filter, Some("my_db.user_x".to_string()));
+        assert_eq!(binlog_cfg.server_id, 2001);
+        Ok(())
+    }
+
+    fn test_schema() -> TableSchema {
+        let mut id = ColumnInfo::new("id".to_string(), "int".to_string(), "int".to_string());
+        id.unsigned = true;
+        let balance = ColumnInfo::new("balance".to_string(), "int".to_string(), "int".to_string());
+        let mut status =
+            ColumnInfo::new("status".to_string(), "enum".to_string(), "text".to_string());
+        status.enum_values = vec!["new".to_string(), "paid".to_string()];
+        let mut tags = ColumnInfo::new("tags".to_string(), "set".to_string(), "text".to_string());
+        tags.enum_values = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        TableSchema::new("test.orders".to_string(), "mysql".to_string())
+            .with_columns(vec![id, balance, status, tags])
+    }
+
+    #[test]
+    fn test_row_data_to_columns_uses_table_schema() {
+        let schema = test_schema();
+        let row = RowData::new(vec![
+            Some(MySqlValue::Int(u32::MAX)),
+            Some(MySqlValue::Int(u32::MAX)),
+            Some(MySqlValue::Enum(2)),
+            Some(MySqlValue::Set(0b101)),
+        ]);
+
+        let columns = row_data_to_columns(&row, Some(&schema));
+        assert_eq!(
+            columns,
+            vec![
+                ("id".to_string(), json!(u32::MAX)),
+                ("balance".to_string(), json!(-1)),
+                ("status".to_string(), json!("paid")),
+                ("tags".to_string(), json!("a,c")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_row_data_to_columns_without_schema() {
+        let row = RowData::new(vec![Some(MySqlValue::TinyInt(255)), None]);
+        let columns = row_data_to_columns(&row, None);
+        assert_eq!(
+            columns,
+            vec![
+                ("col_0".to_string(), json!(255)),
+                ("col_1".to_string(), JsonValue::Null),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signed_medium_int() {
+        let column = ColumnInfo::new("n".to_string(), "mediumint".to_string(), "int".to_string());
+        let value = mysql_value_to_json(&MySqlValue::MediumInt(0xFF_FFFE), Some(&column));
+        assert_eq!(value, json!(-2));
+    }
+
+    #[test]
+    fn test_is_ddl_statement() {
+        assert!(is_ddl_statement(
+            "ALTER TABLE orders ADD COLUMN note varchar(32)"
+        ));
+        assert!(is_ddl_statement("  drop table t"));
+        assert!(!is_ddl_statement("COMMIT"));
+        assert!(!is_ddl_statement("INSERT INTO t VALUES (1)"));
+
+        // 开头带注释的语句
+        assert!(is_ddl_statement(
+            "/* gh-ost */ ALTER TABLE orders ADD COLUMN note varchar(32)"
+        ));
+        assert!(is_ddl_statement(
+            "/* a */\n/* b */ -- migration 42\nCREATE TABLE t (id int)"
+        ));
+        assert!(is_ddl_statement("# flyway\nRENAME TABLE a TO b"));
+        assert!(is_ddl_statement(
+            "/*!50001 CREATE ALGORITHM=UNDEFINED */ /*!50001 VIEW v AS SELECT 1 */"
+        ));
+        assert!(!is_ddl_statement("/* ALTER */ INSERT INTO t VALUES (1)"));
+        assert!(!is_ddl_statement("/* unterminated ALTER"));
+    }
+
+    #[test]
+    fn test_row_data_to_columns_ignores_mismatched_schema() {
+        // 事件只有 2 列（如 DDL 之前的 binlog），不按位置套用 4 列表结构的列名
+        let row = RowData::new(vec![Some(MySqlValue::Int(1)), None]);
+        let columns = row_data_to_columns(&row, Some(&test_schema()));
+        assert_eq!(
+            columns,
+            vec![
+                ("col_0".to_string(), json!(1)),
+                ("col_1".to_string(), JsonValue::Null),
+            ]
+        );
+    }
 }
@@ -3,13 +3,20 @@
 //! `DatabaseReader` 持有 `DatabaseJob`，`ReaderJob` trait 实现在 Reader 上。
 //! `DatabaseJob` 负责业务逻辑（配置构建、schema discovery），
 //! `DatabaseReader` 负责生命周期管理和数据读取。
+//!
+//! `sync_mode = incremental` 且配置了 `watermark_column` 时按水位列增量读取，
+//! 水位通过 checkpoint 持久化（见 `rdbms_reader_util::util::watermark`）。
 
 use crate::{DataReaderJob, DataReaderTask, JsonStream, ReadTask, SplitReaderResult};
 use anyhow::Result;
-use std::sync::Arc;
-use tracing::info;
+use serde_json::Value as JsonValue;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
 
 use crate::rdbms_reader_util::rdbms_reader::{RdbmsConfig, RdbmsJob, RdbmsReader};
+use crate::rdbms_reader_util::util::watermark::{WatermarkConfig, WatermarkMark};
+use relus_common::constant::watermark;
+use relus_common::job_config::WriteMode;
 use relus_common::JobConfig;
 use relus_connector_rdbms::schema::SchemaDiscoveryConfig;
 
@@ -20,6 +27,12 @@ pub struct DatabaseReader {
 pub struct DatabaseJob {
     original_config: Arc<JobConfig>,
     discovery_config: SchemaDiscoveryConfig,
+    /// 水位列，仅水位增量任务有值
+    watermark_column: Option<String>,
+    /// 同一水位值内的排序键，取自 split_pk
+    watermark_keys: Vec<String>,
+    /// 从 checkpoint 恢复的水位
+    last_mark: RwLock<Option<WatermarkMark>>,
 }
 
 impl DatabaseReader {
@@ -31,9 +44,20 @@ impl DatabaseReader {
 
 impl DatabaseJob {
     pub fn new(original_config: Arc<JobConfig>) -> Result<Self> {
+        let watermark_column = original_config
+            .is_watermark_incremental()
+            .then(|| original_config.source.config_str(watermark::COLUMN_KEY))
+            .flatten();
+        let watermark_keys = match &watermark_column {
+            Some(column) => watermark_keys(&original_config, column),
+            None => Vec::new(),
+        };
         Ok(Self {
             original_config,
             discovery_config: SchemaDiscoveryConfig::default(),
+            watermark_column,
+            watermark_keys,
+            last_mark: RwLock::new(None),
         })
     }
 
@@ -48,7 +72,7 @@ impl DatabaseJob {
         let input = &self.original_config.source;
         let split_pk = input.config_str("split_pk");
         let where_clause = input.config_str("where");
-        let columns = input.config_str("columns").unwrap_or_else(|| {
+        let mut columns = input.config_str("columns").unwrap_or_else(|| {
             self.original_config
                 .column_mapping
                 .keys()
@@ -57,6 +81,31 @@ impl DatabaseJob {
                 .join(", ")
         });
         let split_factor = input.config_u64("split_factor").map(|n| n as usize);
+        let watermark = match &self.watermark_column {
+            Some(column) => Some(WatermarkConfig {
+                column: column.clone(),
+                keys: self.watermark_keys.clone(),
+                overlap: input.config_u64(watermark::OVERLAP_KEY).unwrap_or(0),
+                last_mark: self
+                    .last_mark
+                    .read()
+                    .map_err(|e| anyhow::anyhow!("watermark lock is poisoned: {}", e))?
+                    .clone(),
+            }),
+            None => None,
+        };
+        // 水位取自读出的记录，水位列与排序键必须在查询列中
+        if let Some(column) = &self.watermark_column {
+            for required in std::iter::once(column).chain(&self.watermark_keys) {
+                let selected = columns
+                    .split(',')
+                    .map(|c| c.trim())
+                    .any(|c| c == "*" || c == required);
+                if !selected {
+                    columns = format!("{}, {}", columns, required);
+                }
+            }
+        }
 
         let rdbms_config = RdbmsConfig {
             table: db_config.table,
@@ -69,6 +118,7 @@ impl DatabaseJob {
             split_factor,
             where_clause,
             columns,
+            watermark,
         };
 
         Ok(RdbmsJob::new(
@@ -98,6 +148,12 @@ impl DataReaderJob for DatabaseReader {
     fn description(&self) -> String {
         self.job.original_config.source.name.to_string()
     }
+
+    async fn post_sync(&self) -> Result<()> {
+        RdbmsReader::from_job(self.job.build_rdbms_job()?)
+            .post_sync()
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -106,4 +162,58 @@ impl DataReaderTask for DatabaseReader {
         let rdbms_reader: RdbmsReader = self.job.discover().await?;
         rdbms_reader.read_data(task).await
     }
+
+    fn checkpoint_of(&self, record: &JsonValue) -> Option<Vec<u8>> {
+        let column = self.job.watermark_column.as_deref()?;
+        WatermarkMark::from_record(column, &self.job.watermark_keys, record)?
+            .to_bytes()
+            .ok()
+    }
+
+    fn restore_checkpoint(&self, checkpoint: &[u8]) -> Result<()> {
+        let Some(column) = self.job.watermark_column.as_deref() else {
+            return Ok(());
+        };
+        let mark = WatermarkMark::from_bytes(checkpoint)?;
+        if mark.column != column {
+            warn!(
+                "[DatabaseReader] 水位列已由 {} 改为 {}，忽略已保存的水位",
+                mark.column, column
+            );
+            return Ok(());
+        }
+
+        info!(
+            "[DatabaseReader] restore watermark: {} = {}",
+            column, mark.value
+        );
+        let mut last_mark = self
+            .job
+            .last_mark
+            .write()
+            .map_err(|e| anyhow::anyhow!("watermark lock is poisoned: {}", e))?;
+        *last_mark = Some(mark);
+        Ok(())
+    }
+}
+
+/// 水位相同的行按 split_pk 排序；没有 split_pk 时只能按 `>=` 重读水位上的行，
+/// insert 写入会重复写入这些行
+fn watermark_keys(config: &JobConfig, column: &str) -> Vec<String> {
+    let keys: Vec<String> = config
+        .source
+        .config_str("split_pk")
+        .unwrap_or_default()
+        .split(',')
+        .map(|key| key.trim())
+        .filter(|key| !key.is_empty() && *key != column)
+        .map(|key| key.to_string())
+        .collect();
+    if keys.is_empty() && WriteMode::from_config(config) == WriteMode::Insert {
+        warn!(
+            "[DatabaseReader] 水位列 {} 未配置 split_pk，同一水位上的行会在下次运行重读，建议使用 upsert 写入",
+            column
+        );
+    }
+    keys
 }
@@ -4,7 +4,10 @@ pub mod database_reader;
 pub mod rdbms_reader_util;
 
 pub use api_reader::{ApiJob, ApiReader};
-pub use binlog_reader::{BinlogConfig, BinlogReader, CdcOp};
+pub use binlog_reader::{
+    binlog_source_for_snapshot, capture_binlog_position, BinlogConfig, BinlogPosition,
+    BinlogReader, CdcOp,
+};
 pub use database_reader::{DatabaseJob, DatabaseReader};
 pub use rdbms_reader_util::rdbms_reader::{
     count_total_records, execute_query_stream, DbRowStream, RdbmsConfig, RdbmsReader,
@@ -13,8 +16,9 @@ pub use rdbms_reader_util::rdbms_reader::{
 use anyhow::Result;
 use futures::stream::Stream;
 use relus_common::job_config::JobConfig;
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::pin::Pin;
 use std::sync::{Arc, OnceLock, RwLock};
 
@@ -33,7 +37,8 @@ pub enum StreamMode {
     Streaming,
 }
 
-#[derive(Debug, Clone)]
+/// 一个读取分片；可序列化，批量任务据此记录分片并在续跑时重放未完成的分片
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadTask {
     pub task_id: usize,
     pub conn: JsonValue,
@@ -47,6 +52,8 @@ pub struct SplitReaderResult {
     pub total_records: usize,
     pub tasks: Vec<ReadTask>,
     pub stream_mode: StreamMode,
+    /// 源列的逻辑类型（列名 → int / decimal / timestamp ...），未知时为空
+    pub column_types: BTreeMap<String, String>,
 }
 
 /// Reader Job trait
@@ -54,6 +61,11 @@ pub struct SplitReaderResult {
 pub trait DataReaderJob: Send + Sync {
     async fn split(&self, reader_threads: usize) -> Result<SplitReaderResult>;
     fn description(&self) -> String;
+
+    /// 所有 Pair 成功完成后由 core 调用一次，执行 source 的 post_sql
+    async fn post_sync(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Reader Task trait
@@ -62,6 +74,18 @@ pub trait DataReaderTask: Send + Sync {
     async fn read_data(&self, task: &ReadTask) -> Result<JsonStream>;
 
     fn shutdown(&self) {}
+
+    /// 从记录中提取可持久化的消费位点（CDC reader 实现）
+    ///
+    /// core 在记录所在批次被 writer 确认写入后保存该位点，默认不支持。
+    fn checkpoint_of(&self, _record: &JsonValue) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// 使用持久化的位点覆盖起始位置，需在 `read_data` 之前调用
+    fn restore_checkpoint(&self, _checkpoint: &[u8]) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Reader = DataReaderJob + DataReaderTask
@@ -170,7 +194,7 @@ inventory::submit! {
 
 inventory::submit! {
     ReaderPlugin {
-        source_type: "mysql_binlog",
+        source_type: binlog_reader::BINLOG_SOURCE_TYPE,
         create: |config| {
             let reader = BinlogReader::init(config)?;
             Ok(Box::new(reader))
@@ -3,4 +3,6 @@ pub mod util {
     pub mod client_tool;
     pub mod range_split_util;
     pub mod reader_split_util;
+    pub mod row_decoder;
+    pub mod watermark;
 }
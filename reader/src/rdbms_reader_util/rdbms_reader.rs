@@ -4,17 +4,19 @@
 //! `RdbmsJob` 负责业务逻辑（配置管理、schema discovery），
 //! `RdbmsReader` 负责生命周期管理和数据读取（返回 JsonStream）。
 
+use crate::rdbms_reader_util::util::watermark::WatermarkConfig;
 use crate::rdbms_reader_util::util::*;
 use crate::{DataReaderJob, DataReaderTask, JsonStream, ReadTask, SplitReaderResult};
 use anyhow::Result;
-use futures::{stream, StreamExt};
+use futures::{StreamExt, TryStreamExt};
 use relus_common::JobConfig;
 use relus_connector_rdbms::pool::RdbmsPool;
 use relus_connector_rdbms::schema::{MetadataDiscoverer, RdbmsDiscoverer, TableSchema};
 use relus_connector_rdbms::sql_builder::RdbmsSqlBuilder;
-use relus_connector_rdbms::util::get_pool_from_config;
+use relus_connector_rdbms::util::{execute_hook_sql, get_pool_from_config};
 use serde_json::Value as JsonValue;
-use sqlx::{Column, Row};
+use sqlx::mysql::MySqlPool;
+use sqlx::postgres::PgPool;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use tracing::info;
@@ -32,6 +34,8 @@ pub struct RdbmsConfig {
     pub split_factor: Option<usize>,
     pub where_clause: Option<String>,
     pub columns: String,
+    /// 按水位列增量读取，配置后整表读取为单个按水位排序的任务
+    pub watermark: Option<WatermarkConfig>,
 }
 
 /// RDBMS Reader
@@ -53,12 +57,19 @@ impl RdbmsReader {
 #[async_trait::async_trait]
 impl DataReaderJob for RdbmsReader {
     async fn split(&self, reader_threads: usize) -> Result<SplitReaderResult> {
+        let source = &self.job.original_config.source;
+        execute_hook_sql(source, "source pre_sql", &source.pre_sql).await?;
         let result = reader_split_util::do_split(&self.job, reader_threads).await;
         Ok(result)
     }
     fn description(&self) -> String {
         format!("RdbmsReader (table: {})", self.job.config.table)
     }
+
+    async fn post_sync(&self) -> Result<()> {
+        let source = &self.job.original_config.source;
+        execute_hook_sql(source, "source post_sql", &source.post_sql).await
+    }
 }
 
 /// RDBMS Reader Job 业务逻辑
@@ -100,8 +111,9 @@ impl RdbmsJob {
 
 /// Task: 执行查询并返回原始数据流
 ///
-/// 每个 task 在 split 阶段已被切分为 offset/limit 分片，
-/// 在 async 上下文中 collect 行后包装为 `'static` JsonStream。
+/// 每个 task 在 split 阶段已被切分为 offset/limit 分片。
+/// 行由后台任务逐条读取并经有界 channel 送出，下游消费变慢时
+/// 背压一直传到数据库游标，内存占用与分片大小无关。
 #[async_trait::async_trait]
 impl DataReaderTask for RdbmsReader {
     async fn read_data(&self, slice_task: &ReadTask) -> Result<JsonStream> {
@@ -136,51 +148,105 @@ impl DataReaderTask for RdbmsReader {
             }
         };
 
-        let rows = collect_query_rows(&pool, &sql).await?;
-        info!("Reader-{} 查询到 {} 条数据", slice_task.task_id, rows.len());
-
-        Ok(Box::pin(stream::iter(rows.into_iter().map(Ok))))
+        Ok(open_row_stream(pool, sql, slice_task.task_id))
     }
 }
 
-/// 从连接池执行查询并收集所有行为 JsonValue
-async fn collect_query_rows(pool: &RdbmsPool, sql: &str) -> Result<Vec<JsonValue>> {
-    match pool {
-        RdbmsPool::Postgres(pg_pool) => {
-            let mut rows = Vec::new();
-            let mut stream = sqlx::query(sql).fetch(pg_pool);
-            while let Some(result) = stream.next().await {
-                let row: sqlx::postgres::PgRow = result?;
-                let mut obj = serde_json::Map::new();
-                for (idx, col) in row.columns().iter().enumerate() {
-                    let val: Option<String> = row.try_get(idx).ok();
-                    obj.insert(
-                        col.name().to_string(),
-                        val.map(JsonValue::String).unwrap_or(JsonValue::Null),
-                    );
-                }
-                rows.push(JsonValue::Object(obj));
+/// 游标每次 FETCH 的行数，同时作为读取 channel 的容量
+const STREAM_FETCH_SIZE: usize = 1000;
+
+/// PostgreSQL 服务端游标名（每个连接独立，无需区分分片）
+const PG_CURSOR_NAME: &str = "relus_read_cursor";
+
+/// 启动后台读取任务，返回 `'static` 的行流
+///
+/// consumer 丢弃流后 send 失败，后台任务随即结束并释放连接。
+fn open_row_stream(pool: Arc<RdbmsPool>, sql: String, task_id: usize) -> JsonStream {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<JsonValue>>(STREAM_FETCH_SIZE);
+
+    tokio::spawn(async move {
+        let result = match pool.as_ref() {
+            RdbmsPool::Postgres(pg_pool) => stream_pg_cursor(pg_pool, &sql, &tx).await,
+            RdbmsPool::Mysql(my_pool) => stream_mysql_rows(my_pool, &sql, &tx).await,
+        };
+        match result {
+            Ok(total) => info!("Reader-{} 读取完成，共 {} 条数据", task_id, total),
+            Err(e) => {
+                let _ = tx.send(Err(e)).await;
             }
-            Ok(rows)
         }
-        RdbmsPool::Mysql(my_pool) => {
-            let mut rows = Vec::new();
-            let mut stream = sqlx::query(sql).fetch(my_pool);
-            while let Some(result) = stream.next().await {
-                let row: sqlx::mysql::MySqlRow = result?;
-                let mut obj = serde_json::Map::new();
-                for (idx, col) in row.columns().iter().enumerate() {
-                    let val: Option<String> = row.try_get(idx).ok();
-                    obj.insert(
-                        col.name().to_string(),
-                        val.map(JsonValue::String).unwrap_or(JsonValue::Null),
-                    );
-                }
-                rows.push(JsonValue::Object(obj));
+    });
+
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    });
+
+    Box::pin(stream)
+}
+
+/// 在查询外包一层 `DECLARE ... CURSOR`
+fn declare_cursor_sql(sql: &str) -> String {
+    let query = sql.trim().trim_end_matches(';').trim_end();
+    format!("DECLARE {PG_CURSOR_NAME} NO SCROLL CURSOR FOR {query}")
+}
+
+/// PostgreSQL：在事务内声明服务端游标，按 `STREAM_FETCH_SIZE` 分批 FETCH
+async fn stream_pg_cursor(
+    pool: &PgPool,
+    sql: &str,
+    tx: &tokio::sync::mpsc::Sender<Result<JsonValue>>,
+) -> Result<usize> {
+    let mut transaction = pool.begin().await?;
+    sqlx::query(&declare_cursor_sql(sql))
+        .persistent(false)
+        .execute(&mut *transaction)
+        .await?;
+
+    let fetch_sql = format!("FETCH FORWARD {STREAM_FETCH_SIZE} FROM {PG_CURSOR_NAME}");
+    let mut total = 0;
+    loop {
+        // 游标的结果类型随查询变化，FETCH 不进入连接的预编译缓存
+        let rows = sqlx::query(&fetch_sql)
+            .persistent(false)
+            .fetch_all(&mut *transaction)
+            .await?;
+        if rows.is_empty() {
+            break;
+        }
+        for row in &rows {
+            let value = row_decoder::pg_row_to_json(row)?;
+            if tx.send(Ok(value)).await.is_err() {
+                // consumer 已退出，事务随 drop 回滚
+                return Ok(total);
             }
-            Ok(rows)
+            total += 1;
+        }
+    }
+
+    sqlx::query(&format!("CLOSE {PG_CURSOR_NAME}"))
+        .execute(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+    Ok(total)
+}
+
+/// MySQL：独占连接逐行读取流式结果集
+async fn stream_mysql_rows(
+    pool: &MySqlPool,
+    sql: &str,
+    tx: &tokio::sync::mpsc::Sender<Result<JsonValue>>,
+) -> Result<usize> {
+    let mut conn = pool.acquire().await?;
+    let mut rows = sqlx::query(sql).fetch(&mut *conn);
+    let mut total = 0;
+    while let Some(row) = rows.try_next().await? {
+        let value = row_decoder::mysql_row_to_json(&row)?;
+        if tx.send(Ok(value)).await.is_err() {
+            return Ok(total);
         }
+        total += 1;
     }
+    Ok(total)
 }
 
 type JsonStreamInternal<'a> =
@@ -189,32 +255,16 @@ type JsonStreamInternal<'a> =
 /// 执行流式查询（借用 供 split 阶段 count 等场景使用）
 pub fn execute_query_stream<'a>(pool: &'a RdbmsPool, sql: &'a str) -> Result<DbRowStream<'a>> {
     let stream: JsonStreamInternal<'a> = match pool {
-        RdbmsPool::Postgres(pg_pool) => Box::pin(sqlx::query(sql).fetch(pg_pool).map(|result| {
-            result.map(|row: sqlx::postgres::PgRow| {
-                let mut obj = serde_json::Map::new();
-                for (idx, col) in row.columns().iter().enumerate() {
-                    let val: Option<String> = row.try_get(idx).ok();
-                    obj.insert(
-                        col.name().to_string(),
-                        val.map(JsonValue::String).unwrap_or(JsonValue::Null),
-                    );
-                }
-                JsonValue::Object(obj)
-            })
-        })),
-        RdbmsPool::Mysql(my_pool) => Box::pin(sqlx::query(sql).fetch(my_pool).map(|result| {
-            result.map(|row: sqlx::mysql::MySqlRow| {
-                let mut obj = serde_json::Map::new();
-                for (idx, col) in row.columns().iter().enumerate() {
-                    let val: Option<String> = row.try_get(idx).ok();
-                    obj.insert(
-                        col.name().to_string(),
-                        val.map(JsonValue::String).unwrap_or(JsonValue::Null),
-                    );
-                }
-                JsonValue::Object(obj)
-            })
-        })),
+        RdbmsPool::Postgres(pg_pool) => Box::pin(
+            sqlx::query(sql)
+                .fetch(pg_pool)
+                .map(|result| result.and_then(|row| row_decoder::pg_row_to_json(&row))),
+        ),
+        RdbmsPool::Mysql(my_pool) => Box::pin(
+            sqlx::query(sql)
+                .fetch(my_pool)
+                .map(|result| result.and_then(|row| row_decoder::mysql_row_to_json(&row))),
+        ),
     };
 
     Ok(DbRowStream { stream })
@@ -251,3 +301,16 @@ pub async fn count_total_records(
         None => Ok(0),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declare_cursor_sql() {
+        assert_eq!(
+            declare_cursor_sql("SELECT id FROM users LIMIT 10 OFFSET 0;  "),
+            "DECLARE relus_read_cursor NO SCROLL CURSOR FOR SELECT id FROM users LIMIT 10 OFFSET 0"
+        );
+    }
+}
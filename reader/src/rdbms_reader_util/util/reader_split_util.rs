@@ -1,16 +1,20 @@
 use crate::rdbms_reader_util::rdbms_reader::count_total_records;
 use crate::rdbms_reader_util::rdbms_reader::RdbmsJob;
+use crate::rdbms_reader_util::util::row_decoder::{self, describe_column_types};
+use crate::rdbms_reader_util::util::watermark::{WatermarkBound, WatermarkConfig};
 use crate::{ReadTask, SplitReaderResult, StreamMode};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use relus_common::constant::key::SPLIT_FACTOR;
 use relus_connector_rdbms::pool::{ColumnValue, DatabaseKind, RdbmsPool};
-use relus_connector_rdbms::sql_builder::{PkRangeSelect, RdbmsSqlBuilder};
+use relus_connector_rdbms::sql_builder::{
+    KeysetSelect, PkRangeSelect, RdbmsSqlBuilder, WatermarkLower, WatermarkSelect,
+};
 use relus_connector_rdbms::util::get_pool_from_config;
 use serde_json::Value as JsonValue;
 use tracing::{info, warn};
 
 /// Reader 切分工具
-/// 如果need_split_table为true，优先尝试主键切分；如果失败则回退到LIMIT/OFFSET切分；如果不需要切分，则构建单个任务。
+/// 如果need_split_table为true，按 `split_table` 的顺序尝试各切分策略；如果不需要切分，则构建单个任务。
 /// has_split_pk: 是否存在可用的主键用于切分
 /// each_table_should_split: 每个表建议切分的片数 (advice_number / table_number)
 pub async fn do_split(rdbms_job: &RdbmsJob, advice_number: usize) -> SplitReaderResult {
@@ -24,11 +28,12 @@ pub async fn do_split(rdbms_job: &RdbmsJob, advice_number: usize) -> SplitReader
                 total_records: 0,
                 stream_mode: StreamMode::Batch,
                 tasks: vec![],
+                column_types: Default::default(),
             };
         }
     };
     // 计算总行数
-    let total_records: usize = count_total_records(
+    let mut total_records: usize = count_total_records(
         &pool,
         &config.table,
         config
@@ -59,7 +64,21 @@ pub async fn do_split(rdbms_job: &RdbmsJob, advice_number: usize) -> SplitReader
 
     let mut tasks = Vec::new();
 
-    if config.is_table_mode {
+    if let (true, Some(watermark)) = (config.is_table_mode, config.watermark.as_ref()) {
+        match build_watermark_task(&pool, database_kind, config, watermark, &conns).await {
+            Ok((task, count)) => {
+                info!(
+                    "按水位列 {} 增量读取，下界 {:?}，待同步 {} 条",
+                    watermark.column,
+                    watermark.lower_bound(),
+                    count
+                );
+                total_records = count;
+                tasks.push(task);
+            }
+            Err(e) => warn!("构建水位增量任务失败: {:?}", e),
+        }
+    } else if config.is_table_mode {
         let table_number = config.table_count.max(1);
         let mut each_table_should_split =
             calculate_each_table_split_number(advice_number, table_number);
@@ -78,29 +97,16 @@ pub async fn do_split(rdbms_job: &RdbmsJob, advice_number: usize) -> SplitReader
                 let split_factor = config.split_factor.unwrap_or(SPLIT_FACTOR);
                 each_table_should_split *= split_factor;
             }
-            // 进行单表的切分
-            match split_by_pk(
+            tasks = split_table(
                 &pool,
                 database_kind,
                 config,
                 split_pk,
                 each_table_should_split,
+                total_records,
                 &conns,
             )
-            .await
-            {
-                Ok(split_tasks) => tasks.extend(split_tasks),
-                Err(e) => {
-                    warn!("主键切分失败，回退到 LIMIT/OFFSET 模式: {:?}", e);
-                    tasks = split_by_limit_offset(
-                        config,
-                        database_kind,
-                        each_table_should_split,
-                        total_records,
-                        &conns,
-                    );
-                }
-            }
+            .await;
         } else {
             tasks = build_single_task(config, database_kind, &conns);
         }
@@ -132,10 +138,20 @@ pub async fn do_split(rdbms_job: &RdbmsJob, advice_number: usize) -> SplitReader
             .collect::<Vec<String>>()
             .join(", ")
     );
+    // 源列类型随分片结果返回，RecordBuilder 据此保留值的类型
+    let column_types = match tasks.first().and_then(|t| t.query_sql.as_deref()) {
+        Some(sql) => describe_column_types(&pool, sql).await.unwrap_or_else(|e| {
+            warn!("获取源列类型失败，按文本处理: {:?}", e);
+            Default::default()
+        }),
+        None => Default::default(),
+    };
+
     SplitReaderResult {
         total_records,
         tasks,
         stream_mode: StreamMode::Batch,
+        column_types,
     }
 }
 
@@ -187,27 +203,22 @@ async fn split_by_pk(
 
     let is_numeric = min_cv.as_ref().is_some_and(|v| v.is_numeric())
         || max_cv.as_ref().is_some_and(|v| v.is_numeric());
+    if !is_numeric {
+        // 非数值键无法按值域等分，交给采样切分
+        bail!("切分键 {} 不是数值类型", split_pk);
+    }
 
     let min_str = min_cv.and_then(|v| v.into_string());
     let max_str = max_cv.and_then(|v| v.into_string());
 
     let mut tasks = Vec::new();
 
-    let ranges = if is_numeric {
-        split_by_numeric_pk(
-            min_str.as_deref(),
-            max_str.as_deref(),
-            shard_count,
-            split_pk,
-        )
-    } else {
-        split_by_string_pk(
-            min_str.as_deref(),
-            max_str.as_deref(),
-            shard_count,
-            split_pk,
-        )
-    };
+    let ranges = split_by_numeric_pk(
+        min_str.as_deref(),
+        max_str.as_deref(),
+        shard_count,
+        split_pk,
+    );
 
     for (i, range) in ranges.iter().enumerate() {
         let builder = RdbmsSqlBuilder::new(database_kind);
@@ -262,6 +273,339 @@ async fn split_by_pk(
     Ok(tasks)
 }
 
+/// 水位增量任务：读取下界之后的行并按 `(水位列, split_pk)` 排序
+///
+/// 只生成单个任务，保证批次按水位递增写入，checkpoint 才能安全地随批次前移。
+async fn build_watermark_task(
+    pool: &RdbmsPool,
+    database_kind: DatabaseKind,
+    config: &crate::RdbmsConfig,
+    watermark: &WatermarkConfig,
+    conns: &[JsonValue],
+) -> Result<(ReadTask, usize)> {
+    let builder = RdbmsSqlBuilder::new(database_kind);
+    let (after, from) = match watermark.lower_bound() {
+        Some(WatermarkBound::After(values)) => (Some(key_literals(&builder, &values)?), None),
+        Some(WatermarkBound::From(value)) => (None, Some(key_literal(&builder, &value)?)),
+        None => (None, None),
+    };
+    let lower = match (&after, &from) {
+        (Some(values), _) => Some(WatermarkLower::After(values)),
+        (_, Some(value)) => Some(WatermarkLower::From(value)),
+        _ => None,
+    };
+
+    let query_sql = builder.watermark_select(WatermarkSelect {
+        table: &config.table,
+        columns: &config.columns,
+        where_clause: config.where_clause.as_deref(),
+        column: &watermark.column,
+        keys: &watermark.keys,
+        lower,
+    });
+    let count = count_total_records(pool, &config.table, Some(&query_sql)).await?;
+
+    let task = ReadTask {
+        task_id: 0,
+        conn: conns.first().cloned().unwrap_or(JsonValue::Null),
+        query_sql: Some(query_sql),
+        offset: 0,
+        limit: 0,
+    };
+    Ok((task, count))
+}
+
+/// 单表切分：数值主键范围切分 → 采样切分 → keyset 切分 → LIMIT/OFFSET 切分，
+/// 前一种失败时依次回退
+async fn split_table(
+    pool: &RdbmsPool,
+    database_kind: DatabaseKind,
+    config: &crate::RdbmsConfig,
+    split_pk: &str,
+    shard_count: usize,
+    total_records: usize,
+    conns: &[JsonValue],
+) -> Vec<ReadTask> {
+    let keys = parse_split_keys(split_pk);
+
+    if keys.len() == 1 {
+        match split_by_pk(pool, database_kind, config, split_pk, shard_count, conns).await {
+            Ok(tasks) => return tasks,
+            Err(e) => warn!("主键范围切分失败，改用采样切分: {:?}", e),
+        }
+    }
+
+    match split_by_sampling(
+        pool,
+        database_kind,
+        config,
+        &keys,
+        shard_count,
+        total_records,
+        conns,
+    )
+    .await
+    {
+        Ok(tasks) => return tasks,
+        Err(e) => warn!("采样切分失败，回退到 keyset 模式: {:?}", e),
+    }
+
+    match split_by_keyset(
+        pool,
+        database_kind,
+        config,
+        &keys,
+        shard_count,
+        total_records,
+        conns,
+    )
+    .await
+    {
+        Ok(tasks) => return tasks,
+        Err(e) => warn!("keyset 切分失败，回退到 LIMIT/OFFSET 模式: {:?}", e),
+    }
+
+    split_by_limit_offset(config, database_kind, shard_count, total_records, conns)
+}
+
+/// 解析切分键，支持 `k1,k2` 形式的复合键
+fn parse_split_keys(split_pk: &str) -> Vec<String> {
+    split_pk
+        .split(',')
+        .map(|key| key.trim())
+        .filter(|key| !key.is_empty())
+        .map(|key| key.to_string())
+        .collect()
+}
+
+/// Keyset（seek）切分
+///
+/// 预先沿有序键逐段取出分片上界：每次从上一个边界之后跳过 `chunk_size - 1` 行，
+/// 只扫描索引一遍。分片形如 `(keys) > (lower) AND (keys) <= (upper)`，
+/// 不依赖 OFFSET，分片之间也不会因并发写入而重叠或遗漏。
+async fn split_by_keyset(
+    pool: &RdbmsPool,
+    database_kind: DatabaseKind,
+    config: &crate::RdbmsConfig,
+    keys: &[String],
+    shard_count: usize,
+    total_records: usize,
+    conns: &[JsonValue],
+) -> Result<Vec<ReadTask>> {
+    if keys.is_empty() {
+        bail!("未配置 keyset 切分键");
+    }
+    let builder = RdbmsSqlBuilder::new(database_kind);
+    let chunk_size = total_records.div_ceil(shard_count.max(1)).max(1);
+
+    let mut boundaries: Vec<Vec<String>> = Vec::new();
+    while boundaries.len() + 1 < shard_count {
+        let sql = builder.keyset_boundary(
+            &config.table,
+            keys,
+            config.where_clause.as_deref(),
+            boundaries.last().map(|b| b.as_slice()),
+            chunk_size - 1,
+        );
+        match fetch_key_row(pool, &sql).await? {
+            Some(values) => boundaries.push(key_literals(&builder, &values)?),
+            None => break,
+        }
+    }
+
+    Ok(build_keyset_tasks(
+        &builder,
+        config,
+        keys,
+        &boundaries,
+        conns,
+    ))
+}
+
+/// 采样切分
+///
+/// 适用于 UUID、字符串编码、复合键等任意可排序的键：PostgreSQL 用 `TABLESAMPLE`
+/// 取样后挑出等距的边界，MySQL 用 `ORDER BY ... LIMIT 1 OFFSET k` 逐点探测边界。
+async fn split_by_sampling(
+    pool: &RdbmsPool,
+    database_kind: DatabaseKind,
+    config: &crate::RdbmsConfig,
+    keys: &[String],
+    shard_count: usize,
+    total_records: usize,
+    conns: &[JsonValue],
+) -> Result<Vec<ReadTask>> {
+    if keys.is_empty() {
+        bail!("未配置采样切分键");
+    }
+    if shard_count <= 1 || total_records == 0 {
+        bail!("记录数 {} 不足以按 {} 片采样", total_records, shard_count);
+    }
+    let builder = RdbmsSqlBuilder::new(database_kind);
+    let where_clause = config.where_clause.as_deref();
+
+    let sample_sql = builder.key_sample(
+        &config.table,
+        keys,
+        where_clause,
+        sample_percent(total_records, shard_count),
+    );
+    let boundaries = match sample_sql {
+        Some(sql) => {
+            let samples = fetch_key_rows(pool, &sql)
+                .await?
+                .iter()
+                .map(|values| key_literals(&builder, values))
+                .collect::<Result<Vec<_>>>()?;
+            if samples.len() < shard_count {
+                bail!("采样仅得到 {} 行，不足 {} 片", samples.len(), shard_count);
+            }
+            evenly_spaced(&samples, shard_count)
+        }
+        None => {
+            let mut boundaries = Vec::with_capacity(shard_count - 1);
+            for i in 1..shard_count {
+                let offset = i * total_records / shard_count;
+                let sql = builder.keyset_boundary(&config.table, keys, where_clause, None, offset);
+                match fetch_key_row(pool, &sql).await? {
+                    Some(values) => boundaries.push(key_literals(&builder, &values)?),
+                    None => break,
+                }
+            }
+            boundaries.dedup();
+            boundaries
+        }
+    };
+    if boundaries.is_empty() {
+        bail!("采样未得到切分边界");
+    }
+
+    Ok(build_keyset_tasks(
+        &builder,
+        config,
+        keys,
+        &boundaries,
+        conns,
+    ))
+}
+
+/// 每片期望的采样行数
+const SAMPLES_PER_SHARD: usize = 64;
+
+/// 按期望采样行数估算 `TABLESAMPLE` 百分比
+fn sample_percent(total_records: usize, shard_count: usize) -> f64 {
+    let wanted = (shard_count * SAMPLES_PER_SHARD) as f64;
+    (wanted * 100.0 / total_records.max(1) as f64).clamp(0.01, 100.0)
+}
+
+/// 从有序样本中挑出 `shard_count - 1` 个等距边界（去重）
+fn evenly_spaced(samples: &[Vec<String>], shard_count: usize) -> Vec<Vec<String>> {
+    let mut boundaries: Vec<Vec<String>> = (1..shard_count)
+        .filter_map(|i| samples.get(i * samples.len() / shard_count).cloned())
+        .collect();
+    boundaries.dedup();
+    boundaries
+}
+
+/// 按边界生成 `(keys) > (lower) AND (keys) <= (upper)` 分片，外加键为 NULL 的分片
+fn build_keyset_tasks(
+    builder: &RdbmsSqlBuilder,
+    config: &crate::RdbmsConfig,
+    keys: &[String],
+    boundaries: &[Vec<String>],
+    conns: &[JsonValue],
+) -> Vec<ReadTask> {
+    let mut tasks = Vec::with_capacity(boundaries.len() + 2);
+    for i in 0..=boundaries.len() {
+        let query_sql = builder.keyset_select(KeysetSelect {
+            table: &config.table,
+            columns: &config.columns,
+            where_clause: config.where_clause.as_deref(),
+            keys,
+            lower: i.checked_sub(1).map(|prev| boundaries[prev].as_slice()),
+            upper: boundaries.get(i).map(|b| b.as_slice()),
+        });
+        let conn = conns
+            .get(i % conns.len().max(1))
+            .cloned()
+            .unwrap_or(JsonValue::Null);
+        tasks.push(ReadTask {
+            task_id: i,
+            conn,
+            query_sql: Some(query_sql),
+            offset: 0,
+            limit: 0,
+        });
+    }
+
+    let null_sql = builder.null_keys_select(
+        &config.table,
+        &config.columns,
+        config.where_clause.as_deref(),
+        keys,
+    );
+    tasks.push(ReadTask {
+        task_id: tasks.len(),
+        conn: conns.first().cloned().unwrap_or(JsonValue::Null),
+        query_sql: Some(null_sql),
+        offset: 0,
+        limit: 0,
+    });
+
+    tasks
+}
+
+/// 执行边界查询，按列顺序返回键值
+async fn fetch_key_row(pool: &RdbmsPool, sql: &str) -> Result<Option<Vec<JsonValue>>> {
+    let values = match pool {
+        RdbmsPool::Postgres(pg_pool) => match sqlx::query(sql).fetch_optional(pg_pool).await? {
+            Some(row) => Some(row_decoder::pg_row_values(&row)?),
+            None => None,
+        },
+        RdbmsPool::Mysql(my_pool) => match sqlx::query(sql).fetch_optional(my_pool).await? {
+            Some(row) => Some(row_decoder::mysql_row_values(&row)?),
+            None => None,
+        },
+    };
+    Ok(values)
+}
+
+/// 执行采样查询，按列顺序返回每行键值
+async fn fetch_key_rows(pool: &RdbmsPool, sql: &str) -> Result<Vec<Vec<JsonValue>>> {
+    let rows = match pool {
+        RdbmsPool::Postgres(pg_pool) => sqlx::query(sql)
+            .fetch_all(pg_pool)
+            .await?
+            .iter()
+            .map(row_decoder::pg_row_values)
+            .collect::<Result<Vec<_>, _>>()?,
+        RdbmsPool::Mysql(my_pool) => sqlx::query(sql)
+            .fetch_all(my_pool)
+            .await?
+            .iter()
+            .map(row_decoder::mysql_row_values)
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    Ok(rows)
+}
+
+fn key_literals(builder: &RdbmsSqlBuilder, values: &[JsonValue]) -> Result<Vec<String>> {
+    values
+        .iter()
+        .map(|value| key_literal(builder, value))
+        .collect()
+}
+
+/// 将键值渲染为 SQL 字面量
+fn key_literal(builder: &RdbmsSqlBuilder, value: &JsonValue) -> Result<String> {
+    match value {
+        JsonValue::Number(n) => Ok(n.to_string()),
+        JsonValue::String(s) => Ok(builder.literal_string(s)),
+        JsonValue::Bool(b) => Ok(if *b { "TRUE" } else { "FALSE" }.to_string()),
+        other => bail!("不支持的 keyset 键值: {}", other),
+    }
+}
+
 fn split_by_limit_offset(
     config: &crate::RdbmsConfig,
     database_kind: DatabaseKind,
@@ -363,24 +707,43 @@ fn split_by_numeric_pk(
         .collect()
 }
 
-fn split_by_string_pk(
-    min: Option<&str>,
-    max: Option<&str>,
-    count: usize,
-    pk: &str,
-) -> Vec<PkRange> {
-    let min_val = min.unwrap_or("");
-    let max_val = max.unwrap_or("");
-
-    if count > 1 {
-        // super::range_split_util::do_ascii_string_split(min_val, max_val, pk, count, 128)
-        // 这个方法通过ascii 所以redix必须固定128
-        super::range_split_util::do_hex_string_split(min_val, max_val, pk, count, 128)
-    } else {
-        vec![PkRange::Range {
-            pk: pk.to_string(),
-            min: min_val.to_string(),
-            max: max_val.to_string(),
-        }]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_split_keys() {
+        assert_eq!(parse_split_keys("id"), vec!["id"]);
+        assert_eq!(parse_split_keys(" tenant , id ,"), vec!["tenant", "id"]);
+    }
+
+    #[test]
+    fn test_evenly_spaced_boundaries() {
+        let samples: Vec<Vec<String>> = (0..10).map(|i| vec![format!("'k{}'", i)]).collect();
+        assert_eq!(
+            evenly_spaced(&samples, 4),
+            vec![vec!["'k2'"], vec!["'k5'"], vec!["'k7'"]]
+        );
+
+        let duplicated = vec![vec!["1".to_string()]; 8];
+        assert_eq!(evenly_spaced(&duplicated, 4), vec![vec!["1"]]);
+    }
+
+    #[test]
+    fn test_sample_percent_bounds() {
+        assert_eq!(sample_percent(100, 4), 100.0);
+        assert!((sample_percent(1_000_000, 10) - 0.064).abs() < 1e-9);
+        assert_eq!(sample_percent(usize::MAX, 2), 0.01);
+    }
+
+    #[test]
+    fn test_key_literal() -> Result<()> {
+        let builder = RdbmsSqlBuilder::new(DatabaseKind::Mysql);
+        assert_eq!(key_literal(&builder, &json!(42))?, "42");
+        assert_eq!(key_literal(&builder, &json!("O'Neil"))?, "'O''Neil'");
+        assert_eq!(key_literal(&builder, &json!(true))?, "TRUE");
+        assert!(key_literal(&builder, &json!(null)).is_err());
+        Ok(())
     }
 }
@@ -0,0 +1,275 @@
+//! 按原生类型解码数据库行
+//!
+//! 每列按数据库类型解码为带类型的 JsonValue，并通过 `type_kind_of` 给出对应的
+//! 逻辑类型，RecordBuilder 据此把值还原为同类型的 `UnifiedValue`：
+//! - 整数 / 浮点 / 布尔 → JSON number / bool
+//! - DECIMAL / NUMERIC → 字符串（保留全部精度）
+//! - DATE / TIME / DATETIME → `%Y-%m-%d`、`%H:%M:%S%.f`、`%Y-%m-%d %H:%M:%S%.f`，
+//!   TIMESTAMPTZ 转为 UTC 的 RFC3339 字符串
+//! - JSON / JSONB / PG 数组 → JSON 值
+//! - 二进制 → base64 字符串
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use relus_common::types::{bytes_to_json, TypeKind};
+use relus_connector_rdbms::pool::RdbmsPool;
+use rust_decimal::Decimal;
+use serde_json::Value as JsonValue;
+use sqlx::mysql::MySqlRow;
+use sqlx::postgres::PgRow;
+use sqlx::{Column, Executor, Row, TypeInfo, ValueRef};
+
+/// 根据数据库类型名（PostgreSQL / MySQL）得到逻辑类型
+pub fn type_kind_of(type_name: &str) -> TypeKind {
+    let upper = type_name.to_uppercase();
+    if upper.ends_with("[]") {
+        return TypeKind::Json;
+    }
+
+    match upper.trim_end_matches(" UNSIGNED") {
+        "BOOL" | "BOOLEAN" => TypeKind::Bool,
+        "INT2" | "INT4" | "INT8" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT"
+        | "YEAR" | "BIT" => TypeKind::Int,
+        "FLOAT4" | "FLOAT8" | "FLOAT" | "DOUBLE" => TypeKind::Float,
+        "NUMERIC" | "DECIMAL" => TypeKind::Decimal,
+        "DATE" => TypeKind::Date,
+        "TIME" => TypeKind::Time,
+        "TIMESTAMP" | "TIMESTAMPTZ" | "DATETIME" => TypeKind::Timestamp,
+        "JSON" | "JSONB" => TypeKind::Json,
+        "BYTEA" | "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB"
+        | "GEOMETRY" => TypeKind::Bytes,
+        _ => TypeKind::Text,
+    }
+}
+
+/// 查询结果列的逻辑类型（列名 → 类型名），不执行查询
+pub async fn describe_column_types(
+    pool: &RdbmsPool,
+    sql: &str,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let columns: Vec<(String, String)> = match pool {
+        RdbmsPool::Postgres(pg_pool) => {
+            let describe = pg_pool.describe(sql).await?;
+            describe
+                .columns()
+                .iter()
+                .map(|col| (col.name().to_string(), col.type_info().name().to_string()))
+                .collect()
+        }
+        RdbmsPool::Mysql(my_pool) => {
+            let describe = my_pool.describe(sql).await?;
+            describe
+                .columns()
+                .iter()
+                .map(|col| (col.name().to_string(), col.type_info().name().to_string()))
+                .collect()
+        }
+    };
+
+    Ok(columns
+        .into_iter()
+        .map(|(name, type_name)| (name, type_kind_of(&type_name).as_str().to_string()))
+        .collect())
+}
+
+fn float_to_json(f: f64) -> JsonValue {
+    serde_json::Number::from_f64(f)
+        .map(JsonValue::Number)
+        .unwrap_or_else(|| JsonValue::String(f.to_string()))
+}
+
+/// f32 按最短十进制表示转为 f64，避免 0.1f32 变成 0.10000000149011612
+fn f32_to_json(f: f32) -> JsonValue {
+    float_to_json(f.to_string().parse().unwrap_or(f64::from(f)))
+}
+
+fn date_to_json(d: NaiveDate) -> JsonValue {
+    JsonValue::String(d.format("%Y-%m-%d").to_string())
+}
+
+fn time_to_json(t: NaiveTime) -> JsonValue {
+    JsonValue::String(t.format("%H:%M:%S%.f").to_string())
+}
+
+fn datetime_to_json(dt: NaiveDateTime) -> JsonValue {
+    JsonValue::String(dt.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+}
+
+fn decode_error(column: &str, type_name: &str, err: sqlx::Error) -> sqlx::Error {
+    sqlx::Error::Decode(format!("列 '{}' ({}) 解码失败: {}", column, type_name, err).into())
+}
+
+/// 将 PostgreSQL 行解码为带类型的 JsonValue
+pub fn pg_row_to_json(row: &PgRow) -> Result<JsonValue, sqlx::Error> {
+    let values = pg_row_values(row)?;
+    let obj = row
+        .columns()
+        .iter()
+        .map(|col| col.name().to_string())
+        .zip(values)
+        .collect();
+    Ok(JsonValue::Object(obj))
+}
+
+/// 按列顺序解码 PostgreSQL 行
+pub fn pg_row_values(row: &PgRow) -> Result<Vec<JsonValue>, sqlx::Error> {
+    let mut values = Vec::with_capacity(row.len());
+    for (idx, col) in row.columns().iter().enumerate() {
+        let type_name = col.type_info().name();
+        let value = if row.try_get_raw(idx)?.is_null() {
+            JsonValue::Null
+        } else {
+            decode_pg_value(row, idx, type_name)
+                .map_err(|e| decode_error(col.name(), type_name, e))?
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+fn decode_pg_value(row: &PgRow, idx: usize, type_name: &str) -> Result<JsonValue, sqlx::Error> {
+    let value = match type_name {
+        "BOOL" => JsonValue::Bool(row.try_get::<bool, _>(idx)?),
+        "INT2" => JsonValue::from(row.try_get::<i16, _>(idx)?),
+        "INT4" => JsonValue::from(row.try_get::<i32, _>(idx)?),
+        "INT8" => JsonValue::from(row.try_get::<i64, _>(idx)?),
+        "FLOAT4" => f32_to_json(row.try_get::<f32, _>(idx)?),
+        "FLOAT8" => float_to_json(row.try_get::<f64, _>(idx)?),
+        "NUMERIC" => JsonValue::String(row.try_get::<Decimal, _>(idx)?.to_string()),
+        "DATE" => date_to_json(row.try_get::<NaiveDate, _>(idx)?),
+        "TIME" => time_to_json(row.try_get::<NaiveTime, _>(idx)?),
+        "TIMESTAMP" => datetime_to_json(row.try_get::<NaiveDateTime, _>(idx)?),
+        "TIMESTAMPTZ" => JsonValue::String(
+            row.try_get::<DateTime<Utc>, _>(idx)?
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        ),
+        "JSON" | "JSONB" => row.try_get::<JsonValue, _>(idx)?,
+        "BYTEA" => bytes_to_json(&row.try_get::<Vec<u8>, _>(idx)?),
+        "UUID" => {
+            let raw: &[u8] = row.try_get_unchecked(idx)?;
+            let hex: String = raw.iter().map(|b| format!("{:02x}", b)).collect();
+            if hex.len() != 32 {
+                return Err(sqlx::Error::Decode("invalid uuid".into()));
+            }
+            JsonValue::String(format!(
+                "{}-{}-{}-{}-{}",
+                &hex[0..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..32]
+            ))
+        }
+        "INT2[]" => JsonValue::from(row.try_get::<Vec<i16>, _>(idx)?),
+        "INT4[]" => JsonValue::from(row.try_get::<Vec<i32>, _>(idx)?),
+        "INT8[]" => JsonValue::from(row.try_get::<Vec<i64>, _>(idx)?),
+        "FLOAT8[]" => JsonValue::from(row.try_get::<Vec<f64>, _>(idx)?),
+        "BOOL[]" => JsonValue::from(row.try_get::<Vec<bool>, _>(idx)?),
+        "TEXT[]" | "VARCHAR[]" => JsonValue::from(row.try_get::<Vec<String>, _>(idx)?),
+        // TEXT / VARCHAR / BPCHAR / NAME / 自定义 ENUM 等文本编码类型
+        _ => JsonValue::String(row.try_get_unchecked::<String, _>(idx)?),
+    };
+    Ok(value)
+}
+
+/// 将 MySQL 行解码为带类型的 JsonValue
+pub fn mysql_row_to_json(row: &MySqlRow) -> Result<JsonValue, sqlx::Error> {
+    let values = mysql_row_values(row)?;
+    let obj = row
+        .columns()
+        .iter()
+        .map(|col| col.name().to_string())
+        .zip(values)
+        .collect();
+    Ok(JsonValue::Object(obj))
+}
+
+/// 按列顺序解码 MySQL 行
+pub fn mysql_row_values(row: &MySqlRow) -> Result<Vec<JsonValue>, sqlx::Error> {
+    let mut values = Vec::with_capacity(row.len());
+    for (idx, col) in row.columns().iter().enumerate() {
+        let type_name = col.type_info().name();
+        let value = if row.try_get_raw(idx)?.is_null() {
+            JsonValue::Null
+        } else {
+            decode_mysql_value(row, idx, type_name)
+                .map_err(|e| decode_error(col.name(), type_name, e))?
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+fn decode_mysql_value(
+    row: &MySqlRow,
+    idx: usize,
+    type_name: &str,
+) -> Result<JsonValue, sqlx::Error> {
+    let value = match type_name {
+        "BOOLEAN" => JsonValue::Bool(row.try_get::<bool, _>(idx)?),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => {
+            JsonValue::from(row.try_get::<i64, _>(idx)?)
+        }
+        "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED"
+        | "BIGINT UNSIGNED" | "YEAR" | "BIT" => {
+            JsonValue::from(row.try_get_unchecked::<u64, _>(idx)?)
+        }
+        "FLOAT" => f32_to_json(row.try_get::<f32, _>(idx)?),
+        "DOUBLE" => float_to_json(row.try_get::<f64, _>(idx)?),
+        // 二进制协议中 DECIMAL 以字符串传输，直接取原文避免精度损失
+        "DECIMAL" => JsonValue::String(row.try_get_unchecked::<String, _>(idx)?),
+        "DATE" => date_to_json(row.try_get::<NaiveDate, _>(idx)?),
+        "TIME" => time_to_json(row.try_get::<NaiveTime, _>(idx)?),
+        "DATETIME" | "TIMESTAMP" => datetime_to_json(row.try_get::<NaiveDateTime, _>(idx)?),
+        "JSON" => row.try_get::<JsonValue, _>(idx)?,
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => {
+            bytes_to_json(&row.try_get_unchecked::<Vec<u8>, _>(idx)?)
+        }
+        // CHAR / VARCHAR / TEXT / ENUM / SET
+        _ => JsonValue::String(row.try_get_unchecked::<String, _>(idx)?),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_kind_of() {
+        assert_eq!(type_kind_of("INT4"), TypeKind::Int);
+        assert_eq!(type_kind_of("BIGINT UNSIGNED"), TypeKind::Int);
+        assert_eq!(type_kind_of("BOOLEAN"), TypeKind::Bool);
+        assert_eq!(type_kind_of("NUMERIC"), TypeKind::Decimal);
+        assert_eq!(type_kind_of("TIMESTAMPTZ"), TypeKind::Timestamp);
+        assert_eq!(type_kind_of("DATETIME"), TypeKind::Timestamp);
+        assert_eq!(type_kind_of("DATE"), TypeKind::Date);
+        assert_eq!(type_kind_of("JSONB"), TypeKind::Json);
+        assert_eq!(type_kind_of("INT4[]"), TypeKind::Json);
+        assert_eq!(type_kind_of("BYTEA"), TypeKind::Bytes);
+        assert_eq!(type_kind_of("LONGBLOB"), TypeKind::Bytes);
+        assert_eq!(type_kind_of("VARCHAR"), TypeKind::Text);
+        assert_eq!(type_kind_of("ENUM"), TypeKind::Text);
+    }
+
+    #[test]
+    fn test_temporal_formats_match_converters() -> anyhow::Result<()> {
+        use relus_common::types::{TypeConverterRegistry, UnifiedValue};
+
+        let registry = TypeConverterRegistry::new();
+        let dt = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .and_then(|d| d.and_hms_micro_opt(8, 30, 15, 123_456))
+            .ok_or_else(|| anyhow::anyhow!("invalid datetime"))?;
+
+        let value = registry.convert(&datetime_to_json(dt), Some("timestamp"))?;
+        assert_eq!(value, UnifiedValue::DateTime(dt));
+
+        let value = registry.convert(&date_to_json(dt.date()), Some("date"))?;
+        assert_eq!(value, UnifiedValue::Date(dt.date()));
+
+        let value = registry.convert(&time_to_json(dt.time()), Some("time"))?;
+        assert_eq!(value, UnifiedValue::Time(dt.time()));
+        Ok(())
+    }
+}
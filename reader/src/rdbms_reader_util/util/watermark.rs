@@ -0,0 +1,246 @@
+//! 数据库源按水位列增量同步
+//!
+//! 每次运行按 `(水位列, split_pk)` 排序读取，水位随批次写入 checkpoint：
+//! 只有 writer 确认批次后才前移，中断后从已确认的位置继续。
+//!
+//! checkpoint 记录最后一行的水位值和 split_pk 值，下次从 `(column, keys) > (mark)` 继续，
+//! 批次边界落在一串相同水位值中间时也不会跳过剩余的行。配置了 overlap 或没有 split_pk 时
+//! 读取 `column >= 水位 - overlap`，会重读水位上的行，需要 upsert 写入。
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tracing::warn;
+
+/// 水位配置
+#[derive(Debug, Clone)]
+pub struct WatermarkConfig {
+    /// 水位列
+    pub column: String,
+    /// 同一水位值内的排序键（split_pk），不含水位列本身
+    pub keys: Vec<String>,
+    /// 回退窗口（时间列为秒，数值列为数值）
+    pub overlap: u64,
+    /// 上次已确认的水位，首次运行为 None（全量读取）
+    pub last_mark: Option<WatermarkMark>,
+}
+
+/// 本次读取的下界
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatermarkBound {
+    /// `(column, keys...) > (values...)`：从上次确认的行之后继续
+    After(Vec<JsonValue>),
+    /// `column >= value`：重读该水位上的行
+    From(JsonValue),
+}
+
+impl WatermarkConfig {
+    /// 本次读取的下界
+    ///
+    /// 旧 checkpoint 没有记录键值、或键配置已变化时按 `column >= 水位` 读取。
+    pub fn lower_bound(&self) -> Option<WatermarkBound> {
+        let mark = self.last_mark.as_ref()?;
+        if self.overlap > 0 {
+            return Some(WatermarkBound::From(shift_back(&mark.value, self.overlap)));
+        }
+        if self.keys.is_empty() || mark.keys.len() != self.keys.len() {
+            return Some(WatermarkBound::From(mark.value.clone()));
+        }
+        let mut values = Vec::with_capacity(self.keys.len() + 1);
+        values.push(mark.value.clone());
+        values.extend(mark.keys.iter().cloned());
+        Some(WatermarkBound::After(values))
+    }
+}
+
+/// 持久化到 checkpoint 的水位
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatermarkMark {
+    pub column: String,
+    pub value: JsonValue,
+    /// 该行 split_pk 的值，与 `WatermarkConfig::keys` 一一对应
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<JsonValue>,
+}
+
+impl WatermarkMark {
+    /// 从记录中取水位列与排序键的值，水位为 NULL 的行不计入水位；
+    /// 键值缺失或为 NULL 时只记录水位
+    pub fn from_record(column: &str, keys: &[String], record: &JsonValue) -> Option<Self> {
+        let value = record.get(column).filter(|v| !v.is_null())?;
+        let keys = keys
+            .iter()
+            .map(|key| record.get(key).filter(|v| !v.is_null()).cloned())
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default();
+        Some(Self {
+            column: column.to_string(),
+            value: value.clone(),
+            keys,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes)
+            .map_err(|e| anyhow::anyhow!("水位 checkpoint 解析失败: {}", e))
+    }
+}
+
+/// 将水位回退 `overlap`，无法识别的类型保持原值
+fn shift_back(mark: &JsonValue, overlap: u64) -> JsonValue {
+    if overlap == 0 {
+        return mark.clone();
+    }
+
+    let shifted = match mark {
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Some(JsonValue::from(i.saturating_sub_unsigned(overlap)))
+            } else if let Some(u) = n.as_u64() {
+                Some(JsonValue::from(u.saturating_sub(overlap)))
+            } else {
+                n.as_f64().map(|f| JsonValue::from(f - overlap as f64))
+            }
+        }
+        JsonValue::String(s) => shift_text_back(s, overlap).map(JsonValue::String),
+        _ => None,
+    };
+
+    shifted.unwrap_or_else(|| {
+        warn!("水位 {} 无法回退 {}，按原值读取", mark, overlap);
+        mark.clone()
+    })
+}
+
+/// 时间按秒回退（保持原格式），DECIMAL 字符串按数值回退
+fn shift_text_back(s: &str, overlap: u64) -> Option<String> {
+    let seconds = Duration::try_seconds(i64::try_from(overlap).ok()?)?;
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(
+            (dt.with_timezone(&Utc) - seconds).to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        );
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f") {
+        return Some((dt - seconds).format("%Y-%m-%d %H:%M:%S%.f").to_string());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        let dt = date.and_hms_opt(0, 0, 0)? - seconds;
+        return Some(dt.format("%Y-%m-%d").to_string());
+    }
+    if let Ok(decimal) = s.parse::<Decimal>() {
+        return Some((decimal - Decimal::from(overlap)).to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(mark: JsonValue, overlap: u64) -> WatermarkConfig {
+        WatermarkConfig {
+            column: "updated_at".to_string(),
+            keys: Vec::new(),
+            overlap,
+            last_mark: Some(WatermarkMark {
+                column: "updated_at".to_string(),
+                value: mark,
+                keys: Vec::new(),
+            }),
+        }
+    }
+
+    fn from(value: JsonValue) -> Option<WatermarkBound> {
+        Some(WatermarkBound::From(value))
+    }
+
+    #[test]
+    fn test_lower_bound_applies_overlap() {
+        assert_eq!(config(json!(100), 10).lower_bound(), from(json!(90)));
+        assert_eq!(
+            config(json!("2024-03-01 00:00:30.5"), 60).lower_bound(),
+            from(json!("2024-02-29 23:59:30.500"))
+        );
+        assert_eq!(
+            config(json!("2024-03-01T08:00:00+08:00"), 1).lower_bound(),
+            from(json!("2024-02-29T23:59:59Z"))
+        );
+        assert_eq!(
+            config(json!("2024-03-01"), 1).lower_bound(),
+            from(json!("2024-02-29"))
+        );
+        assert_eq!(
+            config(json!("12.50"), 2).lower_bound(),
+            from(json!("10.50"))
+        );
+        assert_eq!(config(json!("abc"), 5).lower_bound(), from(json!("abc")));
+        assert_eq!(config(json!("abc"), 0).lower_bound(), from(json!("abc")));
+
+        let first_run = WatermarkConfig {
+            last_mark: None,
+            ..config(json!(1), 1)
+        };
+        assert_eq!(first_run.lower_bound(), None);
+    }
+
+    #[test]
+    fn test_mark_roundtrip() -> Result<()> {
+        let keys = vec!["id".to_string()];
+        let record = json!({"id": 1, "updated_at": "2024-03-01 00:00:00"});
+        let mark = WatermarkMark::from_record("updated_at", &keys, &record)
+            .ok_or_else(|| anyhow::anyhow!("mark missing"))?;
+        assert_eq!(mark.keys, vec![json!(1)]);
+        assert_eq!(WatermarkMark::from_bytes(&mark.to_bytes()?)?, mark);
+
+        assert!(
+            WatermarkMark::from_record("updated_at", &keys, &json!({"updated_at": null})).is_none()
+        );
+        // 旧格式的 checkpoint 没有 keys
+        let old = WatermarkMark::from_bytes(br#"{"column":"updated_at","value":5}"#)?;
+        assert!(old.keys.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resume_inside_group_of_equal_watermarks() -> Result<()> {
+        let keys = vec!["id".to_string()];
+        let rows = [
+            json!({"id": 1, "updated_at": "2024-03-01 00:00:00"}),
+            json!({"id": 2, "updated_at": "2024-03-01 00:00:00"}),
+            json!({"id": 3, "updated_at": "2024-03-01 00:00:00"}),
+            json!({"id": 4, "updated_at": "2024-03-01 00:00:01"}),
+        ];
+        // 批次在第二行结束，checkpoint 记录 (updated_at, id)
+        let mark = WatermarkMark::from_record("updated_at", &keys, &rows[1])
+            .ok_or_else(|| anyhow::anyhow!("mark missing"))?;
+        let config = WatermarkConfig {
+            column: "updated_at".to_string(),
+            keys,
+            overlap: 0,
+            last_mark: Some(mark),
+        };
+        let Some(WatermarkBound::After(bound)) = config.lower_bound() else {
+            anyhow::bail!("expected a compound bound");
+        };
+        assert_eq!(bound, vec![json!("2024-03-01 00:00:00"), json!(2)]);
+
+        // 按 (updated_at, id) 比较，同一水位上的第三行仍会被读取
+        let sort_key = |row: &JsonValue| (row["updated_at"].to_string(), row["id"].as_i64());
+        let bound_key = (bound[0].to_string(), bound[1].as_i64());
+        let remaining: Vec<_> = rows
+            .iter()
+            .filter(|row| sort_key(row) > bound_key)
+            .map(|row| row["id"].clone())
+            .collect();
+        assert_eq!(remaining, vec![json!(3), json!(4)]);
+        Ok(())
+    }
+}
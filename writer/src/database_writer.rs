@@ -16,7 +16,11 @@ use crate::rdbms_writer_util::rdbms_writer::{
 };
 use crate::{DataWriterJob, DataWriterTask, SplitWriterResult, WriteTask};
 use relus_common::constant::pipeline::DEFAULT_BATCH_SIZE;
+use relus_common::data_source_config::InsertMethod;
 use relus_common::job_config::WriteMode;
+use relus_connector_rdbms::mysql_load::LocalInfileLoader;
+use relus_connector_rdbms::pool::{detect_database_kind, DatabaseKind};
+use relus_connector_rdbms::schema::staging_table_name;
 
 pub struct DatabaseWriter {
     job: DatabaseJob,
@@ -24,6 +28,8 @@ pub struct DatabaseWriter {
 
 pub struct DatabaseJob {
     original_config: Arc<JobConfig>,
+    /// 本次运行的标识，用于 staging 表名
+    run_id: String,
 }
 
 impl DatabaseWriter {
@@ -35,7 +41,10 @@ impl DatabaseWriter {
 
 impl DatabaseJob {
     pub fn new(original_config: Arc<JobConfig>) -> Result<Self> {
-        Ok(Self { original_config })
+        Ok(Self {
+            original_config,
+            run_id: chrono::Local::now().format("%Y%m%d%H%M%S").to_string(),
+        })
     }
 
     fn build_rdbms_config(&self) -> Result<RdbmsConfig> {
@@ -49,20 +58,38 @@ impl DatabaseJob {
             .unwrap_or(DEFAULT_BATCH_SIZE);
         let use_transaction = db_config.use_transaction.unwrap_or(false);
 
+        let staging_table = db_config
+            .use_staging_table
+            .unwrap_or(false)
+            .then(|| staging_table_name(&db_config.table, &self.run_id));
+
         Ok(RdbmsConfig {
+            staging_table,
             table: db_config.table,
             key_columns: db_config.key_columns.unwrap_or_default(),
             mode,
             use_transaction,
             batch_size,
+            commit_batches: db_config.commit_batches.unwrap_or(1),
+            insert_method: db_config.insert_method.unwrap_or_default(),
+            create_table_if_missing: db_config.create_table_if_missing.unwrap_or(false),
+            history: db_config.history,
         })
     }
 
     fn build_rdbms_writer(&self) -> Result<RdbmsWriter> {
         let config = self.build_rdbms_config()?;
+        // load_data 只对 MySQL 目标生效，其它目标沿用 VALUES
+        let db_config = self.original_config.target.parse_database_config()?;
+        let url = db_config.to_url();
+        let load_data = config.insert_method == InsertMethod::LoadData
+            && detect_database_kind(&url, None)? == DatabaseKind::Mysql;
         let writer = Arc::new(PipelineRowWriter);
 
-        let job = RdbmsJob::new(Arc::clone(&self.original_config), config, writer);
+        let mut job = RdbmsJob::new(Arc::clone(&self.original_config), config, writer);
+        if load_data {
+            job = job.with_local_infile(LocalInfileLoader::new(&db_config)?);
+        }
 
         Ok(RdbmsWriter::from_job(job))
     }
@@ -77,6 +104,16 @@ impl DataWriterJob for DatabaseWriter {
     fn description(&self) -> String {
         self.job.original_config.target.name.to_string()
     }
+
+    async fn post_sync(&self) -> Result<()> {
+        let rdbms_writer = self.job.build_rdbms_writer()?;
+        rdbms_writer.post_sync().await
+    }
+
+    async fn abort(&self) -> Result<()> {
+        let rdbms_writer = self.job.build_rdbms_writer()?;
+        rdbms_writer.abort().await
+    }
 }
 
 #[async_trait::async_trait]
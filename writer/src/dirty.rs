@@ -0,0 +1,387 @@
+//! 脏数据收集
+//!
+//! 配置 `error_limit` 后，转换失败或写入失败的行不会让任务立即失败，而是交给
+//! [`DirtyRecordCollector`]：记录到 dead-letter（文件或目标库中的表），累计条数，
+//! 超过 `record` / `percentage` 限制时返回错误中止任务。
+//!
+//! 同一张表的所有 writer task 共享一个收集器，限制按整张表计算。
+//!
+//! 写入阶段发现的脏数据先按 writer task 暂存，事务提交后才写入 dead-letter 并计数；
+//! 事务回滚重试时丢弃暂存，重放批次不会重复记录。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+use relus_common::job_config::{DeadLetterConfig, ErrorLimitConfig};
+use relus_common::types::UnifiedValue;
+use relus_common::JobConfig;
+use relus_connector_rdbms::pool::{DatabaseKind, RdbmsPool};
+use relus_connector_rdbms::util::get_pool_from_output;
+use serde_json::Value as JsonValue;
+use tracing::{info, warn};
+
+/// 已读行数达到该值后，每条脏数据都检查比例限制，不必等 Pair 结束
+const PERCENTAGE_MIN_SAMPLE: usize = 1000;
+
+/// 暂存的脏数据：原始记录与错误
+type StagedRecord = (JsonValue, anyhow::Error);
+
+/// 脏数据输出位置
+enum DeadLetterSink {
+    /// 只记录日志
+    Log,
+    /// 追加写入 JSON Lines 文件
+    File { path: String, file: Mutex<File> },
+    /// 写入目标库中的表
+    Table {
+        table: String,
+        pool: Arc<RdbmsPool>,
+        insert_sql: String,
+    },
+}
+
+/// 单张表的脏数据收集器
+pub struct DirtyRecordCollector {
+    table: String,
+    record_limit: Option<usize>,
+    percentage_limit: Option<f64>,
+    sink: DeadLetterSink,
+    /// 脏数据条数
+    dirty: AtomicUsize,
+    /// 已读取（参与转换）的总行数
+    total: AtomicUsize,
+    /// 各 writer task 写入事务中尚未提交的脏数据
+    staged: Mutex<HashMap<usize, Vec<StagedRecord>>>,
+}
+
+impl fmt::Debug for DirtyRecordCollector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sink = match &self.sink {
+            DeadLetterSink::Log => "log".to_string(),
+            DeadLetterSink::File { path, .. } => format!("file:{}", path),
+            DeadLetterSink::Table { table, .. } => format!("table:{}", table),
+        };
+        f.debug_struct("DirtyRecordCollector")
+            .field("table", &self.table)
+            .field("record_limit", &self.record_limit)
+            .field("percentage_limit", &self.percentage_limit)
+            .field("sink", &sink)
+            .field("dirty", &self.dirty_count())
+            .finish()
+    }
+}
+
+impl DirtyRecordCollector {
+    /// 按 `error_limit` 创建收集器；dead-letter 表不存在时在目标库中创建
+    pub async fn open(
+        config: &ErrorLimitConfig,
+        job: &JobConfig,
+        table: &str,
+    ) -> Result<Arc<Self>> {
+        let sink = match &config.dead_letter {
+            None => DeadLetterSink::Log,
+            Some(DeadLetterConfig::File { path }) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                DeadLetterSink::File {
+                    path: path.clone(),
+                    file: Mutex::new(file),
+                }
+            }
+            Some(DeadLetterConfig::Table { table }) => {
+                validate_table_name(table)?;
+                let pool = get_pool_from_output(job).await?;
+                pool.executor()
+                    .execute(&create_table_sql(pool.kind(), table))
+                    .await?;
+                info!("[DirtyRecord] 脏数据写入表 {}", table);
+                DeadLetterSink::Table {
+                    table: table.clone(),
+                    insert_sql: insert_sql(pool.kind(), table),
+                    pool,
+                }
+            }
+        };
+        Ok(Arc::new(Self::with_sink(config, table, sink)))
+    }
+
+    fn with_sink(config: &ErrorLimitConfig, table: &str, sink: DeadLetterSink) -> Self {
+        Self {
+            table: table.to_string(),
+            record_limit: config.record,
+            percentage_limit: config.percentage,
+            sink,
+            dirty: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+            staged: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 累计已读取的行数，作为比例限制的分母
+    pub fn add_total(&self, count: usize) {
+        self.total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn dirty_count(&self) -> usize {
+        self.dirty.load(Ordering::Relaxed)
+    }
+
+    /// 记录一条脏数据，超过条数限制，或已读行数达到样本量后超过比例限制时返回错误
+    pub async fn record(&self, record: &JsonValue, error: &anyhow::Error) -> Result<()> {
+        let message = format!("{:#}", error);
+        warn!(
+            "[DirtyRecord] 表 {} 脏数据: {}，数据: {}",
+            self.table, message, record
+        );
+        self.write_sink(record, &message).await?;
+
+        let dirty = self.dirty.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(limit) = self.record_limit {
+            if dirty > limit {
+                bail!(
+                    "表 {} 脏数据 {} 条，超过限制 {} 条，最后一条错误: {}",
+                    self.table,
+                    dirty,
+                    limit,
+                    message
+                );
+            }
+        }
+        if self.total.load(Ordering::Relaxed) >= PERCENTAGE_MIN_SAMPLE {
+            self.check_percentage()?;
+        }
+        Ok(())
+    }
+
+    /// 暂存 writer task 写入事务中的一条脏数据
+    ///
+    /// 已记录与暂存的条数超过条数限制时返回错误，让事务回滚、任务中止。
+    pub fn stage(&self, task_id: usize, record: &JsonValue, error: anyhow::Error) -> Result<()> {
+        let mut staged = self.lock_staged()?;
+        staged
+            .entry(task_id)
+            .or_default()
+            .push((record.clone(), error));
+        if let Some(limit) = self.record_limit {
+            let dirty = self.dirty_count() + staged.values().map(Vec::len).sum::<usize>();
+            if dirty > limit {
+                bail!(
+                    "表 {} 脏数据 {} 条，超过限制 {} 条",
+                    self.table,
+                    dirty,
+                    limit
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// 事务提交后记录该 task 暂存的脏数据
+    pub async fn flush_staged(&self, task_id: usize) -> Result<()> {
+        let staged = self.lock_staged()?.remove(&task_id).unwrap_or_default();
+        for (record, error) in staged {
+            self.record(&record, &error).await?;
+        }
+        Ok(())
+    }
+
+    /// 事务回滚时丢弃该 task 暂存的脏数据
+    pub fn discard_staged(&self, task_id: usize) {
+        if let Ok(mut staged) = self.lock_staged() {
+            staged.remove(&task_id);
+        }
+    }
+
+    fn lock_staged(&self) -> Result<std::sync::MutexGuard<'_, HashMap<usize, Vec<StagedRecord>>>> {
+        self.staged
+            .lock()
+            .map_err(|err| anyhow::anyhow!("脏数据暂存锁异常: {}", err))
+    }
+
+    /// 检查脏数据比例是否超过限制
+    pub fn check_percentage(&self) -> Result<()> {
+        let Some(limit) = self.percentage_limit else {
+            return Ok(());
+        };
+        let total = self.total.load(Ordering::Relaxed);
+        let dirty = self.dirty_count();
+        if total > 0 && dirty as f64 / total as f64 > limit {
+            bail!(
+                "表 {} 脏数据 {}/{} 条，比例超过限制 {}",
+                self.table,
+                dirty,
+                total,
+                limit
+            );
+        }
+        Ok(())
+    }
+
+    async fn write_sink(&self, record: &JsonValue, message: &str) -> Result<()> {
+        match &self.sink {
+            DeadLetterSink::Log => Ok(()),
+            DeadLetterSink::File { file, .. } => {
+                let line = serde_json::json!({
+                    "table": self.table,
+                    "error": message,
+                    "record": record,
+                    "time": chrono::Local::now().to_rfc3339(),
+                });
+                let mut file = file
+                    .lock()
+                    .map_err(|err| anyhow::anyhow!("脏数据文件锁异常: {}", err))?;
+                writeln!(file, "{}", line)?;
+                Ok(())
+            }
+            DeadLetterSink::Table {
+                pool, insert_sql, ..
+            } => {
+                let params = [
+                    UnifiedValue::String(self.table.clone()),
+                    UnifiedValue::String(message.to_string()),
+                    UnifiedValue::String(record.to_string()),
+                ];
+                pool.executor()
+                    .execute_with_params(insert_sql, &params)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// dead-letter 表名按目标表的规则校验：`table` 或 `schema.table`，只允许字母、数字和下划线
+fn validate_table_name(table: &str) -> Result<()> {
+    let parts: Vec<&str> = table.split('.').collect();
+    let valid = parts.len() <= 2
+        && parts.iter().all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if !valid {
+        bail!(
+            "dead_letter 表名仅允许字母、数字和下划线（可带 schema 前缀）: {}",
+            table
+        );
+    }
+    Ok(())
+}
+
+fn create_table_sql(kind: DatabaseKind, table: &str) -> String {
+    let created_at = match kind {
+        DatabaseKind::Postgres => "TIMESTAMP DEFAULT CURRENT_TIMESTAMP",
+        DatabaseKind::Mysql => "DATETIME(6) DEFAULT CURRENT_TIMESTAMP(6)",
+    };
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} (table_name VARCHAR(255), error TEXT, record TEXT, created_at {})",
+        table, created_at
+    )
+}
+
+fn insert_sql(kind: DatabaseKind, table: &str) -> String {
+    let placeholders = match kind {
+        DatabaseKind::Postgres => "$1, $2, $3",
+        DatabaseKind::Mysql => "?, ?, ?",
+    };
+    format!(
+        "INSERT INTO {} (table_name, error, record) VALUES ({})",
+        table, placeholders
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collector(record: Option<usize>, percentage: Option<f64>) -> DirtyRecordCollector {
+        let config = ErrorLimitConfig {
+            record,
+            percentage,
+            dead_letter: None,
+        };
+        DirtyRecordCollector::with_sink(&config, "users", DeadLetterSink::Log)
+    }
+
+    #[tokio::test]
+    async fn record_limit_fails_after_limit() -> Result<()> {
+        let dirty = collector(Some(1), None);
+        let error = anyhow::anyhow!("bad value");
+        dirty.record(&serde_json::json!({"id": 1}), &error).await?;
+        assert!(dirty
+            .record(&serde_json::json!({"id": 2}), &error)
+            .await
+            .is_err());
+        assert_eq!(dirty.dirty_count(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn percentage_limit_uses_read_rows() -> Result<()> {
+        let dirty = collector(None, Some(0.1));
+        dirty.add_total(10);
+        dirty
+            .record(&serde_json::json!({"id": 1}), &anyhow::anyhow!("bad"))
+            .await?;
+        dirty.check_percentage()?;
+
+        dirty
+            .record(&serde_json::json!({"id": 2}), &anyhow::anyhow!("bad"))
+            .await?;
+        assert!(dirty.check_percentage().is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn percentage_limit_checked_while_running() -> Result<()> {
+        let dirty = collector(None, Some(0.01));
+        dirty.add_total(PERCENTAGE_MIN_SAMPLE);
+        for id in 0..10 {
+            dirty
+                .record(&serde_json::json!({"id": id}), &anyhow::anyhow!("bad"))
+                .await?;
+        }
+        assert!(dirty
+            .record(&serde_json::json!({"id": 10}), &anyhow::anyhow!("bad"))
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn staged_records_count_only_after_flush() -> Result<()> {
+        let dirty = collector(Some(2), None);
+        let record = serde_json::json!({"id": 1});
+        dirty.stage(0, &record, anyhow::anyhow!("bad"))?;
+        dirty.discard_staged(0);
+        dirty.stage(0, &record, anyhow::anyhow!("bad"))?;
+        dirty.stage(1, &record, anyhow::anyhow!("bad"))?;
+        assert_eq!(dirty.dirty_count(), 0);
+        assert!(dirty.stage(1, &record, anyhow::anyhow!("bad")).is_err());
+
+        dirty.flush_staged(0).await?;
+        assert_eq!(dirty.dirty_count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn dead_letter_table_name_is_validated() {
+        assert!(validate_table_name("relus_dirty").is_ok());
+        assert!(validate_table_name("ops.relus_dirty").is_ok());
+        assert!(validate_table_name("dirty; DROP TABLE users").is_err());
+        assert!(validate_table_name("a.b.c").is_err());
+        assert!(validate_table_name("").is_err());
+    }
+
+    #[test]
+    fn dead_letter_sql_per_database() {
+        assert_eq!(
+            insert_sql(DatabaseKind::Mysql, "dirty"),
+            "INSERT INTO dirty (table_name, error, record) VALUES (?, ?, ?)"
+        );
+        assert!(create_table_sql(DatabaseKind::Postgres, "dirty")
+            .ends_with("created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP)"));
+    }
+}
@@ -1,11 +1,15 @@
 pub mod database_writer;
+pub mod dirty;
 pub mod rdbms_writer_util;
 
 pub use database_writer::{DatabaseJob, DatabaseWriter};
+pub use dirty::DirtyRecordCollector;
 pub use rdbms_writer_util::rdbms_writer::{RdbmsConfig, RdbmsJob, RdbmsWriter, RowWriter};
 
 use anyhow::Result;
 use relus_common::job_config::{JobConfig, WriteMode};
+use relus_common::pipeline::SpeedLimiter;
+use relus_connector_rdbms::retry::RetryPolicy;
 use std::collections::HashMap;
 use std::sync::{Arc, OnceLock, RwLock};
 use tokio::sync::mpsc;
@@ -22,6 +26,15 @@ pub struct WriteTask {
     pub mode: WriteMode,
     pub use_transaction: bool,
     pub batch_size: usize,
+    /// 批次确认通道：每个 DataBatch 写入成功后回传写入条数，
+    /// core 据此推进 CDC checkpoint
+    pub batch_ack: Option<mpsc::UnboundedSender<usize>>,
+    /// 脏数据收集器：配置 `error_limit` 时由 core 设置，写入失败的批次逐行重试隔离脏数据
+    pub dirty: Option<Arc<DirtyRecordCollector>>,
+    /// 瞬时错误重试策略，由 core 按任务配置覆盖
+    pub retry: RetryPolicy,
+    /// 全局限速器，配置 `speed` 时由 core 设置，所有 Writer 共享
+    pub speed: Option<SpeedLimiter>,
 }
 
 /// Job 切分结果
@@ -34,6 +47,16 @@ pub struct SplitWriterResult {
 pub trait DataWriterJob: Send + Sync {
     async fn split(&self, writer_threads: usize) -> Result<SplitWriterResult>;
     fn description(&self) -> String;
+
+    /// 所有 Pair 成功完成后由 core 调用一次，执行 target 的 post_sql
+    async fn post_sync(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 表同步失败或任务取消时由 core 调用，清理本次运行创建的临时表
+    async fn abort(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Writer Task trait
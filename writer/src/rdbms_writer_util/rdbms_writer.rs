@@ -5,21 +5,32 @@
 use std::sync::Arc;
 
 use anyhow::{bail, Result};
-use relus_connector_rdbms::pool::RdbmsPool;
-use relus_connector_rdbms::sql_builder::{execute_rdbms_write, validate_upsert_keys, WriteRows};
-use relus_connector_rdbms::util::get_pool_from_output;
+use relus_connector_rdbms::mysql_load::{execute_load_data, LocalInfileLoader};
+use relus_connector_rdbms::pg_copy::execute_copy_insert;
+use relus_connector_rdbms::pool::{DatabaseExecutor, RdbmsPool};
+use relus_connector_rdbms::retry::is_retryable;
+use relus_connector_rdbms::schema::{
+    create_staging_table, create_table_if_missing, drop_staging_table, map_to_target_schema,
+    swap_staging_table, MetadataDiscoverer, RdbmsDiscoverer,
+};
+use relus_connector_rdbms::sql_builder::{
+    execute_rdbms_write, execute_scd2_close, validate_upsert_keys, WriteRows,
+};
+use relus_connector_rdbms::util::{execute_hook_sql, get_pool_for, get_pool_from_output};
 
-use relus_common::pipeline::PipelineMessage;
+use relus_common::pipeline::{row_bytes, PipelineMessage};
 use relus_common::types::UnifiedValue;
 use relus_common::JobConfig;
 use relus_common::MappingRow;
 use tokio::sync::mpsc;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::rdbms_writer_util::util::writer_split_util;
-use relus_common::job_config::WriteMode;
+use relus_common::constant::cdc;
+use relus_common::data_source_config::{HistoryColumns, InsertMethod};
+use relus_common::job_config::{SyncMode, WriteMode};
 
-use crate::{DataWriterJob, DataWriterTask, SplitWriterResult, WriteTask};
+use crate::{DataWriterJob, DataWriterTask, DirtyRecordCollector, SplitWriterResult, WriteTask};
 
 /// RDBMS 写入配置
 #[derive(Debug, Clone)]
@@ -29,6 +40,23 @@ pub struct RdbmsConfig {
     pub mode: WriteMode,
     pub use_transaction: bool,
     pub batch_size: usize,
+    /// 开启事务时每个事务提交的批次数
+    pub commit_batches: usize,
+    /// insert 模式的写入方式
+    pub insert_method: InsertMethod,
+    /// 目标表不存在时按源表结构自动建表
+    pub create_table_if_missing: bool,
+    /// 开启 staging 时本次运行写入的 staging 表，全部成功后替换 `table`
+    pub staging_table: Option<String>,
+    /// soft_delete / scd2 模式使用的列
+    pub history: HistoryColumns,
+}
+
+impl RdbmsConfig {
+    /// 实际写入的表：开启 staging 时为 staging 表
+    pub fn write_table(&self) -> &str {
+        self.staging_table.as_deref().unwrap_or(&self.table)
+    }
 }
 
 /// RDBMS Writer
@@ -54,6 +82,19 @@ impl RdbmsWriter {
 #[async_trait::async_trait]
 impl DataWriterJob for RdbmsWriter {
     async fn split(&self, writer_threads: usize) -> Result<SplitWriterResult> {
+        if self.job.config.create_table_if_missing {
+            self.create_target_table().await?;
+        }
+        if let Some(stage) = &self.job.config.staging_table {
+            let original = &self.job.original_config;
+            if is_cdc_job(original) || original.sync_mode == Some(SyncMode::Incremental) {
+                bail!("use_staging_table 只用于整表重载，不能用于增量或 CDC 任务");
+            }
+            let pool = get_pool_from_output(&self.job.original_config).await?;
+            create_staging_table(&pool, &self.job.config.table, stage).await?;
+        }
+        let target = &self.job.original_config.target;
+        execute_hook_sql(target, "target pre_sql", &target.pre_sql).await?;
         let result = writer_split_util::do_split(&self.job.original_config, writer_threads);
         Ok(result)
     }
@@ -61,6 +102,52 @@ impl DataWriterJob for RdbmsWriter {
     fn description(&self) -> String {
         format!("RdbmsWriter (table: {})", self.job.config.table)
     }
+
+    async fn post_sync(&self) -> Result<()> {
+        if let Some(stage) = &self.job.config.staging_table {
+            let pool = get_pool_from_output(&self.job.original_config).await?;
+            swap_staging_table(&pool, &self.job.config.table, stage).await?;
+        }
+        let target = &self.job.original_config.target;
+        execute_hook_sql(target, "target post_sql", &target.post_sql).await
+    }
+
+    async fn abort(&self) -> Result<()> {
+        let Some(stage) = &self.job.config.staging_table else {
+            return Ok(());
+        };
+        let pool = get_pool_from_output(&self.job.original_config).await?;
+        drop_staging_table(&pool, stage).await
+    }
+}
+
+impl RdbmsWriter {
+    /// 目标表不存在时按源表结构建表，列名、主键按 column_mapping / key_columns 改写
+    async fn create_target_table(&self) -> Result<()> {
+        let original = &self.job.original_config;
+        if original.source.source_type != "database" {
+            bail!("create_table_if_missing 需要 database 类型的 source");
+        }
+        let Some(source_table) = original.source.table_name() else {
+            bail!("create_table_if_missing 需要 source 连接配置 table");
+        };
+
+        let source_pool = get_pool_for(&original.source).await?;
+        let source_schema = RdbmsDiscoverer::new(source_pool, source_table)
+            .discover()
+            .await?;
+        let target_schema = map_to_target_schema(
+            &source_schema,
+            &self.job.config.table,
+            &original.column_mapping,
+            original.column_types.as_ref(),
+            &self.job.config.key_columns,
+        );
+
+        let target_pool = get_pool_from_output(original).await?;
+        create_table_if_missing(target_pool, &target_schema, &self.job.config.table).await?;
+        Ok(())
+    }
 }
 
 /// RDBMS Writer Job 业务逻辑
@@ -68,6 +155,8 @@ pub struct RdbmsJob {
     pub original_config: Arc<JobConfig>,
     pub config: RdbmsConfig,
     pub writer: Arc<dyn RowWriter>,
+    /// insert_method 为 load_data 时写入 MySQL 目标的连接池
+    pub local_infile: Option<LocalInfileLoader>,
 }
 
 impl RdbmsJob {
@@ -80,8 +169,15 @@ impl RdbmsJob {
             original_config,
             config,
             writer,
+            local_infile: None,
         }
     }
+
+    /// 写入任务改用 LOAD DATA 连接执行全部语句
+    pub fn with_local_infile(mut self, loader: LocalInfileLoader) -> Self {
+        self.local_infile = Some(loader);
+        self
+    }
 }
 
 /// Row Writer trait - 处理消息并写入
@@ -91,14 +187,22 @@ pub trait RowWriter: Send + Sync {
         &self,
         msg: &PipelineMessage,
         pool: &Arc<RdbmsPool>,
+        executor: &dyn DatabaseExecutor,
         config: &RdbmsConfig,
         task: &WriteTask,
     ) -> Result<usize>;
 }
 
 /// 将 pipeline 映射后的行批次转换为 RDBMS 写入行模型。
+///
+/// CDC 元数据字段（`_op`、`_table`、`_timestamp` 与 binlog 位点 `_binlog_file`、`_binlog_pos`、
+/// `_gtid`）即使出现在 column_mapping 中也不会写入。
 fn mapping_rows_to_write_rows(mapped_rows: &[MappingRow]) -> WriteRows {
-    let columns: Vec<String> = mapped_rows[0].field_names().cloned().collect();
+    let columns: Vec<String> = mapped_rows[0]
+        .field_names()
+        .filter(|name| !cdc::METADATA_FIELDS.contains(&name.as_str()))
+        .cloned()
+        .collect();
     let mut rows = Vec::with_capacity(mapped_rows.len());
     for mapped_row in mapped_rows {
         let mut values = Vec::with_capacity(columns.len());
@@ -114,7 +218,97 @@ fn mapping_rows_to_write_rows(mapped_rows: &[MappingRow]) -> WriteRows {
     WriteRows::new(columns, rows)
 }
 
+/// 根据行的 `_op` 元数据决定写入模式
+///
+/// insert / update 统一按 upsert 写入（重放时保持幂等），delete 按 delete 写入；
+/// soft_delete 任务的 delete 改为 soft_delete，scd2 任务的 insert / update 按 scd2 写入，
+/// delete 仍为 delete，由 writer 改为关闭当前版本。
+/// 非 CDC 行（没有 `_op`）使用任务配置的写入模式。
+fn row_write_mode(row: &MappingRow, default_mode: WriteMode) -> Result<WriteMode> {
+    match row.source.get(cdc::OP_FIELD).and_then(|op| op.as_str()) {
+        None => Ok(default_mode),
+        Some("insert") | Some("update") if default_mode == WriteMode::Scd2 => Ok(WriteMode::Scd2),
+        Some("insert") | Some("update") => Ok(WriteMode::Upsert),
+        Some("delete") if default_mode == WriteMode::SoftDelete => Ok(WriteMode::SoftDelete),
+        Some("delete") => Ok(WriteMode::Delete),
+        Some(other) => bail!("未知的 CDC 操作类型: {}", other),
+    }
+}
+
+/// soft_delete 任务 upsert 的行清空删除时间列，已删除的 key 重新出现时恢复
+fn clear_deleted_at(write_rows: &mut WriteRows, column: &str) {
+    if write_rows.columns.iter().any(|c| c == column) {
+        return;
+    }
+    write_rows.columns.push(column.to_string());
+    for row in &mut write_rows.values {
+        row.push(UnifiedValue::Null);
+    }
+}
+
+/// 按写入模式把批次切成连续的分段，保持批次内的操作顺序
+fn split_by_write_mode(
+    rows: &[MappingRow],
+    default_mode: WriteMode,
+) -> Result<Vec<(WriteMode, &[MappingRow])>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current: Option<WriteMode> = None;
+
+    for (i, row) in rows.iter().enumerate() {
+        let mode = row_write_mode(row, default_mode)?;
+        match current {
+            Some(prev) if prev != mode => {
+                runs.push((prev, &rows[start..i]));
+                start = i;
+                current = Some(mode);
+            }
+            Some(_) => {}
+            None => current = Some(mode),
+        }
+    }
+    if let Some(mode) = current {
+        runs.push((mode, &rows[start..]));
+    }
+    Ok(runs)
+}
+
+/// CDC 任务的行会按 `_op` 写成 upsert / delete，需要 key_columns
+///
+/// 按水位列增量同步的数据库任务不带 `_op`，按配置的写入模式写入。
+fn is_cdc_job(config: &JobConfig) -> bool {
+    (config.sync_mode == Some(SyncMode::Incremental) && !config.is_watermark_incremental())
+        || config.source.source_type == cdc::BINLOG_SOURCE_TYPE
+}
+
+/// 批次重放后结果不变：insert 会重复插入，scd2 会多出版本，其它模式按 key 覆盖或删除
+fn is_replayable(msg: &PipelineMessage, default_mode: WriteMode) -> bool {
+    match msg {
+        PipelineMessage::DataBatch(rows) => rows.iter().all(|row| {
+            row_write_mode(row, default_mode)
+                .is_ok_and(|mode| !matches!(mode, WriteMode::Insert | WriteMode::Scd2))
+        }),
+        _ => true,
+    }
+}
+
+/// 只有写入 PostgreSQL 的纯 insert 分段走 COPY；upsert / update / delete
+/// 需要冲突处理或按 key 匹配，仍使用 VALUES SQL
+fn uses_copy(pool: &RdbmsPool, config: &RdbmsConfig, mode: WriteMode) -> bool {
+    config.insert_method == InsertMethod::Copy
+        && mode == WriteMode::Insert
+        && matches!(pool, RdbmsPool::Postgres(_))
+}
+
+/// 配置 load_data 时写入 MySQL 的 insert / upsert 分段走 LOAD DATA（IGNORE / REPLACE）
+fn uses_load_data(pool: &RdbmsPool, config: &RdbmsConfig, mode: WriteMode) -> bool {
+    config.insert_method == InsertMethod::LoadData
+        && matches!(mode, WriteMode::Insert | WriteMode::Upsert)
+        && matches!(pool, RdbmsPool::Mysql(_))
+}
+
 /// PipelineMessage 的 RowWriter 实现
+#[derive(Default)]
 pub struct PipelineRowWriter;
 
 #[async_trait::async_trait]
@@ -123,6 +317,7 @@ impl RowWriter for PipelineRowWriter {
         &self,
         msg: &PipelineMessage,
         pool: &Arc<RdbmsPool>,
+        executor: &dyn DatabaseExecutor,
         config: &RdbmsConfig,
         task: &WriteTask,
     ) -> Result<usize> {
@@ -131,16 +326,42 @@ impl RowWriter for PipelineRowWriter {
                 if rows.is_empty() {
                     return Ok(0);
                 }
-                let write_rows = mapping_rows_to_write_rows(rows);
-                execute_rdbms_write(
-                    pool,
-                    &config.table,
-                    config.mode,
-                    &config.key_columns,
-                    write_rows,
-                    task.batch_size,
-                )
-                .await
+                let mut written = 0;
+                for (mode, run) in split_by_write_mode(rows, config.mode)? {
+                    let mut write_rows = mapping_rows_to_write_rows(run);
+                    if config.mode == WriteMode::SoftDelete && mode == WriteMode::Upsert {
+                        clear_deleted_at(&mut write_rows, &config.history.deleted_at);
+                    }
+                    written += if uses_copy(pool, config, mode) {
+                        execute_copy_insert(executor, config.write_table(), &write_rows).await?
+                    } else if uses_load_data(pool, config, mode) {
+                        execute_load_data(executor, config.write_table(), mode, &write_rows).await?
+                    } else if config.mode == WriteMode::Scd2 && mode == WriteMode::Delete {
+                        execute_scd2_close(
+                            pool,
+                            executor,
+                            config.write_table(),
+                            &config.key_columns,
+                            &config.history,
+                            write_rows,
+                            task.batch_size,
+                        )
+                        .await?
+                    } else {
+                        execute_rdbms_write(
+                            pool,
+                            executor,
+                            config.write_table(),
+                            mode,
+                            &config.key_columns,
+                            &config.history,
+                            write_rows,
+                            task.batch_size,
+                        )
+                        .await?
+                    };
+                }
+                Ok(written)
             }
             PipelineMessage::ReaderFinished => {
                 info!("Writer-{} 收到 Reader 完成信号", task.task_id);
@@ -153,6 +374,229 @@ impl RowWriter for PipelineRowWriter {
     }
 }
 
+impl RdbmsWriter {
+    /// 逐条处理消息并写入
+    ///
+    /// 开启事务时每 `commit_batches` 个 DataBatch 在同一事务内提交，
+    /// channel 暂时没有后续消息时也会提前提交，避免数据长时间停留在未提交事务中；
+    /// 任一批次失败则回滚整个事务。批次确认在提交之后才回传，保证 checkpoint 不超前。
+    /// 配置全局限速时每个批次写入前先取令牌。
+    ///
+    /// 配置了脏数据收集器时每个批次都在事务中写入（未开启事务则每批单独提交），
+    /// 批次失败后回滚到保存点逐行重试，失败的行记为脏数据。
+    async fn write_messages(
+        &self,
+        task: &WriteTask,
+        rx: &mut mpsc::Receiver<PipelineMessage>,
+        pool: &Arc<RdbmsPool>,
+        executor: &dyn DatabaseExecutor,
+    ) -> Result<usize> {
+        let commit_batches = self.job.config.commit_batches.max(1);
+        let transactional = task.use_transaction || task.dirty.is_some();
+        let mut uncommitted: Vec<(PipelineMessage, usize)> = Vec::new();
+        let mut written = 0;
+
+        while let Some(msg) = rx.recv().await {
+            if !matches!(msg, PipelineMessage::DataBatch(_)) {
+                self.job
+                    .writer
+                    .process_message(&msg, pool, executor, &self.job.config, task)
+                    .await?;
+                continue;
+            }
+
+            // 超速时先提交已写入的批次，避免等待期间长时间占用事务
+            if let (Some(speed), PipelineMessage::DataBatch(rows)) = (&task.speed, &msg) {
+                let wait = speed.reserve(rows.len(), rows.iter().map(row_bytes).sum());
+                if !wait.is_zero() {
+                    commit_pending(task, transactional, executor, &mut uncommitted).await?;
+                    tokio::time::sleep(wait).await;
+                }
+            }
+
+            let count = self
+                .write_with_retry(task, &msg, &uncommitted, transactional, pool, executor)
+                .await?;
+            written += count;
+            if count > 0 {
+                info!(
+                    "Writer-{} 写入中 {} 条数据（累计：{}）",
+                    task.task_id, count, written
+                );
+            }
+
+            uncommitted.push((msg, count));
+            if !task.use_transaction || uncommitted.len() >= commit_batches || rx.is_empty() {
+                commit_pending(task, transactional, executor, &mut uncommitted).await?;
+            }
+        }
+        commit_pending(task, transactional, executor, &mut uncommitted).await?;
+        Ok(written)
+    }
+
+    /// 写入一个批次，遇到可重试错误时按 `task.retry` 退避重试
+    ///
+    /// 事务中的批次重试时回滚整个事务，重新开启事务后先重放 `uncommitted` 中的批次。
+    /// 未开启事务时失败前写入的分片已自动提交，只重试可以重放的批次（见 [`is_replayable`]）。
+    async fn write_with_retry(
+        &self,
+        task: &WriteTask,
+        msg: &PipelineMessage,
+        uncommitted: &[(PipelineMessage, usize)],
+        transactional: bool,
+        pool: &Arc<RdbmsPool>,
+        executor: &dyn DatabaseExecutor,
+    ) -> Result<usize> {
+        let replayable = transactional || is_replayable(msg, self.job.config.mode);
+        let mut attempt = 0;
+        loop {
+            let result = async {
+                if transactional && (attempt > 0 || uncommitted.is_empty()) {
+                    executor.begin().await?;
+                }
+                if transactional && attempt > 0 {
+                    for (pending, _) in uncommitted {
+                        self.write_batch(task, pending, pool, executor).await?;
+                    }
+                }
+                self.write_batch(task, msg, pool, executor).await
+            }
+            .await;
+
+            match result {
+                Err(e) if !replayable && task.retry.should_retry(attempt, &e) => {
+                    warn!(
+                        "Writer-{} 未开启事务，批次可能已部分写入，重放会重复写入，不重试",
+                        task.task_id
+                    );
+                    return Err(e);
+                }
+                Err(e) if task.retry.should_retry(attempt, &e) => {
+                    attempt += 1;
+                    if transactional {
+                        if let Err(rollback_err) = executor.rollback().await {
+                            warn!("Writer-{} 事务回滚失败: {}", task.task_id, rollback_err);
+                        }
+                    }
+                    // 重放的批次会重新找出脏数据
+                    if let Some(dirty) = &task.dirty {
+                        dirty.discard_staged(task.task_id);
+                    }
+                    let context = format!("Writer-{}", task.task_id);
+                    task.retry.wait(attempt, &e, &context).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn write_batch(
+        &self,
+        task: &WriteTask,
+        msg: &PipelineMessage,
+        pool: &Arc<RdbmsPool>,
+        executor: &dyn DatabaseExecutor,
+    ) -> Result<usize> {
+        match (&task.dirty, msg) {
+            (Some(dirty), PipelineMessage::DataBatch(rows)) => {
+                self.write_tolerant(msg, rows, dirty, task, pool, executor)
+                    .await
+            }
+            _ => {
+                self.job
+                    .writer
+                    .process_message(msg, pool, executor, &self.job.config, task)
+                    .await
+            }
+        }
+    }
+
+    /// 在保存点内写入批次；失败时回滚到保存点，逐行重试找出脏数据
+    ///
+    /// 可重试的错误（死锁、连接断开等）不是数据问题，不论是否开启重试都直接返回，不记为脏数据。
+    async fn write_tolerant(
+        &self,
+        msg: &PipelineMessage,
+        rows: &[MappingRow],
+        dirty: &DirtyRecordCollector,
+        task: &WriteTask,
+        pool: &Arc<RdbmsPool>,
+        executor: &dyn DatabaseExecutor,
+    ) -> Result<usize> {
+        let writer = &self.job.writer;
+        let config = &self.job.config;
+
+        executor.savepoint(BATCH_SAVEPOINT).await?;
+        match writer
+            .process_message(msg, pool, executor, config, task)
+            .await
+        {
+            Ok(count) => {
+                executor.release_savepoint(BATCH_SAVEPOINT).await?;
+                return Ok(count);
+            }
+            Err(e) if is_retryable(&e) => return Err(e),
+            Err(e) => {
+                warn!(
+                    "Writer-{} 批次写入失败，逐行重试 {} 条: {}",
+                    task.task_id,
+                    rows.len(),
+                    e
+                );
+                executor.rollback_to_savepoint(BATCH_SAVEPOINT).await?;
+            }
+        }
+
+        let mut written = 0;
+        for row in rows {
+            let single = PipelineMessage::DataBatch(vec![row.clone()]);
+            executor.savepoint(ROW_SAVEPOINT).await?;
+            match writer
+                .process_message(&single, pool, executor, config, task)
+                .await
+            {
+                Ok(count) => written += count,
+                Err(e) if is_retryable(&e) => return Err(e),
+                Err(e) => {
+                    executor.rollback_to_savepoint(ROW_SAVEPOINT).await?;
+                    dirty.stage(task.task_id, &row.source, e)?;
+                }
+            }
+            executor.release_savepoint(ROW_SAVEPOINT).await?;
+        }
+        executor.release_savepoint(BATCH_SAVEPOINT).await?;
+        Ok(written)
+    }
+}
+
+/// 脏数据隔离使用的保存点
+const BATCH_SAVEPOINT: &str = "relus_batch";
+const ROW_SAVEPOINT: &str = "relus_row";
+
+/// 提交事务（如果开启），记录事务内暂存的脏数据，并回传事务内各批次的确认
+async fn commit_pending(
+    task: &WriteTask,
+    transactional: bool,
+    executor: &dyn DatabaseExecutor,
+    uncommitted: &mut Vec<(PipelineMessage, usize)>,
+) -> Result<()> {
+    if uncommitted.is_empty() {
+        return Ok(());
+    }
+    if transactional {
+        executor.commit().await?;
+    }
+    if let Some(dirty) = &task.dirty {
+        dirty.flush_staged(task.task_id).await?;
+    }
+    for (_, count) in uncommitted.drain(..) {
+        if let Some(ack) = &task.batch_ack {
+            let _ = ack.send(count);
+        }
+    }
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl DataWriterTask for RdbmsWriter {
     async fn write_data(
@@ -162,33 +606,653 @@ impl DataWriterTask for RdbmsWriter {
     ) -> Result<usize> {
         let pool = get_pool_from_output(&self.job.original_config).await?;
 
-        if self.job.config.mode == WriteMode::Upsert {
-            validate_upsert_keys(&pool, &self.job.config.table, &self.job.config.key_columns)
-                .await?;
+        // scd2 表同一 key 保存多个版本，key 上不会有唯一索引
+        let mode = self.job.config.mode;
+        if (mode == WriteMode::Upsert || is_cdc_job(&self.job.original_config))
+            && mode != WriteMode::Scd2
+        {
+            validate_upsert_keys(
+                &pool,
+                self.job.config.write_table(),
+                &self.job.config.key_columns,
+            )
+            .await?;
         }
 
-        let mut written = 0;
+        // LOAD DATA 需要 mysql_async 连接，任务的其它语句也走这条连接，共享事务和保存点
+        let executor = match &self.job.local_infile {
+            Some(loader) if matches!(pool.as_ref(), RdbmsPool::Mysql(_)) => loader.executor(),
+            _ => pool.executor(),
+        };
+        match self
+            .write_messages(&task, &mut rx, &pool, executor.as_ref())
+            .await
+        {
+            Ok(written) => {
+                info!("Writer-{} 完成，共写入 {} 条数据", task.task_id, written);
+                Ok(written)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = executor.rollback().await {
+                    warn!("Writer-{} 事务回滚失败: {}", task.task_id, rollback_err);
+                }
+                if let Some(dirty) = &task.dirty {
+                    dirty.discard_staged(task.task_id);
+                }
+                bail!("Writer-{} 写入失败: {}", task.task_id, e)
+            }
+        }
+    }
+}
 
-        while let Some(msg) = rx.recv().await {
-            match self
-                .job
-                .writer
-                .process_message(&msg, &pool, &self.job.config, &task)
-                .await
-            {
-                Ok(count) => {
-                    written += count;
-                    if count > 0 {
-                        info!(
-                            "Writer-{} 写入中 {} 条数据（累计：{}）",
-                            task.task_id, count, written
-                        );
-                    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use relus_common::job_config::{ErrorLimitConfig, RetryConfig};
+    use relus_connector_rdbms::retry::RetryPolicy;
+
+    fn cdc_row(op: &str, id: i64) -> MappingRow {
+        let mut row = MappingRow::simple().with_source(serde_json::json!({
+            "_op": op,
+            "_table": "test.users",
+            "_timestamp": 1710000000,
+            "id": id,
+        }));
+        row.insert_simple("id", UnifiedValue::Int(id), "int");
+        row.insert_simple("_op", UnifiedValue::String(op.to_string()), "string");
+        row
+    }
+
+    #[test]
+    fn test_split_by_write_mode_keeps_order() -> Result<()> {
+        let rows = vec![
+            cdc_row("insert", 1),
+            cdc_row("update", 1),
+            cdc_row("delete", 1),
+            cdc_row("insert", 2),
+        ];
+
+        let runs = split_by_write_mode(&rows, WriteMode::Insert)?;
+        let modes: Vec<(WriteMode, usize)> =
+            runs.iter().map(|(mode, run)| (*mode, run.len())).collect();
+        assert_eq!(
+            modes,
+            vec![
+                (WriteMode::Upsert, 2),
+                (WriteMode::Delete, 1),
+                (WriteMode::Upsert, 1)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_modes_remap_cdc_ops() -> Result<()> {
+        let rows = vec![
+            cdc_row("insert", 1),
+            cdc_row("delete", 1),
+            cdc_row("update", 2),
+        ];
+        let modes = |mode| -> Result<Vec<WriteMode>> {
+            Ok(split_by_write_mode(&rows, mode)?
+                .into_iter()
+                .map(|(mode, _)| mode)
+                .collect())
+        };
+        assert_eq!(
+            modes(WriteMode::SoftDelete)?,
+            vec![WriteMode::Upsert, WriteMode::SoftDelete, WriteMode::Upsert]
+        );
+        assert_eq!(
+            modes(WriteMode::Scd2)?,
+            vec![WriteMode::Scd2, WriteMode::Delete, WriteMode::Scd2]
+        );
+
+        let mut write_rows = mapping_rows_to_write_rows(&rows[..1]);
+        clear_deleted_at(&mut write_rows, "deleted_at");
+        assert_eq!(write_rows.columns, vec!["id", "deleted_at"]);
+        assert_eq!(
+            write_rows.values,
+            vec![vec![UnifiedValue::Int(1), UnifiedValue::Null]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_binlog_metadata_is_not_written() {
+        let mut row = cdc_row("insert", 1);
+        row.insert_simple(
+            "_binlog_file",
+            UnifiedValue::String("mysql-bin.000003".to_string()),
+            "string",
+        );
+        row.insert_simple("_binlog_pos", UnifiedValue::Int(194), "int");
+        row.insert_simple(
+            "_gtid",
+            UnifiedValue::String("uuid:1-5".to_string()),
+            "string",
+        );
+        let write_rows = mapping_rows_to_write_rows(&[row]);
+        assert_eq!(write_rows.columns, vec!["id"]);
+    }
+
+    #[tokio::test]
+    async fn test_same_key_insert_and_update_upsert_in_separate_statements() -> Result<()> {
+        let (_, task, pool) = transactional_writer(1)?;
+        let mut update = cdc_row("update", 1);
+        update.insert_simple("name", UnifiedValue::String("b".to_string()), "string");
+        let mut insert = cdc_row("insert", 1);
+        insert.insert_simple("name", UnifiedValue::String("a".to_string()), "string");
+        let mut other = cdc_row("insert", 2);
+        other.insert_simple("name", UnifiedValue::String("c".to_string()), "string");
+        let msg = PipelineMessage::DataBatch(vec![insert, update, other]);
+
+        let config = RdbmsConfig {
+            table: "users".to_string(),
+            key_columns: vec!["id".to_string()],
+            mode: WriteMode::Insert,
+            use_transaction: false,
+            batch_size: 100,
+            commit_batches: 1,
+            insert_method: InsertMethod::Values,
+            create_table_if_missing: false,
+            staging_table: None,
+            history: HistoryColumns::default(),
+        };
+        let executor = RecordingExecutor::default();
+        let written = PipelineRowWriter::default()
+            .process_message(&msg, &pool, &executor, &config, &task)
+            .await?;
+
+        // 同一条 ON CONFLICT DO UPDATE 中 key 重复会被 PostgreSQL 拒绝，按顺序拆成两条
+        assert_eq!(written, 3);
+        let statements = executor.statements();
+        assert_eq!(statements.len(), 2);
+        assert!(statements.iter().all(|sql| sql.contains("ON CONFLICT")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_by_write_mode_without_op_uses_default() -> Result<()> {
+        let mut row = MappingRow::simple().with_source(serde_json::json!({"id": 1}));
+        row.insert_simple("id", UnifiedValue::Int(1), "int");
+
+        let rows = [row];
+        let runs = split_by_write_mode(&rows, WriteMode::Insert)?;
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].0, WriteMode::Insert);
+        Ok(())
+    }
+
+    /// 记录事务调用顺序与执行 SQL 的 executor
+    #[derive(Default)]
+    struct RecordingExecutor {
+        calls: std::sync::Mutex<Vec<&'static str>>,
+        statements: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl RecordingExecutor {
+        fn record(&self, call: &'static str) {
+            if let Ok(mut calls) = self.calls.lock() {
+                calls.push(call);
+            }
+        }
+
+        fn calls(&self) -> Vec<&'static str> {
+            self.calls.lock().map(|c| c.clone()).unwrap_or_default()
+        }
+
+        fn statements(&self) -> Vec<String> {
+            self.statements
+                .lock()
+                .map(|s| s.clone())
+                .unwrap_or_default()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DatabaseExecutor for RecordingExecutor {
+        async fn fetch_string_pair(&self, _: &str) -> Result<(Option<String>, Option<String>)> {
+            Ok((None, None))
+        }
+        async fn fetch_optional_string(&self, _: &str) -> Result<Option<String>> {
+            Ok(None)
+        }
+        async fn fetch_column_pair(
+            &self,
+            _: &str,
+        ) -> Result<(
+            Option<relus_connector_rdbms::pool::ColumnValue>,
+            Option<relus_connector_rdbms::pool::ColumnValue>,
+        )> {
+            Ok((None, None))
+        }
+        async fn execute(&self, _: &str) -> Result<u64> {
+            Ok(0)
+        }
+        async fn execute_with_params(&self, sql: &str, _: &[UnifiedValue]) -> Result<u64> {
+            if let Ok(mut statements) = self.statements.lock() {
+                statements.push(sql.to_string());
+            }
+            Ok(0)
+        }
+        async fn begin(&self) -> Result<()> {
+            self.record("begin");
+            Ok(())
+        }
+        async fn commit(&self) -> Result<()> {
+            self.record("commit");
+            Ok(())
+        }
+        async fn rollback(&self) -> Result<()> {
+            self.record("rollback");
+            Ok(())
+        }
+        async fn savepoint(&self, _: &str) -> Result<()> {
+            self.record("savepoint");
+            Ok(())
+        }
+        async fn rollback_to_savepoint(&self, _: &str) -> Result<()> {
+            self.record("rollback_to");
+            Ok(())
+        }
+        async fn release_savepoint(&self, _: &str) -> Result<()> {
+            self.record("release");
+            Ok(())
+        }
+    }
+
+    /// 写入批次行数，遇到 id 为负数的行时失败
+    struct CountingRowWriter;
+
+    #[async_trait::async_trait]
+    impl RowWriter for CountingRowWriter {
+        async fn process_message(
+            &self,
+            msg: &PipelineMessage,
+            _: &Arc<RdbmsPool>,
+            executor: &dyn DatabaseExecutor,
+            _: &RdbmsConfig,
+            _: &WriteTask,
+        ) -> Result<usize> {
+            let PipelineMessage::DataBatch(rows) = msg else {
+                return Ok(0);
+            };
+            for row in rows {
+                if matches!(row.get_value("id"), Some(UnifiedValue::Int(id)) if *id < 0) {
+                    bail!("写入失败");
                 }
-                Err(e) => bail!("Writer-{} 写入失败: {}", task.task_id, e),
+                executor.execute("insert").await?;
             }
+            Ok(rows.len())
         }
-        info!("Writer-{} 完成，共写入 {} 条数据", task.task_id, written);
-        Ok(written)
+    }
+
+    /// 第 `fail_on` 次调用时返回一次可重试的连接池超时错误
+    struct FlakyRowWriter {
+        calls: std::sync::atomic::AtomicUsize,
+        fail_on: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl RowWriter for FlakyRowWriter {
+        async fn process_message(
+            &self,
+            msg: &PipelineMessage,
+            pool: &Arc<RdbmsPool>,
+            executor: &dyn DatabaseExecutor,
+            config: &RdbmsConfig,
+            task: &WriteTask,
+        ) -> Result<usize> {
+            let call = self
+                .calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                + 1;
+            if call == self.fail_on {
+                return Err(sqlx::Error::PoolTimedOut.into());
+            }
+            CountingRowWriter
+                .process_message(msg, pool, executor, config, task)
+                .await
+        }
+    }
+
+    fn batch(ids: &[i64]) -> PipelineMessage {
+        PipelineMessage::DataBatch(ids.iter().map(|id| cdc_row("insert", *id)).collect())
+    }
+
+    fn transactional_writer(
+        commit_batches: usize,
+    ) -> Result<(RdbmsWriter, WriteTask, Arc<RdbmsPool>)> {
+        let job_config: Arc<JobConfig> = Arc::new(serde_json::from_value(serde_json::json!({
+            "source": {"name": "source", "type": "database", "config": {}},
+            "target": {"name": "target", "type": "database", "config": {}},
+        }))?);
+        let config = RdbmsConfig {
+            table: "users".to_string(),
+            key_columns: vec!["id".to_string()],
+            mode: WriteMode::Insert,
+            use_transaction: true,
+            batch_size: 100,
+            commit_batches,
+            insert_method: InsertMethod::Values,
+            create_table_if_missing: false,
+            staging_table: None,
+            history: HistoryColumns::default(),
+        };
+        let writer =
+            RdbmsWriter::init(Arc::clone(&job_config), config, Arc::new(CountingRowWriter))?;
+        let task = WriteTask {
+            task_id: 0,
+            config: job_config,
+            mode: WriteMode::Insert,
+            use_transaction: true,
+            batch_size: 100,
+            batch_ack: None,
+            dirty: None,
+            retry: RetryPolicy::disabled(),
+            speed: None,
+        };
+        let pool =
+            sqlx::postgres::PgPoolOptions::new().connect_lazy("postgres://localhost/relus")?;
+        Ok((writer, task, Arc::new(RdbmsPool::Postgres(pool))))
+    }
+
+    #[tokio::test]
+    async fn test_transaction_groups_batches_and_acks_after_commit() -> Result<()> {
+        let (writer, mut task, pool) = transactional_writer(2)?;
+        let (ack_tx, mut ack_rx) = mpsc::unbounded_channel();
+        task.batch_ack = Some(ack_tx);
+
+        let (tx, mut rx) = mpsc::channel(8);
+        for msg in [
+            batch(&[1, 2]),
+            batch(&[3]),
+            batch(&[4]),
+            PipelineMessage::ReaderFinished,
+        ] {
+            tx.send(msg).await?;
+        }
+        drop(tx);
+
+        let executor = RecordingExecutor::default();
+        let written = writer
+            .write_messages(&task, &mut rx, &pool, &executor)
+            .await?;
+
+        assert_eq!(written, 4);
+        assert_eq!(executor.calls(), vec!["begin", "commit", "begin", "commit"]);
+        let mut acks = Vec::new();
+        while let Ok(count) = ack_rx.try_recv() {
+            acks.push(count);
+        }
+        assert_eq!(acks, vec![2, 1, 1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_failure_is_not_acked() -> Result<()> {
+        let (writer, mut task, pool) = transactional_writer(1)?;
+        let (ack_tx, mut ack_rx) = mpsc::unbounded_channel();
+        task.batch_ack = Some(ack_tx);
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(batch(&[1])).await?;
+        tx.send(batch(&[2, -1])).await?;
+        drop(tx);
+
+        let executor = RecordingExecutor::default();
+        let result = writer
+            .write_messages(&task, &mut rx, &pool, &executor)
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(executor.calls(), vec!["begin", "commit", "begin"]);
+        assert_eq!(ack_rx.try_recv().ok(), Some(1));
+        assert!(ack_rx.try_recv().is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retryable_error_replays_transaction() -> Result<()> {
+        let (writer, mut task, pool) = transactional_writer(2)?;
+        let writer = RdbmsWriter::init(
+            Arc::clone(&writer.job.original_config),
+            writer.job.config.clone(),
+            Arc::new(FlakyRowWriter {
+                calls: Default::default(),
+                fail_on: 2,
+            }),
+        )?;
+        task.retry = RetryPolicy::new(RetryConfig {
+            max_retries: 1,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 1,
+        });
+        let (ack_tx, mut ack_rx) = mpsc::unbounded_channel();
+        task.batch_ack = Some(ack_tx);
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(batch(&[1])).await?;
+        tx.send(batch(&[2, 3])).await?;
+        drop(tx);
+
+        let executor = RecordingExecutor::default();
+        let written = writer
+            .write_messages(&task, &mut rx, &pool, &executor)
+            .await?;
+
+        assert_eq!(written, 3);
+        assert_eq!(task.retry.retries(), 1);
+        assert_eq!(
+            executor.calls(),
+            vec!["begin", "rollback", "begin", "commit"]
+        );
+        assert_eq!(ack_rx.try_recv().ok(), Some(1));
+        assert_eq!(ack_rx.try_recv().ok(), Some(2));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_non_transactional_retry_only_replays_idempotent_batches() -> Result<()> {
+        let (writer, mut task, pool) = transactional_writer(1)?;
+        let mut config = writer.job.config.clone();
+        config.use_transaction = false;
+        task.use_transaction = false;
+        task.retry = RetryPolicy::new(RetryConfig {
+            max_retries: 1,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 1,
+        });
+        let flaky = || {
+            RdbmsWriter::init(
+                Arc::clone(&writer.job.original_config),
+                config.clone(),
+                Arc::new(FlakyRowWriter {
+                    calls: Default::default(),
+                    fail_on: 1,
+                }),
+            )
+        };
+
+        // 普通 insert 行：已写入的分片无法撤回，不重试
+        let mut row = MappingRow::simple();
+        row.insert_simple("id", UnifiedValue::Int(1), "int");
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(PipelineMessage::DataBatch(vec![row])).await?;
+        drop(tx);
+        let result = flaky()?
+            .write_messages(&task, &mut rx, &pool, &RecordingExecutor::default())
+            .await;
+        assert!(result.is_err());
+        assert_eq!(task.retry.retries(), 0);
+
+        // CDC 事件按 upsert 写入，可以重放
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(batch(&[1, 2])).await?;
+        drop(tx);
+        let written = flaky()?
+            .write_messages(&task, &mut rx, &pool, &RecordingExecutor::default())
+            .await?;
+        assert_eq!(written, 2);
+        assert_eq!(task.retry.retries(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replayed_transaction_records_dirty_rows_once() -> Result<()> {
+        let (writer, mut task, pool) = transactional_writer(2)?;
+        // 第 4 次调用（第二个批次）可重试失败，事务回滚后重放第一个批次
+        let writer = RdbmsWriter::init(
+            Arc::clone(&writer.job.original_config),
+            writer.job.config.clone(),
+            Arc::new(FlakyRowWriter {
+                calls: Default::default(),
+                fail_on: 4,
+            }),
+        )?;
+        task.retry = RetryPolicy::new(RetryConfig {
+            max_retries: 1,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 1,
+        });
+        let dirty =
+            DirtyRecordCollector::open(&ErrorLimitConfig::default(), &task.config, "users").await?;
+        task.dirty = Some(Arc::clone(&dirty));
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(batch(&[1, -1])).await?;
+        tx.send(batch(&[2])).await?;
+        drop(tx);
+
+        let executor = RecordingExecutor::default();
+        let written = writer
+            .write_messages(&task, &mut rx, &pool, &executor)
+            .await?;
+
+        assert_eq!(written, 2);
+        assert_eq!(task.retry.retries(), 1);
+        assert_eq!(dirty.dirty_count(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dirty_rows_are_isolated_row_by_row() -> Result<()> {
+        let (writer, mut task, pool) = transactional_writer(1)?;
+        task.use_transaction = false;
+        let limits = ErrorLimitConfig {
+            record: Some(1),
+            ..Default::default()
+        };
+        let dirty = DirtyRecordCollector::open(&limits, &task.config, "users").await?;
+        task.dirty = Some(Arc::clone(&dirty));
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(batch(&[1, -1, 2])).await?;
+        tx.send(batch(&[3])).await?;
+        drop(tx);
+
+        let executor = RecordingExecutor::default();
+        let written = writer
+            .write_messages(&task, &mut rx, &pool, &executor)
+            .await?;
+
+        assert_eq!(written, 3);
+        assert_eq!(dirty.dirty_count(), 1);
+        assert_eq!(
+            executor.calls(),
+            vec![
+                "begin",
+                "savepoint",
+                "rollback_to",
+                "savepoint",
+                "release",
+                "savepoint",
+                "rollback_to",
+                "release",
+                "savepoint",
+                "release",
+                "release",
+                "commit",
+                "begin",
+                "savepoint",
+                "release",
+                "commit",
+            ]
+        );
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(batch(&[-2])).await?;
+        drop(tx);
+        let result = writer
+            .write_messages(&task, &mut rx, &pool, &executor)
+            .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_not_dirty_rows_without_retry() -> Result<()> {
+        let (writer, mut task, pool) = transactional_writer(1)?;
+        let writer = RdbmsWriter::init(
+            Arc::clone(&writer.job.original_config),
+            writer.job.config.clone(),
+            Arc::new(FlakyRowWriter {
+                calls: Default::default(),
+                fail_on: 1,
+            }),
+        )?;
+        let dirty =
+            DirtyRecordCollector::open(&ErrorLimitConfig::default(), &task.config, "users").await?;
+        task.dirty = Some(Arc::clone(&dirty));
+
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(batch(&[1])).await?;
+        drop(tx);
+        let result = writer
+            .write_messages(&task, &mut rx, &pool, &RecordingExecutor::default())
+            .await;
+
+        // 默认不重试，连接类错误也不能当作数据错误逐行隔离
+        assert!(result.is_err());
+        assert_eq!(dirty.dirty_count(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_copy_only_for_plain_inserts() -> Result<()> {
+        let (writer, _, pool) = transactional_writer(1)?;
+        let mut config = writer.job.config.clone();
+        assert!(!uses_copy(&pool, &config, WriteMode::Insert));
+
+        config.insert_method = InsertMethod::Copy;
+        assert!(uses_copy(&pool, &config, WriteMode::Insert));
+        assert!(!uses_copy(&pool, &config, WriteMode::Upsert));
+        assert!(!uses_copy(&pool, &config, WriteMode::Delete));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_data_only_for_mysql_inserts_and_upserts() -> Result<()> {
+        let (writer, _, pg_pool) = transactional_writer(1)?;
+        let mut config = writer.job.config.clone();
+        config.insert_method = InsertMethod::LoadData;
+        let pool = RdbmsPool::Mysql(
+            sqlx::mysql::MySqlPoolOptions::new().connect_lazy("mysql://localhost/relus")?,
+        );
+        assert!(uses_load_data(&pool, &config, WriteMode::Insert));
+        assert!(uses_load_data(&pool, &config, WriteMode::Upsert));
+        assert!(!uses_load_data(&pool, &config, WriteMode::Update));
+        assert!(!uses_load_data(&pool, &config, WriteMode::Delete));
+        assert!(!uses_load_data(&pg_pool, &config, WriteMode::Insert));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_rows_skip_cdc_metadata() {
+        let rows = vec![cdc_row("insert", 1)];
+        let write_rows = mapping_rows_to_write_rows(&rows);
+        assert_eq!(write_rows.columns, vec!["id".to_string()]);
+        assert_eq!(write_rows.values.len(), 1);
     }
 }
@@ -2,12 +2,12 @@
 
 use std::sync::Arc;
 
-use relus_common::data_source_config::DbConfig;
 use relus_common::job_config::WriteMode;
 use relus_common::JobConfig;
 
 use crate::{SplitWriterResult, WriteTask};
 use relus_common::constant::pipeline::DEFAULT_BATCH_SIZE;
+use relus_connector_rdbms::retry::RetryPolicy;
 
 /// 切分 Writer 任务
 pub fn do_split(original_config: &Arc<JobConfig>, advice_number: usize) -> SplitWriterResult {
@@ -15,8 +15,13 @@ pub fn do_split(original_config: &Arc<JobConfig>, advice_number: usize) -> Split
 
     let batch_size = original_config.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
 
-    let db_config = DbConfig::default();
-    let use_transaction = db_config.use_transaction.unwrap_or(false);
+    // 目标连接未配置 use_transaction 时由 core 按 pipeline.use_transaction 覆盖
+    let use_transaction = original_config
+        .target
+        .parse_database_config()
+        .ok()
+        .and_then(|db| db.use_transaction)
+        .unwrap_or(false);
 
     //这里直接按照advice number进行切分就好，
     let tasks: Vec<WriteTask> = (0..advice_number)
@@ -26,6 +31,10 @@ pub fn do_split(original_config: &Arc<JobConfig>, advice_number: usize) -> Split
             mode,
             use_transaction,
             batch_size,
+            batch_ack: None,
+            dirty: None,
+            retry: RetryPolicy::disabled(),
+            speed: None,
         })
         .collect();
 